@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+use aoc_2023::report::{csv_field, json_string, millis, Format, Status};
+use aoc_2023::runner;
+use aoc_2023::runner::{DayError, Settings};
+use aoc_2023::solution::{Part, Solver};
+
+#[derive(PartialEq, Debug)]
+pub struct Stats {
+    min: Duration,
+    median: Duration,
+    mean: Duration,
+    max: Duration
+}
+
+impl Stats {
+    fn from(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        // with an even number of runs, the median is halfway between the two middle entries
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+
+        Stats {
+            min: *sorted.first().unwrap(),
+            median,
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            max: *sorted.last().unwrap()
+        }
+    }
+
+    // In the order they're written out
+    fn durations(&self) -> [Duration; 4] {
+        [self.min, self.median, self.mean, self.max]
+    }
+}
+
+// Times each phase separately, so load and parse costs don't get hidden inside the parts.
+#[derive(Default)]
+struct Samples {
+    load: Vec<Duration>,
+    parse: Vec<Duration>,
+    parts: BTreeMap<Part, Vec<Duration>>
+}
+
+// One line of the results: a phase of a day and how long it took over the runs, and for the parts, how the answer
+// compared to the expected one. A day that couldn't be run has no timings, only the error.
+#[derive(PartialEq, Debug)]
+struct Row {
+    day: u8,
+    phase: String,
+    runs: usize,
+    stats: Option<Stats>,
+    status: Option<Status>
+}
+
+impl Row {
+    fn new(day: u8, phase: String, samples: &[Duration], status: Option<Status>) -> Row {
+        Row { day, phase, runs: samples.len(), stats: Some(Stats::from(samples)), status }
+    }
+}
+
+// Runs the day through the runner, so a day that panics or runs past the timeout is reported like it is without
+// --bench. A part that times out isn't run again, as it would only time out again.
+fn bench_day(solver: &dyn Solver, settings: &Settings, runs: u32) -> Result<Vec<Row>, DayError> {
+    let mut samples = Samples::default();
+    let mut statuses: BTreeMap<Part, Status> = BTreeMap::new();
+    for _ in 0..runs {
+        let run = runner::run_day_timed(solver, settings)?;
+        samples.load.push(run.load);
+        samples.parse.push(run.parse);
+        let timed_out = run.parts.iter().any(|result| result.status == Status::Timeout);
+        for result in run.parts {
+            samples.parts.entry(result.part).or_default().push(result.duration);
+            statuses.insert(result.part, result.status);
+        }
+        if timed_out {
+            break;
+        }
+    }
+
+    let day = solver.day();
+    let mut rows = vec![Row::new(day, String::from("Load"), &samples.load, None), Row::new(day, String::from("Parse"), &samples.parse, None)];
+    for (part, part_samples) in &samples.parts {
+        rows.push(Row::new(day, format!("Part {}", part), part_samples, statuses.remove(part)));
+    }
+    Ok(rows)
+}
+
+// Returns false if any of the days could not be run, got the wrong answer or timed out.
+pub fn run(days_to_run: &[u8], days: &BTreeMap<u8, &'static dyn Solver>, settings: &Settings, runs: u32, output: Format) -> bool {
+    let mut ok = true;
+    let mut rows: Vec<Row> = Vec::new();
+    for day in days_to_run {
+        let mut day_rows = match bench_day(*days.get(day).unwrap(), settings, runs) {
+            Ok(day_rows) => day_rows,
+            Err(error) => {
+                match &error {
+                    DayError::Parse(e) => eprintln!("{}", e),
+                    e => eprintln!("Day {}: {}", day, e)
+                }
+                ok = false;
+                runner::error_results(*day, settings.parts, &error).into_iter().map(|result| {
+                    Row { day: *day, phase: format!("Part {}", result.part), runs: 0, stats: None, status: Some(result.status) }
+                }).collect()
+            }
+        };
+        ok &= !day_rows.iter().any(|row| matches!(row.status, Some(Status::Fail(_) | Status::Timeout)));
+        if output.is_streamed() {
+            write(output, &day_rows, &mut io::stdout()).unwrap();
+        } else {
+            rows.append(&mut day_rows);
+        }
+    }
+
+    if !output.is_streamed() {
+        write(output, &rows, &mut io::stdout()).unwrap();
+    }
+    ok
+}
+
+fn write(format: Format, rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Text => write_text(rows, out),
+        Format::Json => write_json(rows, out),
+        Format::Csv => write_csv(rows, out),
+        Format::Markdown => write_markdown(rows, out)
+    }
+}
+
+fn write_text(rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    for (i, row) in rows.iter().enumerate() {
+        if i == 0 || rows[i - 1].day != row.day {
+            let runs = rows.iter().filter(|other| other.day == row.day).map(|other| other.runs).max().unwrap_or(0);
+            writeln!(out, "Day {} ({} runs)", row.day, runs)?;
+            writeln!(out, "---")?;
+            writeln!(out, "{:<8} {:>12} {:>12} {:>12} {:>12}", "Phase", "Min", "Median", "Mean", "Max")?;
+        }
+        let [min, median, mean, max] = row.stats.as_ref()
+            .map_or_else(|| [(); 4].map(|_| String::from("-")), |stats| stats.durations().map(|duration| format!("{:.2?}", duration)));
+        write!(out, "{:<8} {:>12} {:>12} {:>12} {:>12}", row.phase, min, median, mean, max)?;
+        if let Some(status) = row.status.as_ref().filter(|status| **status != Status::Solved) {
+            write!(out, "  {}", status)?;
+        }
+        writeln!(out)?;
+        if i + 1 == rows.len() || rows[i + 1].day != row.day {
+            writeln!(out, "---")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_json(rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, row) in rows.iter().enumerate() {
+        writeln!(
+            out,
+            "  {{\"day\": {}, \"phase\": {}, \"runs\": {}{}{}}}{}",
+            row.day,
+            json_string(row.phase.as_str()),
+            row.runs,
+            row.stats.as_ref().map_or(String::new(), |stats| {
+                let [min, median, mean, max] = stats.durations().map(|duration| millis(&duration));
+                format!(", \"min_ms\": {}, \"median_ms\": {}, \"mean_ms\": {}, \"max_ms\": {}", min, median, mean, max)
+            }),
+            row.status.as_ref().map_or(String::new(), |status| format!(", \"status\": {}", json_string(status.name()))),
+            if i + 1 == rows.len() { "" } else { "," }
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+fn write_csv(rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "day,phase,runs,min_ms,median_ms,mean_ms,max_ms,status")?;
+    for row in rows {
+        let [min, median, mean, max] = row.stats.as_ref()
+            .map_or_else(|| [(); 4].map(|_| String::new()), |stats| stats.durations().map(|duration| millis(&duration)));
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            row.day,
+            csv_field(row.phase.as_str()),
+            row.runs,
+            min,
+            median,
+            mean,
+            max,
+            row.status.as_ref().map_or("", Status::name)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_markdown(rows: &[Row], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "| Day | Phase | Runs | Min | Median | Mean | Max | Status |")?;
+    writeln!(out, "|----:|-------|-----:|----:|-------:|-----:|----:|--------|")?;
+    for row in rows {
+        let [min, median, mean, max] = row.stats.as_ref()
+            .map_or_else(|| [(); 4].map(|_| String::new()), |stats| stats.durations().map(|duration| format!("{:.2?}", duration)));
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {} | {} |",
+            row.day,
+            row.phase,
+            row.runs,
+            min,
+            median,
+            mean,
+            max,
+            row.status.as_ref().map_or("", Status::name)
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+    use indoc::indoc;
+    use rstest::rstest;
+    use aoc_2023::check::Answers;
+    use aoc_2023::common::InputSource;
+    use aoc_2023::report::{Format, Status};
+    use aoc_2023::runner::{DayError, Settings};
+    use aoc_2023::solution::{registry, Part};
+    use aoc_2023::testing::TempDir;
+    use crate::bench::{bench_day, write, Row, Stats};
+
+    #[rstest]
+    #[case(vec![5], Stats { min: Duration::from_millis(5), median: Duration::from_millis(5), mean: Duration::from_millis(5), max: Duration::from_millis(5) })]
+    #[case(vec![9, 1, 5], Stats { min: Duration::from_millis(1), median: Duration::from_millis(5), mean: Duration::from_millis(5), max: Duration::from_millis(9) })]
+    #[case(vec![4, 10, 2, 8], Stats { min: Duration::from_millis(2), median: Duration::from_millis(6), mean: Duration::from_millis(6), max: Duration::from_millis(10) })]
+    #[case(vec![1, 1, 1, 13], Stats { min: Duration::from_millis(1), median: Duration::from_millis(1), mean: Duration::from_millis(4), max: Duration::from_millis(13) })]
+    fn test_stats(#[case] millis: Vec<u64>, #[case] expected: Stats) {
+        let samples: Vec<Duration> = millis.into_iter().map(Duration::from_millis).collect();
+        assert_eq!(Stats::from(&samples), expected);
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let dir = TempDir::new("bench");
+        fs::write(dir.join("day15.txt"), "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7").unwrap();
+        let source = InputSource::DataDir(dir.to_path_buf());
+        let mut answers = Answers::default();
+        answers.add(15, Part::One, "1320");
+        answers.add(15, Part::Two, "146");
+        let settings = Settings { source: &source, parts: &[Part::One, Part::Two], answers: Some(&answers), memory: false, timeout: None };
+
+        let rows = bench_day(*registry().get(&15).unwrap(), &settings, 3).unwrap();
+        let summary: Vec<(&str, usize, Option<Status>)> = rows.iter().map(|row| (row.phase.as_str(), row.runs, row.status.clone())).collect();
+        assert_eq!(summary, vec![
+            ("Load", 3, None),
+            ("Parse", 3, None),
+            ("Part 1", 3, Some(Status::Pass)),
+            ("Part 2", 3, Some(Status::Fail(String::from("146"))))
+        ]);
+    }
+
+    #[test]
+    fn test_bench_day_without_input() {
+        let dir = TempDir::new("bench-missing");
+        let source = InputSource::DataDir(dir.to_path_buf());
+        let settings = Settings { source: &source, parts: &[Part::One], answers: None, memory: false, timeout: None };
+        assert!(matches!(bench_day(*registry().get(&15).unwrap(), &settings, 3), Err(DayError::Input(_))));
+    }
+
+    fn rows() -> Vec<Row> {
+        let stats = |millis: [u64; 4]| {
+            let [min, median, mean, max] = millis.map(Duration::from_millis);
+            Some(Stats { min, median, mean, max })
+        };
+        vec![
+            Row { day: 2, phase: String::from("Load"), runs: 5, stats: stats([1, 2, 2, 3]), status: None },
+            Row { day: 2, phase: String::from("Part 1"), runs: 5, stats: stats([4, 5, 6, 9]), status: Some(Status::Solved) },
+            Row { day: 2, phase: String::from("Part 2"), runs: 5, stats: stats([10, 10, 11, 12]), status: Some(Status::Fail(String::from("2287"))) },
+            Row { day: 16, phase: String::from("Part 1"), runs: 0, stats: None, status: Some(Status::Error(String::from("panicked: oops"))) }
+        ]
+    }
+
+    #[rstest]
+    #[case(Format::Text, indoc! {
+        "Day 2 (5 runs)
+         ---
+         Phase             Min       Median         Mean          Max
+         Load           1.00ms       2.00ms       2.00ms       3.00ms
+         Part 1         4.00ms       5.00ms       6.00ms       9.00ms
+         Part 2        10.00ms      10.00ms      11.00ms      12.00ms  FAIL, expected 2287
+         ---
+         Day 16 (0 runs)
+         ---
+         Phase             Min       Median         Mean          Max
+         Part 1              -            -            -            -  ERROR, panicked: oops
+         ---
+         "
+    })]
+    #[case(Format::Json, indoc! {
+        r#"[
+             {"day": 2, "phase": "Load", "runs": 5, "min_ms": 1.000, "median_ms": 2.000, "mean_ms": 2.000, "max_ms": 3.000},
+             {"day": 2, "phase": "Part 1", "runs": 5, "min_ms": 4.000, "median_ms": 5.000, "mean_ms": 6.000, "max_ms": 9.000, "status": "ok"},
+             {"day": 2, "phase": "Part 2", "runs": 5, "min_ms": 10.000, "median_ms": 10.000, "mean_ms": 11.000, "max_ms": 12.000, "status": "fail"},
+             {"day": 16, "phase": "Part 1", "runs": 0, "status": "error"}
+           ]
+           "#
+    })]
+    #[case(Format::Csv, indoc! {
+        "day,phase,runs,min_ms,median_ms,mean_ms,max_ms,status
+         2,Load,5,1.000,2.000,2.000,3.000,
+         2,Part 1,5,4.000,5.000,6.000,9.000,ok
+         2,Part 2,5,10.000,10.000,11.000,12.000,fail
+         16,Part 1,0,,,,,error
+         "
+    })]
+    #[case(Format::Markdown, indoc! {
+        "| Day | Phase | Runs | Min | Median | Mean | Max | Status |
+         |----:|-------|-----:|----:|-------:|-----:|----:|--------|
+         | 2 | Load | 5 | 1.00ms | 2.00ms | 2.00ms | 3.00ms |  |
+         | 2 | Part 1 | 5 | 4.00ms | 5.00ms | 6.00ms | 9.00ms | ok |
+         | 2 | Part 2 | 5 | 10.00ms | 10.00ms | 11.00ms | 12.00ms | fail |
+         | 16 | Part 1 | 0 |  |  |  |  | error |
+         "
+    })]
+    fn test_write(#[case] format: Format, #[case] expected: &str) {
+        let mut out: Vec<u8> = Vec::new();
+        write(format, &rows(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+}
@@ -0,0 +1,136 @@
+//! Cooperative cancellation, so a day that runs for too long can be stopped without stopping everything else.
+//!
+//! The solvers can't be interrupted from outside, so the slow ones call [`check`] in their hot loops. It does nothing
+//! unless the code is running under [`run`] with a [`Token`] that has been cancelled, in which case it unwinds out of
+//! the solver and [`run`] returns [`Cancelled`].
+
+use std::cell::RefCell;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Shared between whatever is doing the work and whatever decides it should stop. Clones all refer to the same token.
+#[derive(Debug, Clone, Default)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>
+}
+
+impl Token {
+    /// A token that hasn't been cancelled.
+    pub fn new() -> Token {
+        Token::default()
+    }
+
+    /// Asks everything holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether [`cancel`](Token::cancel) has been called on this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Why [`run`] didn't finish.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Cancelled;
+
+thread_local! {
+    // The token for the work running on this thread, if any
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Runs `f`, which stops at its next [`check`] once the token has been cancelled. Other panics carry on unwinding.
+pub fn run<T>(token: &Token, f: impl FnOnce() -> T) -> Result<T, Cancelled> {
+    if token.is_cancelled() {
+        return Err(Cancelled);
+    }
+    let previous = CURRENT.with(|current| current.replace(Some(token.clone())));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CURRENT.with(|current| current.replace(previous));
+    match result {
+        Ok(value) => Ok(value),
+        Err(payload) if payload.is::<Cancelled>() => Err(Cancelled),
+        Err(payload) => panic::resume_unwind(payload)
+    }
+}
+
+/// Stops the work running on this thread if its token has been cancelled. This is cheap enough to call on every
+/// iteration of most loops.
+pub fn check() {
+    let cancelled = CURRENT.with(|current| current.borrow().as_ref().is_some_and(Token::is_cancelled));
+    if cancelled {
+        // resume_unwind rather than panic!, so the panic hook doesn't print anything
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
+
+/// Cancels the token after `timeout`, unless the returned sender is dropped first.
+pub fn cancel_after(token: &Token, timeout: Duration) -> mpsc::Sender<()> {
+    let (sender, receiver) = mpsc::channel::<()>();
+    let token = token.clone();
+    thread::spawn(move || {
+        if receiver.recv_timeout(timeout) == Err(mpsc::RecvTimeoutError::Timeout) {
+            token.cancel();
+        }
+    });
+    sender
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use crate::cancel::{cancel_after, check, run, Cancelled, Token};
+
+    fn spin() {
+        loop {
+            check();
+        }
+    }
+
+    #[test]
+    fn test_run_finishes() {
+        assert_eq!(run(&Token::new(), || 7), Ok(7));
+    }
+
+    #[test]
+    fn test_run_cancelled() {
+        let token = Token::new();
+        let start = Instant::now();
+        let _timer = cancel_after(&token, Duration::from_millis(50));
+        assert_eq!(run(&token, spin), Err(Cancelled));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        // Once it's cancelled, nothing else gets started
+        assert_eq!(run(&token, || 7), Err(Cancelled));
+    }
+
+    #[test]
+    fn test_dropping_the_timer_stops_it() {
+        let token = Token::new();
+        drop(cancel_after(&token, Duration::from_millis(20)));
+        thread::sleep(Duration::from_millis(100));
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn test_check_outside_run() {
+        let token = Token::new();
+        token.cancel();
+        check();
+        // The token only applies inside run, and on the thread that called it
+        assert_eq!(run(&Token::new(), check), Ok(()));
+    }
+
+    #[test]
+    fn test_other_panics_carry_on() {
+        let result = panic::catch_unwind(|| run(&Token::new(), || panic!("not cancelled")));
+        assert_eq!(result.unwrap_err().downcast_ref::<&str>(), Some(&"not cancelled"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Checking answers against the ones we know are right, for `--check`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use toml::{Table, Value};
+use crate::report::Status;
+use crate::solution::Part;
+
+/// Expected answers are kept in a TOML file, one table per day:
+///
+/// ```toml
+/// [day01]
+/// part1 = 56397
+/// part2 = "55701"
+/// ```
+///
+/// Answers can be numbers or strings, they are compared as strings either way.
+#[derive(PartialEq, Debug, Default)]
+pub struct Answers {
+    expected: HashMap<(u8, Part), String>
+}
+
+impl Answers {
+    /// Reads the answers from a TOML file like the one above.
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        Answers::parse(text.as_str()).map_err(|e| format!("Could not parse {}: {}", path.display(), e))
+    }
+
+    fn parse(text: &str) -> Result<Answers, String> {
+        let table: Table = text.parse().map_err(|e: toml::de::Error| e.message().to_string())?;
+        let mut expected: HashMap<(u8, Part), String> = HashMap::new();
+        for (key, value) in table {
+            let day = key.strip_prefix("day")
+                .and_then(|x| x.parse::<u8>().ok())
+                .ok_or_else(|| format!("[{}] should be named day<n>, like [day01]", key))?;
+            let Value::Table(parts) = value else {
+                return Err(format!("{} should be a table", key));
+            };
+            for (part_key, answer) in parts {
+                let part = match part_key.as_str() {
+                    "part1" => Part::One,
+                    "part2" => Part::Two,
+                    p => return Err(format!("{}.{} should be part1 or part2", key, p))
+                };
+                let answer = match answer {
+                    Value::String(s) => s,
+                    Value::Integer(i) => i.to_string(),
+                    a => return Err(format!("{}.{} should be a number or a string, not {}", key, part_key, a.type_str()))
+                };
+                expected.insert((day, part), answer);
+            }
+        }
+        Ok(Answers { expected })
+    }
+
+    /// Sets the expected answer for one part of a day.
+    pub fn add(&mut self, day: u8, part: Part, answer: &str) {
+        self.expected.insert((day, part), answer.to_string());
+    }
+
+    /// Compares an answer with the expected one, if there is one.
+    pub fn check(&self, day: u8, part: Part, actual: &str) -> Status {
+        match self.expected.get(&(day, part)) {
+            Some(expected) if expected == actual => Status::Pass,
+            Some(expected) => Status::Fail(expected.clone()),
+            None => Status::Missing
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use rstest::rstest;
+    use crate::check::Answers;
+    use crate::report::Status;
+    use crate::solution::Part;
+
+    const ANSWERS: &str = indoc! {
+        r#"[day01]
+           part1 = 142
+           part2 = "281"
+
+           [day10]
+           part1 = 8"#
+    };
+
+    #[rstest]
+    #[case(1, Part::One, "142", Status::Pass)]
+    #[case(1, Part::Two, "281", Status::Pass)]
+    #[case(1, Part::One, "143", Status::Fail(String::from("142")))]
+    #[case(10, Part::One, "8", Status::Pass)]
+    #[case(10, Part::Two, "1", Status::Missing)]
+    #[case(2, Part::One, "8", Status::Missing)]
+    fn test_check(#[case] day: u8, #[case] part: Part, #[case] actual: &str, #[case] expected: Status) {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.check(day, part, actual), expected);
+    }
+
+    #[rstest]
+    #[case("[one]\npart1 = 1")]
+    #[case("[day01]\npart3 = 1")]
+    #[case("[day01]\npart1 = 1.5")]
+    #[case("day01 = 1")]
+    #[case("[day01")]
+    fn test_parse_errors(#[case] text: &str) {
+        assert!(Answers::parse(text).is_err());
+    }
+}
@@ -1,13 +1,307 @@
+//! Things that are shared between the days: reading the input, reporting problems with it, logging how the answers
+//! were worked out, the [`grid::Grid`] that the maps are read into, the [`point::Direction`]s and points for
+//! moving around them, finding where a sequence of states starts to [`cycle`], the cheapest way between states
+//! with a best-first [`search`], ranges of numbers as an [`interval::IntervalSet`], measuring a
+//! [`geometry::Polygon`], and the small [`parse`]rs that read each line of the input.
+
+use std::error::Error;
+use std::fmt::{Arguments, Display, Formatter};
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
 use structopt::lazy_static::lazy_static;
 
+pub mod cycle;
+pub mod geometry;
+pub mod grid;
+pub mod interval;
+pub mod parse;
+pub mod point;
+pub mod search;
+
 lazy_static! {
+    /// An empty list that lives forever, for things that hand out references to lists they may not have.
     pub static ref EMPTY_STRING_VEC: Vec<String> = Vec::new();
 }
 
-pub fn load_from(filename: &str) -> String {
-    let path = format!("data{}{}", std::path::MAIN_SEPARATOR, filename);
-    let data_file = Path::new(path.as_str());
-    fs::read_to_string(data_file).unwrap()
+/// Where the puzzle input comes from. By default this is data/dayNN.txt, relative to where
+/// the runner is started from.
+#[derive(PartialEq, Debug, Clone)]
+pub enum InputSource {
+    /// The same file, whichever day it is for.
+    File(PathBuf),
+    /// The dayNN.txt file for the day in this directory.
+    DataDir(PathBuf),
+    /// Standard input, which is read once and shared by every day.
+    Stdin,
+    /// Input that's already in memory, such as one of the examples.
+    Text(String)
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::DataDir(PathBuf::from("data"))
+    }
+}
+
+impl InputSource {
+    /// The source for an --input argument, where `-` means stdin.
+    pub fn from_input(path: &Path) -> InputSource {
+        if path == Path::new("-") {
+            InputSource::Stdin
+        } else {
+            InputSource::File(path.to_path_buf())
+        }
+    }
+
+    /// Only a data directory can hold the input for more than one day.
+    pub fn supports_many_days(&self) -> bool {
+        matches!(self, InputSource::DataDir(_))
+    }
+
+    /// The file a day's input is read from, if it comes from a file.
+    pub fn path(&self, day: u8) -> Option<PathBuf> {
+        match self {
+            InputSource::File(path) => Some(path.clone()),
+            InputSource::DataDir(dir) => Some(dir.join(data_file_name(day))),
+            InputSource::Stdin | InputSource::Text(_) => None
+        }
+    }
+}
+
+/// The name of a day's input file in the data directory.
+pub fn data_file_name(day: u8) -> String {
+    format!("day{:02}.txt", day)
+}
+
+/// Reads the input for a day.
+pub fn load_from(source: &InputSource, day: u8) -> io::Result<String> {
+    match source {
+        InputSource::File(path) => read_file(path),
+        InputSource::DataDir(dir) => read_file(&dir.join(data_file_name(day))).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::new(e.kind(), format!("{}\n  Run `fetch --day {}` to download it", e, day)),
+            _ => e
+        }),
+        InputSource::Stdin => read_stdin(),
+        InputSource::Text(text) => Ok(text.clone())
+    }
+}
+
+fn read_file(path: &Path) -> io::Result<String> {
+    fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("Could not read {}: {}", path.display(), e)))
+}
+
+// Stdin can only be read once, so we keep hold of it in case the day is run more than once (when benchmarking, say).
+static STDIN: OnceLock<Result<String, String>> = OnceLock::new();
+
+fn read_stdin() -> io::Result<String> {
+    STDIN.get_or_init(|| {
+        let mut data = String::new();
+        io::stdin().read_to_string(&mut data).map(|_| data).map_err(|e| format!("Could not read from stdin: {}", e))
+    }).clone().map_err(io::Error::other)
+}
+
+/// Something in the puzzle input that a day's parser couldn't make sense of. Lines and columns count from 1.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ParseError {
+    /// The day whose input it is, or 0 until the registry fills it in.
+    pub day: u8,
+    /// The line of the input the problem is on.
+    pub line: usize,
+    /// Where the problem starts in that line.
+    pub column: usize,
+    /// The part of the line that couldn't be parsed.
+    pub text: String,
+    /// What was wrong with it.
+    pub message: String
+}
+
+impl ParseError {
+    /// The parsers don't know which day they are for, the day is filled in by the registry (see with_day).
+    pub fn new(line: usize, column: usize, text: &str, message: impl Into<String>) -> ParseError {
+        ParseError { day: 0, line, column, text: text.to_string(), message: message.into() }
+    }
+
+    /// For when the problem is `fragment`, which must be a slice of `line`. The column is worked out
+    /// from where the fragment sits in the line. The index counts from 0, as it does from enumerate().
+    pub fn in_line(index: usize, line: &str, fragment: &str, message: impl Into<String>) -> ParseError {
+        let start = line.as_ptr() as usize;
+        let offset = (fragment.as_ptr() as usize).wrapping_sub(start);
+        let column = if offset <= line.len() && offset + fragment.len() <= line.len() { line[..offset].chars().count() + 1 } else { 1 };
+        ParseError::new(index + 1, column, line, message)
+    }
+
+    /// Sets which day the error came from.
+    pub fn with_day(self, day: u8) -> ParseError {
+        ParseError { day, ..self }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Day {}, line {}, column {}: {}", self.day, self.line, self.column, self.message)?;
+        writeln!(f, "  {}", self.text)?;
+        write!(f, "  {}^", " ".repeat(self.column.saturating_sub(1)))
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `fragment`, a slice of `line`, as a number.
+pub fn parse_number<T: FromStr>(index: usize, line: &str, fragment: &str) -> Result<T, ParseError> {
+    fragment.parse::<T>().map_err(|_| ParseError::in_line(index, line, fragment, format!("expected a number, found \"{}\"", fragment)))
+}
+
+/// How much detail is logged about how the answers were worked out, chosen with -v, -vv or -vvv.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum Level {
+    /// The key steps, a few lines for each part
+    Info = 1,
+    /// The values behind each step
+    Debug = 2,
+    /// Everything, which can be a lot
+    Trace = 3
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace")
+        }
+    }
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Sets how many -v flags were given. Nothing is logged at 0, which is the default.
+pub fn set_verbosity(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are written out.
+pub fn log_enabled(level: Level) -> bool {
+    level as u8 <= VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// Writes a message to stderr, so it doesn't get mixed up with the answers. Use the [`info!`](crate::info),
+/// [`debug!`](crate::debug) and [`trace!`](crate::trace) macros rather than calling this.
+#[doc(hidden)]
+pub fn write_log(level: Level, module: &str, message: Arguments) {
+    let source = module.rsplit("::").next().unwrap_or(module);
+    eprintln!("[{} {}] {}", level, source, message);
+}
+
+/// Logs a message at the given [`Level`], formatted like `println!`. The arguments are only worked out if the message
+/// is going to be written.
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if $crate::common::log_enabled(level) {
+            $crate::common::write_log(level, module_path!(), format_args!($($arg)+));
+        }
+    }};
+}
+
+/// Logs a key step, shown with -v.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::log!($crate::common::Level::Info, $($arg)+) };
+}
+
+/// Logs the values behind a step, shown with -vv.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::log!($crate::common::Level::Debug, $($arg)+) };
+}
+
+/// Logs every detail, shown with -vvv.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::log!($crate::common::Level::Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use crate::common::{InputSource, Level, load_from, log_enabled, parse_number, ParseError, set_verbosity};
+    use crate::testing::TempDir;
+
+    #[test]
+    fn test_load_from_data_dir() {
+        let dir = TempDir::new("data-dir");
+        fs::write(dir.join("day07.txt"), "32T3K 765").unwrap();
+        assert_eq!(load_from(&InputSource::DataDir(dir.to_path_buf()), 7).unwrap(), "32T3K 765");
+        assert!(load_from(&InputSource::DataDir(dir.to_path_buf()), 8).is_err());
+    }
+
+    #[test]
+    fn test_load_from_file_ignores_day() {
+        let dir = TempDir::new("file");
+        let file = dir.join("my_input.txt");
+        fs::write(&file, "RL").unwrap();
+        assert_eq!(load_from(&InputSource::File(file.clone()), 8).unwrap(), "RL");
+        assert_eq!(load_from(&InputSource::File(file), 9).unwrap(), "RL");
+    }
+
+    #[test]
+    fn test_missing_file_error_has_path() {
+        let error = load_from(&InputSource::File(PathBuf::from("does/not/exist.txt")), 1).unwrap_err();
+        assert!(error.to_string().contains("exist.txt"), "{}", error);
+    }
+
+    #[test]
+    fn test_missing_data_file_suggests_fetch() {
+        let error = load_from(&InputSource::DataDir(PathBuf::from("does/not/exist")), 4).unwrap_err();
+        assert!(error.to_string().ends_with("Run `fetch --day 4` to download it"), "{}", error);
+    }
+
+    #[test]
+    fn test_from_input() {
+        assert_eq!(InputSource::from_input(Path::new("-")), InputSource::Stdin);
+        assert_eq!(InputSource::from_input(Path::new("day01.txt")), InputSource::File(PathBuf::from("day01.txt")));
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(InputSource::DataDir(PathBuf::from("data")).path(7), Some(PathBuf::from("data/day07.txt")));
+        assert_eq!(InputSource::File(PathBuf::from("my_input.txt")).path(7), Some(PathBuf::from("my_input.txt")));
+        assert_eq!(InputSource::Stdin.path(7), None);
+    }
+
+    #[test]
+    fn test_parse_error_column_from_fragment() {
+        let line = "Game 3: x red";
+        let error = ParseError::in_line(2, line, &line[8..9], "expected a number").with_day(2);
+        assert_eq!(error, ParseError { day: 2, line: 3, column: 9, text: String::from(line), message: String::from("expected a number") });
+        assert_eq!(error.to_string(), "Day 2, line 3, column 9: expected a number\n  Game 3: x red\n          ^");
+    }
+
+    #[test]
+    fn test_parse_error_fragment_not_in_line() {
+        assert_eq!(ParseError::in_line(0, "abc", "other", "nope").column, 1);
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let line = "50 98 x2";
+        assert_eq!(parse_number::<u64>(0, line, &line[3..5]), Ok(98));
+        assert_eq!(parse_number::<u64>(0, line, &line[6..]), Err(ParseError::new(1, 7, line, "expected a number, found \"x2\"")));
+    }
+
+    #[test]
+    fn test_log_enabled() {
+        set_verbosity(2);
+        let enabled = [Level::Info, Level::Debug, Level::Trace].map(log_enabled);
+        set_verbosity(0);
+        assert_eq!(enabled, [true, true, false]);
+        assert!(!log_enabled(Level::Info));
+    }
 }
@@ -0,0 +1,143 @@
+//! Finding where something that's stepped over and over again starts repeating itself, so the state after a huge
+//! number of steps can be worked out without taking them all.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::iter::successors;
+
+/// Where a sequence of states repeats: after `prefix` steps it reaches the first state in `states`, and from then on
+/// goes round the `period` states in `states` forever.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Cycle<S> {
+    /// How many steps are taken before the cycle starts.
+    pub prefix: usize,
+    /// How many steps it takes to go once round the cycle.
+    pub period: usize,
+    /// The states in the cycle, in the order they come round.
+    pub states: Vec<S>
+}
+
+impl<S> Cycle<S> {
+    /// The state after `steps` steps, if that's far enough in to be part of the cycle.
+    pub fn state_after(&self, steps: usize) -> Option<&S> {
+        steps.checked_sub(self.prefix).map(|into_cycle| &self.states[into_cycle % self.period])
+    }
+}
+
+/// Finds the cycle by remembering every state seen, so each state is only made once. The first state is `start`, and
+/// each one after that is `step` applied to the one before.
+pub fn find_by_hashing<S: Hash + Eq + Clone>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    find_in(successors(Some(start), |state| Some(step(state)))).expect("an endless sequence always repeats if it can")
+}
+
+/// Finds the cycle in a sequence of states by remembering every state seen, or None if the sequence ends before any
+/// state comes round again. This suits states that can only be made in order, such as by changing something in
+/// place.
+pub fn find_in<S: Hash + Eq + Clone>(states: impl IntoIterator<Item=S>) -> Option<Cycle<S>> {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history: Vec<S> = Vec::new();
+    for (steps, state) in states.into_iter().enumerate() {
+        if let Some(&prefix) = seen.get(&state) {
+            return Some(Cycle { prefix, period: steps - prefix, states: history.split_off(prefix) });
+        }
+        seen.insert(state.clone(), steps);
+        history.push(state);
+    }
+    None
+}
+
+/// Finds the cycle with Brent's algorithm, which only keeps a couple of states at a time, so it suits states that
+/// can't be hashed or are too big to keep them all. It makes each state more than once, so `step` should be cheap.
+pub fn find_by_brent<S: Eq + Clone>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    // Look for the period in ever doubling windows
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // With the hare a period ahead of the tortoise, they first meet where the cycle starts
+    let mut tortoise = start.clone();
+    let mut hare = (0..period).fold(start, |state, _| step(&state));
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+
+    let states = successors(Some(tortoise), |state| Some(step(state))).take(period).collect();
+    Cycle { prefix, period, states }
+}
+
+/// The state after `steps` steps, skipping the repeats once the states start going round in a cycle.
+pub fn state_after<S: Hash + Eq + Clone>(start: S, steps: usize, mut step: impl FnMut(&S) -> S) -> S {
+    let mut last = None;
+    let states = successors(Some(start), |state| Some(step(state))).take(steps + 1).inspect(|state| last = Some(state.clone()));
+    match find_in(states) {
+        Some(cycle) => cycle.state_after(steps).unwrap().clone(),
+        // It didn't repeat before getting there, so the last state is the one we want
+        None => last.unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use rstest::rstest;
+    use crate::common::cycle::{Cycle, find_by_brent, find_by_hashing, find_in, state_after};
+
+    // Goes 0, 1, ..., prefix - 1 and then round prefix, ..., prefix + period - 1 forever
+    fn rho(prefix: usize, period: usize) -> impl Fn(&usize) -> usize {
+        move |state| if state + 1 < prefix + period { state + 1 } else { prefix }
+    }
+
+    #[rstest]
+    #[case(0, 1)]
+    #[case(0, 5)]
+    #[case(3, 1)]
+    #[case(7, 4)]
+    fn test_find(#[case] prefix: usize, #[case] period: usize) {
+        let expected = Cycle { prefix, period, states: (prefix..prefix + period).collect() };
+        assert_eq!(find_by_hashing(0, rho(prefix, period)), expected);
+        assert_eq!(find_by_brent(0, rho(prefix, period)), expected);
+    }
+
+    #[test]
+    fn test_find_in() {
+        assert_eq!(find_in("abcdc".chars()), Some(Cycle { prefix: 2, period: 2, states: vec!['c', 'd'] }));
+        assert_eq!(find_in("abcd".chars()), None);
+    }
+
+    #[test]
+    fn test_state_after() {
+        let cycle = find_by_hashing(0, rho(3, 4));
+        assert_eq!(cycle.state_after(2), None);
+        assert_eq!(cycle.state_after(3), Some(&3));
+        assert_eq!(cycle.state_after(1_000_000_000), Some(&(3 + (1_000_000_000 - 3) % 4)));
+        assert_eq!(state_after(0, 1_000_000_000, rho(3, 4)), 3 + (1_000_000_000 - 3) % 4);
+        assert_eq!(state_after(0, 2, rho(3, 4)), 2);
+    }
+
+    proptest! {
+        #[test]
+        fn test_brent_and_hashing_agree(modulus in 1..500u64, multiplier in 0..500u64, increment in 0..500u64, start in 0..500u64) {
+            let step = |state: &u64| (state * multiplier + increment) % modulus;
+            let start = start % modulus;
+            let cycle = find_by_hashing(start, step);
+            prop_assert_eq!(&find_by_brent(start, step), &cycle);
+            for steps in [0, 1, 17, 1000] {
+                let walked = (0..steps).fold(start, |state, _| step(&state));
+                prop_assert_eq!(state_after(start, steps, step), walked);
+            }
+        }
+    }
+}
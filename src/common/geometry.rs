@@ -0,0 +1,153 @@
+//! Measuring polygons whose corners are on whole-numbered points, such as loops drawn on a map: their area with the
+//! shoelace formula, and how many points are on and inside their edges with Pick's theorem. Everything is checked, and
+//! gives None rather than a wrong answer if the numbers get too big.
+
+use num::integer::gcd;
+use crate::common::point::Point;
+
+/// Which way round a polygon's corners go, as seen on a map with row 0 at the top.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Orientation {
+    /// The way a clock's hands go.
+    Clockwise,
+    /// The other way.
+    Anticlockwise,
+    /// The corners are all on one line, so there's no inside.
+    Degenerate
+}
+
+/// A polygon that doesn't cross itself, as its corners in order. The last corner joins back up to the first.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Polygon {
+    corners: Vec<Point>
+}
+
+impl Polygon {
+    /// The polygon with these corners, in order.
+    pub fn new(corners: Vec<Point>) -> Polygon {
+        Polygon { corners }
+    }
+
+    /// The corners, in the order they were given.
+    pub fn corners(&self) -> &[Point] {
+        &self.corners
+    }
+
+    /// Twice the area, which is always a whole number, negative if the corners go clockwise.
+    pub fn twice_signed_area(&self) -> Option<i64> {
+        self.edges().try_fold(0i64, |total, (from, to)| total.checked_add(cross(from, to)?))
+    }
+
+    /// Which way round the corners go.
+    pub fn orientation(&self) -> Option<Orientation> {
+        Some(match self.twice_signed_area()? {
+            0 => Orientation::Degenerate,
+            area if area < 0 => Orientation::Clockwise,
+            _ => Orientation::Anticlockwise
+        })
+    }
+
+    /// The number of whole-numbered points on the edges, corners included.
+    pub fn boundary_points(&self) -> Option<i64> {
+        self.edges().try_fold(0i64, |total, (from, to)| {
+            let rows = (to.row as i64).checked_sub(from.row as i64)?.checked_abs()?;
+            let columns = (to.column as i64).checked_sub(from.column as i64)?.checked_abs()?;
+            total.checked_add(gcd(rows, columns))
+        })
+    }
+
+    /// The number of whole-numbered points strictly inside the edges, by Pick's theorem. A flat polygon, whose edges
+    /// go back over each other, has no inside for the theorem to count, so it gives None.
+    pub fn interior_points(&self) -> Option<i64> {
+        let area = self.twice_signed_area()?.checked_abs()?;
+        let twice_interior = area.checked_sub(self.boundary_points()?)?.checked_add(2)?;
+        (twice_interior >= 0).then_some(twice_interior / 2)
+    }
+
+    /// The number of whole-numbered points inside the edges or on them.
+    pub fn enclosed_points(&self) -> Option<i64> {
+        self.interior_points()?.checked_add(self.boundary_points()?)
+    }
+
+    // Each corner and the one after it, ending with the last back to the first
+    fn edges(&self) -> impl Iterator<Item=(Point, Point)> + '_ {
+        self.corners.iter().copied().zip(self.corners.iter().copied().cycle().skip(1))
+    }
+}
+
+// The cross product of the two points, like Point::cross but checked
+fn cross(from: Point, to: Point) -> Option<i64> {
+    let forward = (from.row as i64).checked_mul(to.column as i64)?;
+    let backward = (from.column as i64).checked_mul(to.row as i64)?;
+    forward.checked_sub(backward)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use rstest::rstest;
+    use crate::common::geometry::{Orientation, Polygon};
+    use crate::common::point::Point;
+
+    fn polygon(corners: &[(isize, isize)]) -> Polygon {
+        Polygon::new(corners.iter().map(|&(row, column)| Point::new(row, column)).collect())
+    }
+
+    #[rstest]
+    // A square going east first, which is clockwise with row 0 at the top
+    #[case(&[(0, 0), (0, 2), (2, 2), (2, 0)], -8, Orientation::Clockwise, 8, 1)]
+    #[case(&[(0, 0), (2, 0), (2, 2), (0, 2)], 8, Orientation::Anticlockwise, 8, 1)]
+    #[case(&[(0, 0), (0, 4), (4, 0)], -16, Orientation::Clockwise, 12, 3)]
+    #[case(&[(0, 0), (0, 3), (1, 3), (1, 1), (3, 1), (3, 0)], -10, Orientation::Clockwise, 12, 0)]
+    fn test_polygon(#[case] corners: &[(isize, isize)], #[case] twice_area: i64, #[case] orientation: Orientation,
+                    #[case] boundary: i64, #[case] interior: i64) {
+        let polygon = polygon(corners);
+        assert_eq!(polygon.twice_signed_area(), Some(twice_area));
+        assert_eq!(polygon.orientation(), Some(orientation));
+        assert_eq!(polygon.boundary_points(), Some(boundary));
+        assert_eq!(polygon.interior_points(), Some(interior));
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(polygon(&[(0, 0), (0, 5)]).orientation(), Some(Orientation::Degenerate));
+        assert_eq!(polygon(&[(1, 1), (2, 2), (4, 4)]).orientation(), Some(Orientation::Degenerate));
+    }
+
+    #[rstest]
+    #[case(&[(0, 0), (0, 5)])]
+    #[case(&[(1, 1), (2, 2), (4, 4)])]
+    #[case(&[(0, 0), (0, 5), (0, 2), (3, 2), (0, 2)])]
+    fn test_flat(#[case] corners: &[(isize, isize)]) {
+        let polygon = polygon(corners);
+        assert_eq!(polygon.twice_signed_area(), Some(0));
+        assert_eq!(polygon.interior_points(), None);
+        assert_eq!(polygon.enclosed_points(), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let huge = isize::MAX / 8;
+        let polygon = polygon(&[(0, 0), (0, huge), (huge, huge), (huge, 0)]);
+        assert_eq!(polygon.twice_signed_area(), None);
+        assert_eq!(polygon.enclosed_points(), None);
+        assert_eq!(polygon.boundary_points(), Some(4 * huge as i64));
+    }
+
+    proptest! {
+        #[test]
+        fn test_rectangles(top in -1000..1000isize, left in -1000..1000isize, height in 1..1000isize, width in 1..1000isize) {
+            let (bottom, right) = (top + height, left + width);
+            let rectangle = polygon(&[(top, left), (top, right), (bottom, right), (bottom, left)]);
+            let (height, width) = (height as i64, width as i64);
+            prop_assert_eq!(rectangle.twice_signed_area(), Some(-2 * height * width));
+            prop_assert_eq!(rectangle.boundary_points(), Some(2 * (height + width)));
+            prop_assert_eq!(rectangle.interior_points(), Some((height - 1) * (width - 1)));
+            prop_assert_eq!(rectangle.enclosed_points(), Some((height + 1) * (width + 1)));
+
+            let mut backwards = rectangle.corners().to_vec();
+            backwards.reverse();
+            prop_assert_eq!(Polygon::new(backwards).twice_signed_area(), Some(2 * height * width));
+        }
+    }
+}
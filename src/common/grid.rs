@@ -0,0 +1,333 @@
+//! A rectangle of cells, for the days whose input is a map drawn with one character per cell.
+
+use std::ops::{Index, IndexMut};
+use itertools::Itertools;
+use crate::common::ParseError;
+use crate::common::point::{Direction, Position};
+
+/// A rectangle of cells, stored row by row. Positions are `(row, column)`, counting from 0 in the top left.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub struct Grid<T> {
+    height: usize,
+    width: usize,
+    cells: Vec<T>
+}
+
+// The steps to each neighbour, in reading order
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+const NEIGHBOURS_8: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+impl<T> Grid<T> {
+    /// A grid from its cells, row by row. Panics if there aren't `height * width` of them.
+    pub fn new(height: usize, width: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), height * width, "a {} by {} grid needs {} cells", height, width, height * width);
+        Grid { height, width, cells }
+    }
+
+    /// A grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == width), "the rows are not all {} long", width);
+        Grid { height, width, cells: rows.into_iter().flatten().collect() }
+    }
+
+    /// Reads a grid drawn with one character per cell, turning each character into a cell with `cell`. Every line
+    /// must be the same length, and `cell` returns None for characters that shouldn't be there.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for (index, line) in input.lines().enumerate() {
+            let expected = *width.get_or_insert(line.chars().count());
+            let found = line.chars().count();
+            if found != expected {
+                return Err(ParseError::new(index + 1, found.min(expected) + 1, line, format!("expected {} cells in the row, found {}", expected, found)));
+            }
+            for (column, c) in line.chars().enumerate() {
+                cells.push(cell(c).ok_or_else(|| ParseError::new(index + 1, column + 1, line, format!("unexpected {:?}", c)))?);
+            }
+            height += 1;
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Grid { height, width, cells }),
+            _ => Err(ParseError::new(1, 1, "", "the grid is empty"))
+        }
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Whether the position is inside the grid.
+    pub fn contains(&self, (row, column): (usize, usize)) -> bool {
+        row < self.height && column < self.width
+    }
+
+    /// The cell at the position, if it's inside the grid.
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.contains(position).then(|| &self.cells[position.0 * self.width + position.1])
+    }
+
+    /// The cell at the position to change, if it's inside the grid.
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        self.contains(position).then(|| &mut self.cells[position.0 * self.width + position.1])
+    }
+
+    /// The position `by` rows and columns away, if that's still inside the grid.
+    pub fn step(&self, (row, column): (usize, usize), by: (isize, isize)) -> Option<(usize, usize)> {
+        let position = (row.checked_add_signed(by.0)?, column.checked_add_signed(by.1)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// The position next to this one in the direction, if that's still inside the grid.
+    pub fn next(&self, position: (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        Position::from(position).step_within(direction, self.height, self.width).map(Into::into)
+    }
+
+    /// The positions above, to the left, to the right and below, leaving out any that are off the edge.
+    pub fn neighbours4(&self, position: (usize, usize)) -> impl Iterator<Item=(usize, usize)> + '_ {
+        NEIGHBOURS_4.iter().filter_map(move |by| self.step(position, *by))
+    }
+
+    /// The eight positions around this one in reading order, leaving out any that are off the edge.
+    pub fn neighbours8(&self, position: (usize, usize)) -> impl Iterator<Item=(usize, usize)> + '_ {
+        NEIGHBOURS_8.iter().filter_map(move |by| self.step(position, *by))
+    }
+
+    /// Every position, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item=(usize, usize)> {
+        (0..self.height).cartesian_product(0..self.width)
+    }
+
+    /// Every cell and its position, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item=((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position, in reading order, whose cell matches.
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(position, _)| position)
+    }
+
+    /// The cells in a row, from the left. Panics if the row is outside the grid.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    /// Each row from the top.
+    pub fn rows(&self) -> impl Iterator<Item=&[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// The cells in a column, from the top.
+    pub fn column(&self, column: usize) -> impl Iterator<Item=&T> {
+        assert!(column < self.width, "column {} is outside a grid {} wide", column, self.width);
+        self.cells.iter().skip(column).step_by(self.width)
+    }
+
+    /// Each column from the left.
+    pub fn columns(&self) -> impl Iterator<Item=impl Iterator<Item=&T>> {
+        (0..self.width).map(|column| self.column(column))
+    }
+
+    /// A grid the same shape, with `f` applied to each cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { height: self.height, width: self.width, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Draws the grid with one character per cell, the opposite of [`Grid::parse`]. There's no newline at the end.
+    pub fn to_text(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        self.rows().map(|row| row.iter().map(&mut cell).collect::<String>()).join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn filled(height: usize, width: usize, value: T) -> Grid<T> {
+        Grid { height, width, cells: vec![value; height * width] }
+    }
+
+    /// Swaps the rows and columns, mirroring the grid across the diagonal from the top left.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid { height: self.width, width: self.height, cells: self.columns().flatten().cloned().collect() }
+    }
+
+    /// Turns the grid a quarter turn clockwise, so the left column becomes the top row.
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        let cells = (0..self.width).flat_map(|column| (0..self.height).rev().map(move |row| (row, column)))
+            .map(|position| self[position].clone())
+            .collect();
+        Grid { height: self.width, width: self.height, cells }
+    }
+
+    /// Turns the grid a quarter turn anticlockwise, so the top row becomes the left column.
+    pub fn rotate_anticlockwise(&self) -> Grid<T> {
+        let cells = (0..self.width).rev().flat_map(|column| (0..self.height).map(move |row| (row, column)))
+            .map(|position| self[position].clone())
+            .collect();
+        Grid { height: self.width, width: self.height, cells }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position).unwrap_or_else(|| panic!("{:?} is outside a {} by {} grid", position, self.height, self.width))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        self.get_mut(position).unwrap_or_else(|| panic!("{:?} is outside a {} by {} grid", position, height, width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::collection::vec as prop_vec;
+    use proptest::{prop_assert_eq, proptest};
+    use rstest::rstest;
+    use crate::common::grid::Grid;
+    use crate::common::point::Direction;
+    use crate::common::point::Direction::{East, North, South, West};
+    use crate::common::ParseError;
+
+    // 1 2 3 4
+    // 5 6 7 8
+    // 9 a b c
+    fn numbers() -> Grid<char> {
+        Grid::parse("1234\n5678\n9abc", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse("#.\n.#\n##", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        });
+        assert_eq!(grid, Ok(Grid::from_rows(vec![vec![true, false], vec![false, true], vec![true, true]])));
+    }
+
+    #[rstest]
+    #[case("ab\nabc", ParseError::new(2, 3, "abc", "expected 2 cells in the row, found 3"))]
+    #[case("abc\na", ParseError::new(2, 2, "a", "expected 3 cells in the row, found 1"))]
+    #[case("ab\naX", ParseError::new(2, 2, "aX", "unexpected 'X'"))]
+    #[case("", ParseError::new(1, 1, "", "the grid is empty"))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(Grid::parse(input, |c| c.is_lowercase().then_some(c)), Err(expected));
+    }
+
+    #[test]
+    fn test_get() {
+        let grid = numbers();
+        assert_eq!((grid.height(), grid.width()), (3, 4));
+        assert_eq!(grid.get((1, 2)), Some(&'7'));
+        assert_eq!(grid[(2, 0)], '9');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 4)), None);
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut grid = numbers();
+        grid[(1, 1)] = 'x';
+        *grid.get_mut((0, 3)).unwrap() = 'y';
+        assert_eq!(grid.to_text(|c| *c), "123y\n5x78\n9abc");
+    }
+
+    #[rstest]
+    #[case((1, 1), vec![(0, 1), (1, 0), (1, 2), (2, 1)])]
+    #[case((0, 0), vec![(0, 1), (1, 0)])]
+    #[case((2, 3), vec![(1, 3), (2, 2)])]
+    fn test_neighbours4(#[case] position: (usize, usize), #[case] expected: Vec<(usize, usize)>) {
+        assert_eq!(numbers().neighbours4(position).collect::<Vec<_>>(), expected);
+    }
+
+    #[rstest]
+    #[case((1, 1), vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)])]
+    #[case((0, 0), vec![(0, 1), (1, 0), (1, 1)])]
+    #[case((2, 3), vec![(1, 2), (1, 3), (2, 2)])]
+    fn test_neighbours8(#[case] position: (usize, usize), #[case] expected: Vec<(usize, usize)>) {
+        assert_eq!(numbers().neighbours8(position).collect::<Vec<_>>(), expected);
+    }
+
+    #[rstest]
+    #[case((1, 1), (1, 2), Some((2, 3)))]
+    #[case((1, 1), (-1, -1), Some((0, 0)))]
+    #[case((0, 1), (-1, 0), None)]
+    #[case((1, 3), (0, 1), None)]
+    fn test_step(#[case] from: (usize, usize), #[case] by: (isize, isize), #[case] expected: Option<(usize, usize)>) {
+        assert_eq!(numbers().step(from, by), expected);
+    }
+
+    #[rstest]
+    #[case((1, 1), East, Some((1, 2)))]
+    #[case((0, 1), North, None)]
+    #[case((2, 3), South, None)]
+    #[case((2, 3), West, Some((2, 2)))]
+    fn test_next(#[case] from: (usize, usize), #[case] direction: Direction, #[case] expected: Option<(usize, usize)>) {
+        assert_eq!(numbers().next(from, direction), expected);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = numbers();
+        let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+        let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+        assert_eq!(rows, vec!["1234", "5678", "9abc"]);
+        assert_eq!(columns, vec!["159", "26a", "37b", "48c"]);
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(numbers().find(|c| c.is_alphabetic()), Some((2, 1)));
+        assert_eq!(numbers().find(|c| *c == 'z'), None);
+    }
+
+    #[test]
+    fn test_transpose() {
+        assert_eq!(numbers().transpose().to_text(|c| *c), "159\n26a\n37b\n48c");
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(numbers().rotate_clockwise().to_text(|c| *c), "951\na62\nb73\nc84");
+        assert_eq!(numbers().rotate_anticlockwise().to_text(|c| *c), "48c\n37b\n26a\n159");
+    }
+
+    #[test]
+    fn test_map() {
+        let grid = numbers().map(|c| c.to_digit(16).unwrap());
+        assert_eq!(grid.row(2), [9, 10, 11, 12]);
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip(width in 1..20usize, cells in prop_vec(proptest::sample::select(vec!['.', '#', 'O']), 1..200)) {
+            let height = cells.len() / width;
+            let text = cells[..height * width].chunks(width).map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+            if height > 0 {
+                prop_assert_eq!(Grid::parse(text.as_str(), Some).unwrap().to_text(|c| *c), text);
+            }
+        }
+
+        #[test]
+        fn test_four_turns(width in 1..8usize, height in 1..8usize) {
+            let grid = Grid::new(height, width, (0..width * height).collect());
+            let turned = grid.rotate_clockwise();
+            prop_assert_eq!(&turned.rotate_anticlockwise(), &grid);
+            prop_assert_eq!(&turned.rotate_clockwise().rotate_clockwise().rotate_clockwise(), &grid);
+            prop_assert_eq!(&grid.transpose().transpose(), &grid);
+        }
+    }
+}
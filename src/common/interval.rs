@@ -0,0 +1,357 @@
+//! Ranges of whole numbers, sets of them, and boxes made of one range along each axis, so the days that work on
+//! whole ranges of values at once don't each have to get the overlaps right for themselves.
+
+use std::ops::Range;
+use num::PrimInt;
+
+/// The numbers from `start` up to but not including `end`, like a [`Range`] but [`Copy`]. It's empty if `end` isn't
+/// after `start`.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct Interval<T> {
+    /// The first number in it.
+    pub start: T,
+    /// The number just after the last one in it.
+    pub end: T
+}
+
+impl<T: PrimInt> Interval<T> {
+    /// The numbers from `start` up to but not including `end`.
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval { start, end }
+    }
+
+    /// The numbers from `first` to `last`, including both. `last` has to be below the largest number `T` can hold, as
+    /// the end is the number after it.
+    pub fn inclusive(first: T, last: T) -> Interval<T> {
+        Interval { start: first, end: last + T::one() }
+    }
+
+    /// Whether there are no numbers in it.
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// How many numbers are in it.
+    pub fn len(&self) -> T {
+        if self.is_empty() { T::zero() } else { self.end - self.start }
+    }
+
+    /// Whether the number is in it.
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether there's any number in both.
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The numbers in both, if there are any.
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Some(Interval { start: self.start.max(other.start), end: self.end.min(other.end) }).filter(|both| !both.is_empty())
+    }
+
+    /// The numbers below `at`, and the numbers from `at` up. Either can be missing if there are no numbers in it.
+    pub fn split_at(&self, at: T) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        let below = Interval { start: self.start, end: self.end.min(at) };
+        let above = Interval { start: self.start.max(at), end: self.end };
+        (Some(below).filter(|below| !below.is_empty()), Some(above).filter(|above| !above.is_empty()))
+    }
+
+    /// The interval moved along so that what was at `from` is at `to`. Nothing in it, including the end, can end up
+    /// below zero or above the largest number `T` can hold.
+    pub fn translate(&self, from: T, to: T) -> Interval<T> {
+        Interval { start: translate(self.start, from, to), end: translate(self.end, from, to) }
+    }
+}
+
+// Moves the value so that what was at `from` is at `to`, without going below zero on the way for unsigned numbers
+fn translate<T: PrimInt>(value: T, from: T, to: T) -> T {
+    if to >= from { value + (to - from) } else { value - (from - to) }
+}
+
+impl<T: PrimInt> From<Range<T>> for Interval<T> {
+    fn from(range: Range<T>) -> Interval<T> {
+        Interval { start: range.start, end: range.end }
+    }
+}
+
+/// Any collection of numbers, as the fewest intervals that hold them, in order. Intervals that touch or overlap are
+/// joined together.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    /// A set with nothing in it.
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: Vec::new() }
+    }
+
+    /// The intervals in the set, in order, none of them touching.
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    /// Whether there are no numbers in the set.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// How many numbers are in the set.
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    /// The smallest number in the set.
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    /// Whether the number is in the set.
+    pub fn contains(&self, value: T) -> bool {
+        // The intervals are in order, so only the last one starting at or before the value can hold it
+        let after = self.intervals.partition_point(|interval| interval.start <= value);
+        after > 0 && self.intervals[after - 1].contains(value)
+    }
+
+    /// Adds the numbers in the interval to the set.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // Everything that touches the new interval is taken out and joined onto it
+        let first = self.intervals.partition_point(|existing| existing.end < interval.start);
+        let last = self.intervals.partition_point(|existing| existing.start <= interval.end);
+        let joined = self.intervals[first..last].iter().fold(interval, |joined, existing| {
+            Interval { start: joined.start.min(existing.start), end: joined.end.max(existing.end) }
+        });
+        self.intervals.splice(first..last, [joined]);
+    }
+
+    /// The numbers in either set.
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for interval in &other.intervals {
+            union.insert(*interval);
+        }
+        union
+    }
+
+    /// The numbers in both sets.
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut intersection = IntervalSet::new();
+        let (mut mine, mut theirs) = (self.intervals.iter().peekable(), other.intervals.iter().peekable());
+        while let (Some(a), Some(b)) = (mine.peek(), theirs.peek()) {
+            if let Some(both) = a.intersection(b) {
+                intersection.intervals.push(both);
+            }
+            // Whichever ends first can't overlap anything else in the other set
+            if a.end <= b.end { mine.next(); } else { theirs.next(); }
+        }
+        intersection
+    }
+
+    /// The numbers in this set but not the other.
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = IntervalSet::new();
+        for interval in &self.intervals {
+            let mut left = Some(*interval);
+            for taken in other.intervals.iter().skip_while(|taken| taken.end <= interval.start) {
+                let Some(rest) = left.filter(|rest| taken.start < rest.end) else {
+                    break;
+                };
+                let (below, _) = rest.split_at(taken.start);
+                difference.intervals.extend(below);
+                left = rest.split_at(taken.end).1;
+            }
+            difference.intervals.extend(left);
+        }
+        difference
+    }
+
+    /// The numbers below `at`, and the numbers from `at` up.
+    pub fn split_at(&self, at: T) -> (IntervalSet<T>, IntervalSet<T>) {
+        let (mut below, mut above) = (IntervalSet::new(), IntervalSet::new());
+        for interval in &self.intervals {
+            let (under, over) = interval.split_at(at);
+            below.intervals.extend(under);
+            above.intervals.extend(over);
+        }
+        (below, above)
+    }
+
+    /// Every number in the set moved along so that what was at `from` is at `to`. As with [`Interval::translate`], the
+    /// ends of its intervals all have to stay within what `T` can hold.
+    pub fn translate(&self, from: T, to: T) -> IntervalSet<T> {
+        IntervalSet { intervals: self.intervals.iter().map(|interval| interval.translate(from, to)).collect() }
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item=Interval<T>>>(intervals: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for interval in intervals {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+/// A box in `N` dimensions, with an interval along each axis, such as a brick in space.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub struct Region<T, const N: usize> {
+    /// The interval along each axis, in the order the axes are numbered.
+    pub sides: [Interval<T>; N]
+}
+
+impl<T: PrimInt, const N: usize> Region<T, N> {
+    /// The box with these intervals along each axis.
+    pub fn new(sides: [Interval<T>; N]) -> Region<T, N> {
+        Region { sides }
+    }
+
+    /// Whether there are no points in it, which is so if it's empty along any axis.
+    pub fn is_empty(&self) -> bool {
+        self.sides.iter().any(Interval::is_empty)
+    }
+
+    /// How many points are in it.
+    pub fn volume(&self) -> T {
+        self.sides.iter().fold(T::one(), |volume, side| volume * side.len())
+    }
+
+    /// Whether any point is in both.
+    pub fn overlaps(&self, other: &Region<T, N>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The points in both, if there are any.
+    pub fn intersection(&self, other: &Region<T, N>) -> Option<Region<T, N>> {
+        let mut sides = self.sides;
+        for (side, other) in sides.iter_mut().zip(other.sides.iter()) {
+            *side = side.intersection(other)?;
+        }
+        Some(Region { sides })
+    }
+
+    /// Cuts the box across the axis, into the part below `at` and the part from `at` up. Either can be missing if
+    /// there's nothing in it.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Region<T, N>>, Option<Region<T, N>>) {
+        let (below, above) = self.sides[axis].split_at(at);
+        let with = |side: Interval<T>| {
+            let mut sides = self.sides;
+            sides[axis] = side;
+            Region { sides }
+        };
+        (below.map(with), above.map(with))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use proptest::collection::vec as prop_vec;
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+    use proptest::strategy::Strategy;
+    use rstest::rstest;
+    use crate::common::interval::{Interval, IntervalSet, Region};
+
+    fn set(intervals: &[(u32, u32)]) -> IntervalSet<u32> {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    // The same set, one number at a time
+    fn numbers(set: &IntervalSet<u32>) -> BTreeSet<u32> {
+        set.intervals().iter().flat_map(|interval| interval.start..interval.end).collect()
+    }
+
+    fn sets() -> impl Strategy<Value=IntervalSet<u32>> {
+        prop_vec((0..60u32, 0..10u32), 0..6).prop_map(|intervals| {
+            intervals.into_iter().map(|(start, length)| Interval::new(start, start + length)).collect()
+        })
+    }
+
+    #[rstest]
+    #[case(Interval::new(2, 8), Interval::new(5, 12), Some(Interval::new(5, 8)))]
+    #[case(Interval::new(2, 8), Interval::new(8, 12), None)]
+    #[case(Interval::new(2, 8), Interval::new(3, 4), Some(Interval::new(3, 4)))]
+    fn test_interval_intersection(#[case] a: Interval<u32>, #[case] b: Interval<u32>, #[case] expected: Option<Interval<u32>>) {
+        assert_eq!(a.intersection(&b), expected);
+        assert_eq!(b.intersection(&a), expected);
+    }
+
+    #[rstest]
+    #[case(5, Some(Interval::new(2, 5)), Some(Interval::new(5, 8)))]
+    #[case(2, None, Some(Interval::new(2, 8)))]
+    #[case(8, Some(Interval::new(2, 8)), None)]
+    #[case(20, Some(Interval::new(2, 8)), None)]
+    fn test_interval_split_at(#[case] at: u32, #[case] below: Option<Interval<u32>>, #[case] above: Option<Interval<u32>>) {
+        assert_eq!(Interval::new(2, 8).split_at(at), (below, above));
+    }
+
+    #[test]
+    fn test_interval() {
+        let interval = Interval::inclusive(3u64, 7);
+        assert_eq!((interval.len(), interval.contains(7), interval.contains(8)), (5, true, false));
+        assert_eq!(interval.translate(3, 10), Interval::new(10, 15));
+        assert_eq!(interval.translate(3, 0), Interval::new(0, 5));
+        assert_eq!(Interval::new(5u64, 5).len(), 0);
+        assert_eq!(Interval::from(4..9), Interval::new(4, 9));
+    }
+
+    #[test]
+    fn test_insert_joins_touching_intervals() {
+        assert_eq!(set(&[(1, 3), (3, 5), (8, 9), (0, 2)]).intervals(), [Interval::new(0, 5), Interval::new(8, 9)]);
+        assert_eq!(set(&[(1, 3), (6, 8), (2, 7)]).intervals(), [Interval::new(1, 8)]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 5), (25, 30)]));
+        assert_eq!(a.split_at(22), (set(&[(0, 10), (20, 22)]), set(&[(22, 30)])));
+        assert_eq!(a.translate(0, 5), set(&[(5, 15), (25, 35)]));
+        assert_eq!((a.len(), a.min(), a.contains(20), a.contains(10)), (20, Some(0), true, false));
+    }
+
+    #[test]
+    fn test_region() {
+        let brick = Region::new([Interval::inclusive(0u64, 2), Interval::inclusive(0, 0), Interval::inclusive(1, 1)]);
+        let other = Region::new([Interval::inclusive(1u64, 1), Interval::inclusive(0, 2), Interval::inclusive(1, 3)]);
+        assert_eq!(brick.volume(), 3);
+        assert_eq!(brick.intersection(&other), Some(Region::new([Interval::new(1, 2), Interval::new(0, 1), Interval::new(1, 2)])));
+        assert!(!brick.overlaps(&Region::new([Interval::new(0, 3), Interval::new(1, 2), Interval::new(1, 2)])));
+        let (below, above) = other.split_at(2, 3);
+        assert_eq!((below.map(|r| r.volume()), above.map(|r| r.volume())), (Some(6), Some(3)));
+        assert_eq!(other.split_at(0, 5), (Some(other), None));
+    }
+
+    proptest! {
+        #[test]
+        fn test_set_operations_match_sets_of_numbers(a in sets(), b in sets(), at in 0..70u32) {
+            let (mine, theirs) = (numbers(&a), numbers(&b));
+            prop_assert_eq!(numbers(&a.union(&b)), mine.union(&theirs).copied().collect::<BTreeSet<u32>>());
+            prop_assert_eq!(numbers(&a.intersection(&b)), mine.intersection(&theirs).copied().collect::<BTreeSet<u32>>());
+            prop_assert_eq!(numbers(&a.difference(&b)), mine.difference(&theirs).copied().collect::<BTreeSet<u32>>());
+            let (below, above) = a.split_at(at);
+            prop_assert_eq!(numbers(&below), mine.iter().copied().filter(|n| *n < at).collect::<BTreeSet<u32>>());
+            prop_assert_eq!(numbers(&above), mine.iter().copied().filter(|n| *n >= at).collect::<BTreeSet<u32>>());
+            prop_assert_eq!(a.len() as usize, mine.len());
+            prop_assert!((0..70).all(|n| a.contains(n) == mine.contains(&n)));
+        }
+
+        #[test]
+        fn test_results_stay_tidy(a in sets(), b in sets()) {
+            // Every result is as few intervals as it can be: in order, none empty and none touching
+            for result in [a.union(&b), a.intersection(&b), a.difference(&b), a.split_at(30).0, a.split_at(30).1] {
+                prop_assert!(result.intervals().iter().all(|interval| !interval.is_empty()));
+                prop_assert!(result.intervals().windows(2).all(|pair| pair[0].end < pair[1].start));
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Small parsers for reading the puzzle input, which can be put together to read a whole line. Each works on a slice
+//! of a [`Line`], so that when one fails the [`ParseError`] points at the exact place in the line that was wrong.
+
+use std::str::FromStr;
+use crate::common::{parse_number, ParseError};
+
+/// A line of the input, and where it is in the input, so that anything parsed from a slice of it can say where a
+/// problem is. The fragments passed to its parsers must be slices of `text`.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Line<'a> {
+    /// Counts from 0, as it does from enumerate()
+    pub index: usize,
+    /// The whole line, without its line ending
+    pub text: &'a str
+}
+
+impl<'a> Line<'a> {
+    /// The line at `index`, counting from 0.
+    pub fn new(index: usize, text: &'a str) -> Line<'a> {
+        Line { index, text }
+    }
+
+    /// An error pointing at the start of `fragment`.
+    pub fn error(&self, fragment: &str, message: impl Into<String>) -> ParseError {
+        ParseError::in_line(self.index, self.text, fragment, message)
+    }
+
+    /// The fragment as a number.
+    pub fn number<T: FromStr>(&self, fragment: &str) -> Result<T, ParseError> {
+        parse_number(self.index, self.text, fragment)
+    }
+
+    /// Every number in the fragment, with whitespace between them.
+    pub fn numbers<T: FromStr>(&self, fragment: &'a str) -> Result<Vec<T>, ParseError> {
+        self.words(fragment, |word| self.number(word))
+    }
+
+    /// The rest of the fragment after `prefix`, or an error with the message if it doesn't start with it.
+    pub fn prefixed(&self, fragment: &'a str, prefix: &str, message: impl Into<String>) -> Result<&'a str, ParseError> {
+        fragment.strip_prefix(prefix).ok_or_else(|| self.error(fragment, message))
+    }
+
+    /// What's between `open` and `close`, or an error with the message if the fragment doesn't start and end with
+    /// them.
+    pub fn delimited(&self, fragment: &'a str, open: &str, close: &str, message: impl Into<String>) -> Result<&'a str, ParseError> {
+        fragment.strip_prefix(open)
+            .and_then(|inside| inside.strip_suffix(close))
+            .ok_or_else(|| self.error(fragment, message))
+    }
+
+    /// What's before and after the first `separator`, or an error with the message if it isn't there.
+    pub fn split_once(&self, fragment: &'a str, separator: &str, message: impl Into<String>) -> Result<(&'a str, &'a str), ParseError> {
+        fragment.split_once(separator).ok_or_else(|| self.error(fragment, message))
+    }
+
+    /// Each piece of the fragment between the separators, trimmed and then parsed with `item`.
+    pub fn list<T>(&self, fragment: &'a str, separator: &str, item: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        fragment.split(separator).map(str::trim).map(item).collect()
+    }
+
+    /// Each word in the fragment, with whitespace between them, parsed with `item`.
+    pub fn words<T>(&self, fragment: &'a str, item: impl FnMut(&'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        fragment.split_whitespace().map(item).collect()
+    }
+
+    /// A list of keys and values, like `x=1,m=2`, with each key and value parsed together by `item`.
+    pub fn pairs<T>(&self, fragment: &'a str, separator: &str, between: &str, mut item: impl FnMut(&'a str, &'a str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        self.list(fragment, separator, |pair| {
+            let (key, value) = self.split_once(pair, between, format!("expected a key and a value with \"{}\" between them", between))?;
+            item(key, value)
+        })
+    }
+}
+
+/// Every line of the input.
+pub fn lines(input: &str) -> impl Iterator<Item=Line<'_>> {
+    input.lines().enumerate().map(|(index, text)| Line::new(index, text))
+}
+
+/// The lines of the input in blocks, where each block ends at a blank line. There are no empty blocks, however many
+/// blank lines there are between them.
+pub fn blocks(input: &str) -> Vec<Vec<Line<'_>>> {
+    let mut blocks: Vec<Vec<Line>> = vec![Vec::new()];
+    for line in lines(input) {
+        if line.text.trim().is_empty() {
+            blocks.push(Vec::new());
+        } else {
+            blocks.last_mut().unwrap().push(line);
+        }
+    }
+    blocks.retain(|block| !block.is_empty());
+    blocks
+}
+
+/// An error for something that should have been in the input but wasn't, pointing after the last line.
+pub fn missing(input: &str, message: impl Into<String>) -> ParseError {
+    ParseError::new(input.lines().count(), 1, "", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::common::ParseError;
+    use crate::common::parse::{blocks, Line, lines, missing};
+
+    const TEXT: &str = "key: {a=1, b=22}";
+
+    fn line() -> Line<'static> {
+        Line::new(2, TEXT)
+    }
+
+    #[test]
+    fn test_combinators() {
+        let line = line();
+        let (key, value) = line.split_once(TEXT, ": ", "expected a colon").unwrap();
+        assert_eq!(line.prefixed(key, "ke", "expected ke"), Ok("y"));
+        let inside = line.delimited(value, "{", "}", "expected braces").unwrap();
+        let pairs = line.pairs(inside, ",", "=", |key, value| Ok((key, line.number::<u32>(value)?)));
+        assert_eq!(pairs, Ok(vec![("a", 1), ("b", 22)]));
+        assert_eq!(Line::new(0, " 3  4 5").numbers::<u8>(" 3  4 5"), Ok(vec![3, 4, 5]));
+    }
+
+    #[rstest]
+    #[case(line().split_once(TEXT, "=>", "expected an arrow").map(|(key, _)| key), ParseError::new(3, 1, TEXT, "expected an arrow"))]
+    #[case(line().prefixed(&TEXT[5..], "(", "expected a bracket"), ParseError::new(3, 6, TEXT, "expected a bracket"))]
+    #[case(line().delimited(&TEXT[5..15], "{", "}", "expected braces"), ParseError::new(3, 6, TEXT, "expected braces"))]
+    #[case(line().numbers::<u32>(&TEXT[5..]).map(|_| ""), ParseError::new(3, 6, TEXT, "expected a number, found \"{a=1,\""))]
+    fn test_errors_point_at_the_problem(#[case] result: Result<&str, ParseError>, #[case] expected: ParseError) {
+        assert_eq!(result, Err(expected));
+    }
+
+    #[test]
+    fn test_list_errors_point_at_the_item() {
+        let line = line();
+        let inside = &TEXT[6..15];
+        assert_eq!(line.pairs(inside, ",", ":", |key, _| Ok(key)), Err(ParseError::new(3, 7, TEXT, "expected a key and a value with \":\" between them")));
+        assert_eq!(line.list(inside, ",", |item| line.number::<u8>(item)), Err(ParseError::new(3, 7, TEXT, "expected a number, found \"a=1\"")));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let input = "a\nb\n\n\nc\n\n";
+        let blocks = blocks(input);
+        assert_eq!(blocks, vec![vec![Line::new(0, "a"), Line::new(1, "b")], vec![Line::new(4, "c")]]);
+        assert_eq!(lines(input).count(), 6);
+        assert_eq!(missing(input, "expected d"), ParseError::new(6, 1, "", "expected d"));
+    }
+}
@@ -0,0 +1,286 @@
+//! The four ways to move on a map, and the points they move between. A [`Point`] can be anywhere, such as on a map
+//! that goes on forever, while a [`Position`] can't go above or left of the top left, like a cell in a grid.
+
+use std::ops::{Add, AddAssign, Mul, Sub};
+use crate::common::point::Direction::{East, North, South, West};
+
+/// One of the four ways to move on a map. North is up, towards row 0.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum Direction {
+    /// Up, towards row 0.
+    North,
+    /// Right, towards higher columns.
+    East,
+    /// Down, towards higher rows.
+    South,
+    /// Left, towards column 0.
+    West
+}
+
+/// A quarter turn, as seen by whoever is facing the direction being turned.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum Turn {
+    /// Anticlockwise, as seen on the map.
+    Left,
+    /// Clockwise, as seen on the map.
+    Right
+}
+
+impl Direction {
+    /// Every direction, clockwise from north.
+    pub const ALL: [Direction; 4] = [North, East, South, West];
+
+    /// The direction faced after making the turn.
+    pub fn turn(self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right()
+        }
+    }
+
+    /// The direction a quarter turn anticlockwise.
+    pub fn turn_left(self) -> Direction {
+        match self {
+            North => West,
+            East => North,
+            South => East,
+            West => South
+        }
+    }
+
+    /// The direction a quarter turn clockwise.
+    pub fn turn_right(self) -> Direction {
+        match self {
+            North => East,
+            East => South,
+            South => West,
+            West => North
+        }
+    }
+
+    /// The opposite direction.
+    pub fn reverse(self) -> Direction {
+        match self {
+            North => South,
+            East => West,
+            South => North,
+            West => East
+        }
+    }
+
+    /// The rows and columns moved by one step this way.
+    pub fn offset(self) -> Point {
+        match self {
+            North => Point { row: -1, column: 0 },
+            East => Point { row: 0, column: 1 },
+            South => Point { row: 1, column: 0 },
+            West => Point { row: 0, column: -1 }
+        }
+    }
+}
+
+/// A point that can be anywhere, or how far apart two points are.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, Default)]
+pub struct Point {
+    /// How far down, with negative numbers above row 0.
+    pub row: isize,
+    /// How far right, with negative numbers left of column 0.
+    pub column: isize
+}
+
+impl Point {
+    /// The point at this row and column.
+    pub fn new(row: isize, column: isize) -> Point {
+        Point { row, column }
+    }
+
+    /// The point one step away in the direction.
+    pub fn step(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+
+    /// The point `steps` steps away in the direction, or None if it's too far away to hold.
+    pub fn step_by(self, direction: Direction, steps: isize) -> Option<Point> {
+        let offset = direction.offset();
+        Some(Point {
+            row: self.row.checked_add(offset.row.checked_mul(steps)?)?,
+            column: self.column.checked_add(offset.column.checked_mul(steps)?)?
+        })
+    }
+
+    /// The number of steps to get here from the origin, without going diagonally.
+    pub fn manhattan(self) -> isize {
+        self.row.abs() + self.column.abs()
+    }
+
+    /// The cross product of the two points as vectors from the origin, which is twice the signed area of the triangle
+    /// they make with it.
+    pub fn cross(self, other: Point) -> isize {
+        self.row * other.column - self.column * other.row
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point { row: self.row + other.row, column: self.column + other.column }
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point { row: self.row - other.row, column: self.column - other.column }
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, times: isize) -> Point {
+        Point { row: self.row * times, column: self.column * times }
+    }
+}
+
+impl From<Position> for Point {
+    fn from(position: Position) -> Point {
+        Point { row: position.row as isize, column: position.column as isize }
+    }
+}
+
+/// A point that's never above or left of the top left, such as a cell in a grid. Converts to and from the
+/// `(row, column)` that [`crate::common::grid::Grid`] uses.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy, Default)]
+pub struct Position {
+    /// How far down from the top.
+    pub row: usize,
+    /// How far right from the left edge.
+    pub column: usize
+}
+
+impl Position {
+    /// The position at this row and column.
+    pub fn new(row: usize, column: usize) -> Position {
+        Position { row, column }
+    }
+
+    /// The position `by` rows and columns away, if that isn't above or left of the top left.
+    pub fn add_signed(self, by: Point) -> Option<Position> {
+        Some(Position { row: self.row.checked_add_signed(by.row)?, column: self.column.checked_add_signed(by.column)? })
+    }
+
+    /// The position one step away in the direction, if that isn't above or left of the top left.
+    pub fn step(self, direction: Direction) -> Option<Position> {
+        self.add_signed(direction.offset())
+    }
+
+    /// The position one step away in the direction, if that's still inside `height` rows and `width` columns.
+    pub fn step_within(self, direction: Direction, height: usize, width: usize) -> Option<Position> {
+        self.step(direction).filter(|next| next.row < height && next.column < width)
+    }
+}
+
+impl From<(usize, usize)> for Position {
+    fn from((row, column): (usize, usize)) -> Position {
+        Position { row, column }
+    }
+}
+
+impl From<Position> for (usize, usize) {
+    fn from(position: Position) -> (usize, usize) {
+        (position.row, position.column)
+    }
+}
+
+impl TryFrom<Point> for Position {
+    type Error = Point;
+
+    /// The point as a position, or the point back again if it's above or left of the top left.
+    fn try_from(point: Point) -> Result<Position, Point> {
+        match (usize::try_from(point.row), usize::try_from(point.column)) {
+            (Ok(row), Ok(column)) => Ok(Position { row, column }),
+            _ => Err(point)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{prop_assert_eq, proptest};
+    use proptest::sample::select;
+    use rstest::rstest;
+    use crate::common::point::{Direction, Point, Position, Turn};
+    use crate::common::point::Direction::{East, North, South, West};
+
+    #[rstest]
+    #[case(North, Turn::Left, West)]
+    #[case(North, Turn::Right, East)]
+    #[case(East, Turn::Right, South)]
+    #[case(West, Turn::Left, South)]
+    fn test_turn(#[case] direction: Direction, #[case] turn: Turn, #[case] expected: Direction) {
+        assert_eq!(direction.turn(turn), expected);
+    }
+
+    #[test]
+    fn test_reverse() {
+        assert_eq!(Direction::ALL.map(Direction::reverse), [South, West, North, East]);
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let point = Point::new(3, -2);
+        assert_eq!(point.step(North), Point::new(2, -2));
+        assert_eq!(point + West.offset() * 4, Point::new(3, -6));
+        assert_eq!(point.step_by(West, 4), Some(Point::new(3, -6)));
+        assert_eq!(point.step_by(South, isize::MAX), None);
+        assert_eq!(point - Point::new(5, 5), Point::new(-2, -7));
+        assert_eq!(point.manhattan(), 5);
+        assert_eq!(Point::new(0, 6).cross(Point::new(5, 6)), -30);
+    }
+
+    #[rstest]
+    #[case(Position::new(0, 0), North, None)]
+    #[case(Position::new(0, 0), West, None)]
+    #[case(Position::new(0, 0), South, Some(Position::new(1, 0)))]
+    #[case(Position::new(2, 1), East, Some(Position::new(2, 2)))]
+    #[case(Position::new(2, 2), East, None)]
+    #[case(Position::new(2, 2), South, None)]
+    fn test_step_within(#[case] position: Position, #[case] direction: Direction, #[case] expected: Option<Position>) {
+        assert_eq!(position.step_within(direction, 3, 3), expected);
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Position::from((4, 7)), Position::new(4, 7));
+        assert_eq!(<(usize, usize)>::from(Position::new(4, 7)), (4, 7));
+        assert_eq!(Point::from(Position::new(4, 7)), Point::new(4, 7));
+        assert_eq!(Position::try_from(Point::new(4, 7)), Ok(Position::new(4, 7)));
+        assert_eq!(Position::try_from(Point::new(-1, 7)), Err(Point::new(-1, 7)));
+    }
+
+    proptest! {
+        #[test]
+        fn test_turns(direction in select(Direction::ALL.to_vec())) {
+            prop_assert_eq!(direction.turn_left().turn_right(), direction);
+            prop_assert_eq!(direction.turn_right().turn_right(), direction.reverse());
+            prop_assert_eq!(direction.turn_left().turn_left().turn_left().turn_left(), direction);
+            prop_assert_eq!(direction.offset() + direction.reverse().offset(), Point::default());
+        }
+
+        #[test]
+        fn test_step_and_back(row in 0..100usize, column in 0..100usize, direction in select(Direction::ALL.to_vec())) {
+            let position = Position::new(row, column);
+            let back = position.step(direction).and_then(|next| next.step(direction.reverse()));
+            let expected = Position::try_from(Point::from(position).step(direction)).ok().map(|_| position);
+            prop_assert_eq!(back, expected);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! Best-first search for the cheapest way from one state to another, which is Dijkstra's algorithm, or A* when the
+//! states can give a heuristic.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A state in a search, such as where something is on a map and anything else that limits where it can go next. The
+/// `Context` is whatever the states move around in, such as the map itself, so it doesn't have to be in every state.
+pub trait SearchState: Clone + Eq + Hash {
+    /// What the states move around in.
+    type Context: ?Sized;
+    /// What a move costs, such as a number of steps. Costs are added up along the way, and the smallest total wins.
+    type Cost: Copy + Ord + Default + Add<Output=Self::Cost>;
+
+    /// The states one move away, and what each move costs. No move can cost less than nothing.
+    fn successors(&self, context: &Self::Context) -> Vec<(Self, Self::Cost)>;
+
+    /// Whether the search can stop here. There can be any number of goals.
+    fn is_goal(&self, context: &Self::Context) -> bool;
+
+    /// A guess at the cost to the nearest goal, which makes the search A*. It must never guess too high, and shouldn't
+    /// drop by more than the cost of a move, or the search might not find the cheapest way. Guesses nothing by default,
+    /// which is plain Dijkstra.
+    fn heuristic(&self, _context: &Self::Context) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+/// The cheapest way to a goal, and every state on the way there, from the start it began at to the goal.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Found<S: SearchState> {
+    /// The total cost of the moves from the start to the goal.
+    pub cost: S::Cost,
+    /// The start, the goal, and every state in between, in order.
+    pub path: Vec<S>
+}
+
+/// Finds the cheapest way from any of the starts to any goal, or None if no goal can be reached.
+pub fn search<S: SearchState>(context: &S::Context, starts: impl IntoIterator<Item=S>) -> Option<Found<S>> {
+    let mut search = Search::new(context, starts);
+    while let Some(index) = search.next() {
+        if search.states[index].is_goal(context) {
+            return Some(Found { cost: search.costs[index], path: search.path_to(index) });
+        }
+    }
+    None
+}
+
+/// The least cost to get to every state that can be reached from the starts, which there must be a limited number of.
+pub fn costs<S: SearchState>(context: &S::Context, starts: impl IntoIterator<Item=S>) -> HashMap<S, S::Cost> {
+    let mut search = Search::new(context, starts);
+    while search.next().is_some() {}
+    search.states.into_iter().zip(search.costs).collect()
+}
+
+// The guess at the total cost, the cost so far and the index of a state waiting to be looked at. Reversed, so the
+// heap gives the smallest guess first.
+type Queued<C> = Reverse<(C, C, usize)>;
+
+// Each state seen is kept once, and the rest refer to it by where it is in `states`
+struct Search<'a, S: SearchState> {
+    context: &'a S::Context,
+    states: Vec<S>,
+    indices: HashMap<S, usize>,
+    costs: Vec<S::Cost>,
+    previous: Vec<Option<usize>>,
+    // The states to look at next, cheapest guess first. An index can be in here more than once, if a cheaper way to
+    // it was found after it was added, but only the cheapest is used.
+    queue: BinaryHeap<Queued<S::Cost>>
+}
+
+impl<'a, S: SearchState> Search<'a, S> {
+    fn new(context: &'a S::Context, starts: impl IntoIterator<Item=S>) -> Search<'a, S> {
+        let mut search = Search {
+            context,
+            states: Vec::new(),
+            indices: HashMap::new(),
+            costs: Vec::new(),
+            previous: Vec::new(),
+            queue: BinaryHeap::new()
+        };
+        for start in starts {
+            search.reach(start, S::Cost::default(), None);
+        }
+        search
+    }
+
+    // Notes a way to the state, if it's cheaper than any found so far
+    fn reach(&mut self, state: S, cost: S::Cost, previous: Option<usize>) {
+        let index = match self.indices.get(&state) {
+            Some(&index) if self.costs[index] <= cost => return,
+            Some(&index) => {
+                self.costs[index] = cost;
+                self.previous[index] = previous;
+                index
+            },
+            None => {
+                let index = self.states.len();
+                self.indices.insert(state.clone(), index);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.previous.push(previous);
+                index
+            }
+        };
+        let guess = cost + self.states[index].heuristic(self.context);
+        self.queue.push(Reverse((guess, cost, index)));
+    }
+
+    // The next state to be reached as cheaply as it can be, after adding the states next to it
+    fn next(&mut self) -> Option<usize> {
+        while let Some(Reverse((_, cost, index))) = self.queue.pop() {
+            if cost > self.costs[index] {
+                continue;
+            }
+            for (next, step) in self.states[index].successors(self.context) {
+                self.reach(next, cost + step, Some(index));
+            }
+            return Some(index);
+        }
+        None
+    }
+
+    fn path_to(&self, index: usize) -> Vec<S> {
+        let mut path: Vec<S> = Vec::new();
+        let mut current = Some(index);
+        while let Some(index) = current {
+            path.push(self.states[index].clone());
+            current = self.previous[index];
+        }
+        path.reverse();
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use proptest::{prop_assert_eq, proptest};
+    use proptest::collection::vec as prop_vec;
+    use crate::common::grid::Grid;
+    use crate::common::search::{costs, search, Found, SearchState};
+
+    // Moving around a grid of digits, paying each digit on the way in. 0 is a wall.
+    #[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+    struct Walker {
+        position: (usize, usize),
+        guided: bool
+    }
+
+    impl SearchState for Walker {
+        type Context = Grid<u32>;
+        type Cost = u32;
+
+        fn successors(&self, grid: &Grid<u32>) -> Vec<(Walker, u32)> {
+            grid.neighbours4(self.position)
+                .filter(|next| grid[*next] > 0)
+                .map(|position| (Walker { position, ..*self }, grid[position]))
+                .collect()
+        }
+
+        fn is_goal(&self, grid: &Grid<u32>) -> bool {
+            self.position == (grid.height() - 1, grid.width() - 1)
+        }
+
+        // Every step costs at least one, so the distance to the corner never guesses too high
+        fn heuristic(&self, grid: &Grid<u32>) -> u32 {
+            if self.guided {
+                (grid.height() - 1 - self.position.0 + grid.width() - 1 - self.position.1) as u32
+            } else {
+                0
+            }
+        }
+    }
+
+    fn grid(text: &str) -> Grid<u32> {
+        Grid::parse(text, |c| c.to_digit(10)).unwrap()
+    }
+
+    fn walker(row: usize, column: usize) -> Walker {
+        Walker { position: (row, column), guided: false }
+    }
+
+    #[test]
+    fn test_search() {
+        let grid = grid("1191\n1911\n1101\n9111");
+        let found = search(&grid, [walker(0, 0)]);
+        let path = [(0, 0), (1, 0), (2, 0), (2, 1), (3, 1), (3, 2), (3, 3)].map(|(row, column)| walker(row, column));
+        assert_eq!(found, Some(Found { cost: 6, path: path.to_vec() }));
+    }
+
+    #[test]
+    fn test_search_from_several_starts() {
+        let grid = grid("119\n991\n111");
+        let found = search(&grid, [walker(0, 0), walker(1, 2)]).unwrap();
+        assert_eq!((found.cost, found.path.first()), (1, Some(&walker(1, 2))));
+    }
+
+    #[test]
+    fn test_search_without_a_way() {
+        assert_eq!(search(&grid("11\n10"), [walker(0, 0)]), None);
+    }
+
+    #[test]
+    fn test_costs() {
+        let expected = HashMap::from([(walker(0, 0), 0), (walker(0, 1), 2), (walker(1, 0), 3), (walker(1, 1), 6)]);
+        assert_eq!(costs(&grid("12\n34"), [walker(0, 0)]), expected);
+    }
+
+    proptest! {
+        #[test]
+        fn test_heuristic_finds_the_same_cost(width in 1..8usize, cells in prop_vec(1..10u32, 1..64)) {
+            let height = cells.len() / width;
+            if height > 0 {
+                let grid = Grid::new(height, width, cells[..height * width].to_vec());
+                let plain = search(&grid, [walker(0, 0)]).map(|found| found.cost);
+                let guided = search(&grid, [Walker { position: (0, 0), guided: true }]).map(|found| found.cost);
+                prop_assert_eq!(plain, guided);
+            }
+        }
+    }
+}
@@ -1,13 +1,49 @@
+//! Day 1: Trebuchet?!
+//!
+//! Finds the first and last digit on each line, with part two also counting digits that are spelled out.
+
 use std::collections::HashMap;
 use once_cell;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use crate::common::load_from;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::lines;
+use crate::solution::Solution;
+
+/// Sums the calibration values. The parsed input is just the text, as each part reads the lines differently.
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed = String;
+    type PartOne = i32;
+    type PartTwo = i32;
+
+    fn parse(&self, input: &str) -> Result<String, ParseError> {
+        check_lines(input)?;
+        Ok(input.to_string())
+    }
 
-pub fn run_day() {
-    let data = load_from("day01.txt");
-    println!("Part 1: {}", day01a(data.as_str()));
-    println!("Part 2: {}", day01b(data.as_str()));
+    fn part_one(&self, data: &String) -> i32 {
+        day01a(data.as_str())
+    }
+
+    fn part_two(&self, data: &String) -> i32 {
+        day01b(data.as_str())
+    }
+}
+
+// Every line needs a digit, or a digit spelled out, to have a calibration value in part two. Part one also needs a
+// real digit on every line, which its examples don't all have, so that's left to part one.
+fn check_lines(data: &str) -> Result<(), ParseError> {
+    let any_digit = Regex::new(r"\d|one|two|three|four|five|six|seven|eight|nine").unwrap();
+    lines(data)
+        .find(|line| !any_digit.is_match(line.text))
+        .map_or(Ok(()), |line| Err(line.error(line.text.trim_start(), "expected a digit, or a digit spelled out, like 7 or seven")))
 }
 
 fn day01a(data: &str) -> i32 {
@@ -107,10 +143,28 @@ fn number_replacement(str: &str) -> Vec<String> {
     result_vec
 }
 
+/// Generates `size` lines of calibration values, mixing letters, digits and numbers that are spelled out. Every line
+/// has at least one digit, as part one needs.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    (0..size.max(1)).map(|_| {
+        let mut pieces: Vec<String> = (0..rng.gen_range(1..=6)).map(|_| match rng.gen_range(0..3) {
+            0 => (0..rng.gen_range(1..=4)).map(|_| rng.gen_range('a'..='z')).collect(),
+            1 => rng.gen_range(1..=9).to_string(),
+            _ => WORDS[rng.gen_range(0..WORDS.len())].to_string()
+        }).collect();
+        let at = rng.gen_range(0..=pieces.len());
+        pieces.insert(at, rng.gen_range(1..=9).to_string());
+        pieces.concat()
+    }).join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
-    use crate::day01::{day01a, day01b, digit_scraper, map_string_to_int, number_replacement, parse_lines, split_data_lines};
+    use crate::common::ParseError;
+    use crate::day01::{check_lines, day01a, day01b, digit_scraper, map_string_to_int, number_replacement, parse_lines, split_data_lines};
+    use crate::examples::{DAY01_1, DAY01_2};
 
     #[rstest]
     #[case("1abc2", 12)]
@@ -234,24 +288,26 @@ mod tests {
         assert_eq!(142, parse_lines(input))
     }
 
+    #[rstest]
+    #[case("1abc2\nabc", ParseError::new(2, 1, "abc", "expected a digit, or a digit spelled out, like 7 or seven"))]
+    #[case("  xyz", ParseError::new(1, 3, "  xyz", "expected a digit, or a digit spelled out, like 7 or seven"))]
+    fn test_check_lines_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(check_lines(input), Err(expected));
+    }
+
+    #[test]
+    fn test_check_lines() {
+        assert_eq!(check_lines(DAY01_1), Ok(()));
+        assert_eq!(check_lines(DAY01_2), Ok(()));
+    }
+
     #[test]
     fn test_day01a() {
-        let input = "1abc2
-                        pqr3stu8vwx
-                        a1b2c3d4e5f
-                        treb7uchet";
-        assert_eq!(142, day01a(input))
+        assert_eq!(142, day01a(DAY01_1))
     }
 
     #[test]
     fn test_day01b() {
-        let input = "two1nine
-                    eightwothree
-                    abcone2threexyz
-                    xtwone3four
-                    4nineeightseven2
-                    zoneight234
-                    7pqrstsixteen";
-        assert_eq!(281, day01b(input))
+        assert_eq!(281, day01b(DAY01_2))
     }
 }
\ No newline at end of file
@@ -1,14 +1,37 @@
+//! Day 2: Cube Conundrum
+//!
+//! Works out which games are possible with a given bag of cubes, and the smallest bag each game needs.
+
 use std::collections::HashMap;
-use std::str::Lines;
-use once_cell::sync::Lazy;
-use regex::Regex;
-use crate::common::load_from;
-
-pub fn run_day() {
-    let data = load_from("day02.txt");
-    let games = parse_lines(data.lines());
-    println!("Part 1: {}", day02a(&games));
-    println!("Part 2: {}", day02b(&games));
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines};
+use crate::solution::Solution;
+
+/// Sums the possible games (with 12 red, 13 green and 14 blue cubes) and the power of each game's smallest bag.
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Parsed = Vec<Game>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Game>, ParseError> {
+        parse_lines(input)
+    }
+
+    fn part_one(&self, games: &Vec<Game>) -> u32 {
+        day02a(games)
+    }
+
+    fn part_two(&self, games: &Vec<Game>) -> u32 {
+        day02b(games)
+    }
 }
 
 fn day02a(input: &Vec<Game>) -> u32 {
@@ -26,8 +49,9 @@ fn day02b(input: &Vec<Game>) -> u32 {
         .sum()
 }
 
+/// One game, with the cubes that were shown in each set.
 #[derive(Debug, PartialEq)]
-struct Game {
+pub struct Game {
     index: u32,
     sets: Vec<Set>
 }
@@ -39,43 +63,43 @@ struct Set {
     blue: u8
 }
 
-fn parse_lines(lines: Lines) -> Vec<Game> {
-    lines.map(parse_line).collect()
+fn parse_lines(input: &str) -> Result<Vec<Game>, ParseError> {
+    lines(input).map(parse_line).collect()
 }
 
-fn parse_line(line: &str) -> Game {
-    let split_line = line.split_once(":").unwrap();
-    Game {
-        index: parse_game_index(split_line.0),
-        sets: parse_sets(split_line.1)
-    }
+// The line is passed down so that any errors can point at the right place.
+fn parse_line(line: Line) -> Result<Game, ParseError> {
+    let (game, sets) = line.split_once(line.text, ":", "expected a colon after the game number")?;
+    Ok(Game {
+        index: parse_game_index(line, game)?,
+        sets: parse_sets(line, sets)?
+    })
 }
 
-static BALL_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+) (red|green|blue)").unwrap());
-
-fn parse_game_index(game: &str) -> u32 {
-    str::parse::<u32>(game.replace("Game ", "").as_str()).unwrap()
+fn parse_game_index(line: Line, game: &str) -> Result<u32, ParseError> {
+    line.number(line.prefixed(game, "Game ", "expected the line to start with Game")?)
 }
 
-fn parse_sets(sets: &str) -> Vec<Set> {
-    sets.split(";").map(|x| parse_set(x.trim())).collect()
+fn parse_sets<'a>(line: Line<'a>, sets: &'a str) -> Result<Vec<Set>, ParseError> {
+    line.list(sets, ";", |set| parse_set(line, set))
 }
 
-fn parse_set(set: &str) -> Set {
-    let split_set_string = set.split(",").map(|s| s.trim());
-    let mut map: HashMap<String, u8> = HashMap::new();
-
-    for entry in split_set_string {
-        let captures = BALL_REGEX.captures(entry).unwrap();
-        // Gets the groups, assumes they are there, adds the colour to the map and the number.
-        map.insert(captures.get(2).unwrap().as_str().to_string(), str::parse::<u8>(captures.get(1).unwrap().as_str()).unwrap());
-    }
+fn parse_set<'a>(line: Line<'a>, set: &'a str) -> Result<Set, ParseError> {
+    let balls = line.list(set, ",", |entry| {
+        let wrong = || line.error(entry, format!("expected a number of red, green or blue balls, found \"{}\"", entry));
+        let (number, colour) = entry.split_once(" ").ok_or_else(wrong)?;
+        if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) || !["red", "green", "blue"].contains(&colour) {
+            return Err(wrong());
+        }
+        Ok((colour, line.number::<u8>(number)?))
+    })?;
+    let map: HashMap<&str, u8> = balls.into_iter().collect();
 
-    Set {
+    Ok(Set {
         red: map.get("red").map(|x| x.clone()).unwrap_or(0),
         green: map.get("green").map(|x| x.clone()).unwrap_or(0),
         blue: map.get("blue").map(|x| x.clone()).unwrap_or(0)
-    }
+    })
 }
 
 impl Game {
@@ -95,13 +119,30 @@ impl Game {
 
 }
 
+/// Generates `size` games, each with up to six handfuls of between one and twenty cubes of each colour.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (1..=size.max(1)).map(|game| {
+        let sets = (0..rng.gen_range(1..=6)).map(|_| {
+            let mut colours = vec!["red", "green", "blue"];
+            colours.shuffle(rng);
+            colours.truncate(rng.gen_range(1..=3));
+            colours.iter().map(|colour| format!("{} {}", rng.gen_range(1..=20), colour)).join(", ")
+        }).join("; ");
+        format!("Game {}: {}", game, sets)
+    }).join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use once_cell::sync::Lazy;
     use proptest::collection::{vec as prop_vec};
     use proptest::{prop_assert_eq, prop_compose, proptest};
     use proptest::strategy::Just;
+    use rstest::rstest;
+    use crate::common::ParseError;
+    use crate::common::parse::Line;
     use crate::day02::{parse_set, parse_game_index, Set, Game, parse_sets, parse_line, parse_lines, day02a, day02b};
+    use crate::examples::DAY02;
 
     impl Set {
         fn get_string(&self) -> String {
@@ -134,7 +175,7 @@ mod tests {
         #[test]
         fn test_parse_set(input in set_strategy()) {
             let string_to_parse = input.get_string();
-            prop_assert_eq!(parse_set(string_to_parse.as_str()), input);
+            prop_assert_eq!(parse_set(Line::new(0, string_to_parse.as_str()), string_to_parse.as_str()), Ok(input));
         }
     }
 
@@ -142,7 +183,7 @@ mod tests {
         #[test]
         fn test_parse_game_index(input in 1..=100u32) {
             let a = format!("Game {}", input);
-            prop_assert_eq!(parse_game_index(a.as_str()), input);
+            prop_assert_eq!(parse_game_index(Line::new(0, a.as_str()), a.as_str()), Ok(input));
         }
     }
 
@@ -151,7 +192,7 @@ mod tests {
         fn test_parse_sets(input in prop_vec(set_strategy(), 1..=5)) {
             let string_to_parse: String = input.iter().map(|x| x.get_string()).collect::<Vec<String>>().join("; ");
             println!("{}", string_to_parse);
-            let mut sets = parse_sets(string_to_parse.as_str());
+            let mut sets = parse_sets(Line::new(0, string_to_parse.as_str()), string_to_parse.as_str()).unwrap();
             let mut sorted_input = input; // taking ownership here as we won't use the original after this
             // sorting is done on the vecs themselves, so return unit.
             sets.sort();
@@ -164,8 +205,8 @@ mod tests {
         #[test]
         fn test_parse_game_line(sets in prop_vec(set_strategy(), 1..=5), index in 1..=100u32) {
             let string_to_parse: String = format!("Game {index}: {}", sets.iter().map(|x| x.get_string()).collect::<Vec<String>>().join("; "));
-            let game = parse_line(string_to_parse.as_str());
-            prop_assert_eq!(Game { index, sets }, game);
+            let game = parse_line(Line::new(0, string_to_parse.as_str()));
+            prop_assert_eq!(Ok(Game { index, sets }), game);
         }
     }
 
@@ -213,12 +254,17 @@ mod tests {
 
     #[test]
     fn test_parsing_example() {
-        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
-        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
-        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n\
-        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n\
-        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        assert_eq!(parse_lines(input.lines()), *Lazy::force(&EXAMPLE_DATA))
+        assert_eq!(parse_lines(DAY02).unwrap(), *Lazy::force(&EXAMPLE_DATA))
+    }
+
+    #[rstest]
+    #[case("Game 1 3 blue", ParseError::new(1, 1, "Game 1 3 blue", "expected a colon after the game number"))]
+    #[case("Round 1: 3 blue", ParseError::new(1, 1, "Round 1: 3 blue", "expected the line to start with Game"))]
+    #[case("Game x: 3 blue", ParseError::new(1, 6, "Game x: 3 blue", "expected a number, found \"x\""))]
+    #[case("Game 1: 3 blue; 4 purple", ParseError::new(1, 17, "Game 1: 3 blue; 4 purple", "expected a number of red, green or blue balls, found \"4 purple\""))]
+    #[case("Game 1: 300 blue", ParseError::new(1, 9, "Game 1: 300 blue", "expected a number, found \"300\""))]
+    fn test_parse_errors(#[case] line: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_line(Line::new(0, line)), Err(expected));
     }
 
     #[test]
@@ -1,72 +1,85 @@
-use std::collections::HashSet;
-use structopt::lazy_static::lazy_static;
-use regex::Regex;
-use crate::common::load_from;
+//! Day 3: Gear Ratios
+//!
+//! Finds the part numbers on the engine schematic that sit next to a symbol, and the gears with exactly two of them.
 
+use std::collections::HashSet;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::grid::Grid;
+use crate::common::point::{Point, Position};
+use crate::common::ParseError;
+use crate::solution::Solution;
+
+/// A number on the schematic and where it is.
 #[derive(Debug, PartialEq)]
-struct Code {
+pub struct Code {
     code: u32,
-    positions: Vec<Coord>
+    positions: Vec<Position>
 }
 
+/// A symbol on the schematic. `*` is a possible gear.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Symbol {
+pub struct Symbol {
     is_gear: bool,
-    coord: Coord
-}
-
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-struct Coord {
-    row: usize,
-    col: usize
+    position: Position
 }
 
 impl Code {
-    fn is_valid_in(&self, valid_coords: &HashSet<Coord>) -> bool {
+    fn is_valid_in(&self, valid_coords: &HashSet<Position>) -> bool {
         self.positions.iter().any(|coord| valid_coords.contains(coord))
     }
 }
 
-impl Coord {
-    fn surrounding(&self) -> Vec<Coord> {
-        let row_range = self.row.checked_sub(1).unwrap_or(self.row)..=(self.row + 1);
-        let col_range = self.col.checked_sub(1).unwrap_or(self.col)..=(self.col + 1);
+// The position and the eight around it, in reading order, leaving out any above or left of the top left
+fn surrounding(position: Position) -> Vec<Position> {
+    (-1..=1).cartesian_product(-1..=1)
+        .filter_map(|(row, column)| position.add_signed(Point::new(row, column)))
+        .collect()
+}
 
-        let mut vec: Vec<Coord> = Vec::new();
-        for row in row_range {
-            for col in col_range.clone() { // avoids moving the original
-                vec.push(Coord { row, col });
-            }
-        }
-        vec
+/// The numbers in the schematic, its symbols and where the symbols are.
+pub type Schematic = (Vec<Code>, HashSet<Symbol>, HashSet<Position>);
+
+/// Parses the schematic into its numbers, symbols and the positions of the symbols, then sums the part numbers
+/// and the gear ratios.
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+
+    type Parsed = Schematic;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(&self, input: &str) -> Result<Schematic, ParseError> {
+        load_data(input)
+    }
+
+    fn part_one(&self, parsed: &Schematic) -> u32 {
+        day03a(&parsed.0, &parsed.2)
     }
-}
 
-pub fn run_day() {
-    let data = load_from("day03.txt");
-    let (codes, symbol_locations, symbol_coords) = load_data(data.as_str());
-    println!("Part 1: {}", day03a(&codes, &symbol_coords));
-    println!("Part 2: {}", day03b(&codes, &symbol_locations));
+    fn part_two(&self, parsed: &Schematic) -> u32 {
+        day03b(&parsed.0, &parsed.1)
+    }
 }
 
-fn load_data(input: &str) -> (Vec<Code>, HashSet<Symbol>, HashSet<Coord>) {
+fn load_data(input: &str) -> Result<Schematic, ParseError> {
+    // Letters aren't numbers or symbols, so they can't be in a schematic
+    let schematic = Grid::parse(input, |c| (c.is_ascii_graphic() && !c.is_ascii_alphabetic()).then_some(c))?;
     let mut codes: Vec<Code> = Vec::new();
     let mut symbol_locations: HashSet<Symbol> = HashSet::new();
-    for (idx, line) in input.lines().enumerate() {
-        let (line_codes, line_symbols) = read_line(idx, line);
+    for (idx, row) in schematic.rows().enumerate() {
+        let (line_codes, line_symbols) = read_line(idx, row)?;
         codes.extend(line_codes);
         symbol_locations.extend(line_symbols);
     }
-    let symbol_coords: HashSet<Coord> = get_surrounding_coords_from_symbols(&symbol_locations);
-    (codes, symbol_locations, symbol_coords)
+    let symbol_coords: HashSet<Position> = get_surrounding_coords_from_symbols(&symbol_locations);
+    Ok((codes, symbol_locations, symbol_coords))
 }
 
-lazy_static! {
-    static ref SYMBOL_REGEX: Regex = Regex::new(r"[^a-zA-Z0-9\.]").unwrap();
-    static ref DIGIT_REGEX: Regex = Regex::new(r"\d+").unwrap();
-}
-
-fn day03a(codes: &Vec<Code>, symbols: &HashSet<Coord>) -> u32 {
+fn day03a(codes: &[Code], symbols: &HashSet<Position>) -> u32 {
     codes.iter().filter(|code| code.is_valid_in(&symbols)).map(|code| code.code).sum()
 }
 
@@ -79,7 +92,7 @@ fn day03b(code: &Vec<Code>, symbol: &HashSet<Symbol>) -> u32 {
 }
 
 fn select_codes_from_gear(symbol: &Symbol, codes: &Vec<Code>) -> u32 {
-    let coords = symbol.coord.surrounding();
+    let coords = surrounding(symbol.position);
     let mut items: Vec<u32> = Vec::new();
     for code in codes {
         if coords.iter().any(|c| code.positions.contains(c)) {
@@ -95,36 +108,74 @@ fn select_codes_from_gear(symbol: &Symbol, codes: &Vec<Code>) -> u32 {
     }
 }
 
-fn read_line(line_no: usize, line: &str) -> (Vec<Code>, HashSet<Symbol>) {
-    (
-        read_numbers(line_no, line),
-        read_symbols(line_no, line)
-    )
+fn read_line(line_no: usize, row: &[char]) -> Result<(Vec<Code>, HashSet<Symbol>), ParseError> {
+    Ok((
+        read_numbers(line_no, row)?,
+        read_symbols(line_no, row)
+    ))
 }
 
-fn read_symbols(line_no: usize, line: &str) -> HashSet<Symbol> {
-    SYMBOL_REGEX
-        .find_iter(line)
-        .into_iter()
-        .map(|col| Symbol { is_gear: col.as_str() == "*", coord: Coord { row: line_no, col: col.start() } })
+// Anything that isn't a letter, a digit or a '.' is a symbol
+fn read_symbols(line_no: usize, row: &[char]) -> HashSet<Symbol> {
+    row.iter()
+        .enumerate()
+        .filter(|(_, c)| !c.is_ascii_alphanumeric() && **c != '.')
+        .map(|(col, c)| Symbol { is_gear: *c == '*', position: Position { row: line_no, column: col } })
         .collect()
 }
 
-fn get_surrounding_coords_from_symbols(symbols: &HashSet<Symbol>) -> HashSet<Coord> {
-    symbols.iter().flat_map(|symbol| symbol.coord.surrounding()).collect()
+fn get_surrounding_coords_from_symbols(symbols: &HashSet<Symbol>) -> HashSet<Position> {
+    symbols.iter().flat_map(|symbol| surrounding(symbol.position)).collect()
+}
+
+fn read_numbers(line_no: usize, row: &[char]) -> Result<Vec<Code>, ParseError> {
+    let mut codes: Vec<Code> = Vec::new();
+    let mut col = 0;
+    while col < row.len() {
+        let length = row[col..].iter().take_while(|c| c.is_ascii_digit()).count();
+        if length == 0 {
+            col += 1;
+            continue;
+        }
+        let digits: String = row[col..col + length].iter().collect();
+        let code = digits.parse::<u32>().map_err(|_| {
+            ParseError::new(line_no + 1, col + 1, &row.iter().collect::<String>(), format!("expected a number, found \"{}\"", digits))
+        })?;
+        codes.push(Code {
+            code,
+            positions: (col..col + length).map(|col| Position { row: line_no, column: col }).collect()
+        });
+        col += length;
+    }
+    Ok(codes)
 }
 
-fn read_numbers(line_no: usize, line: &str) -> Vec<Code> {
-    DIGIT_REGEX
-        .find_iter(line)
-        .map(|m| {
-            let positions: Vec<Coord> = m.range().into_iter().map(|r| Coord { row: line_no, col: r }).collect();
-            Code {
-                code: str::parse::<u32>(m.as_str()).unwrap(),
-                positions
+/// Generates a `size` by `size` schematic. There are never more than 4000 `*` symbols, so that the gear ratios add up
+/// to less than a u32 can hold.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const SYMBOLS: [char; 9] = ['#', '$', '%', '&', '+', '-', '/', '=', '@'];
+    let size = size.max(3);
+    let mut stars = 0;
+    (0..size).map(|_| {
+        let mut line = String::new();
+        while line.len() < size {
+            match rng.gen_range(0..10) {
+                // Each number is followed by a '.', so it doesn't run into the next one
+                0 | 1 if size - line.len() >= 4 => {
+                    line.push_str(rng.gen_range(1..=999).to_string().as_str());
+                    line.push('.');
+                }
+                2 if stars < 4000 && rng.gen_bool(0.4) => {
+                    stars += 1;
+                    line.push('*');
+                }
+                2 => line.push(SYMBOLS[rng.gen_range(0..SYMBOLS.len())]),
+                _ => line.push('.')
             }
-        })
-        .collect()
+        }
+        line.truncate(size);
+        line
+    }).join("\n")
 }
 
 #[cfg(test)]
@@ -134,12 +185,19 @@ mod test {
     use proptest::bool::{ANY as ANY_BOOL};
     use proptest::strategy::Just;
     use proptest::collection::{vec as prop_vec};
-    use crate::day03::{Code, Coord, day03a, day03b, load_data, read_numbers, read_symbols, Symbol};
+    use crate::common::ParseError;
+    use crate::common::point::Position;
+    use crate::day03::{Code, day03a, day03b, load_data, read_numbers, read_symbols, surrounding, Symbol};
+    use crate::examples::DAY03 as INPUT_EXAMPLE;
+
+    fn chars(line: &str) -> Vec<char> {
+        line.chars().collect()
+    }
 
     #[test]
     fn test_symbols_none() {
         let sample = ".....";
-        let coords = read_symbols(1, sample);
+        let coords = read_symbols(1, &chars(sample));
         let expected: HashSet<Symbol> = HashSet::new();
 
         assert_eq!(coords, expected);
@@ -148,11 +206,11 @@ mod test {
     #[test]
     fn test_symbols_one() {
         let sample = "#....";
-        let coords = read_symbols(1, sample);
+        let coords = read_symbols(1, &chars(sample));
         let mut expected: HashSet<Symbol> = HashSet::new();
         expected.insert(Symbol {
             is_gear: false,
-            coord: Coord { row: 1, col: 0 }
+            position: Position { row: 1, column: 0 }
         });
 
         assert_eq!(coords, expected);
@@ -161,11 +219,11 @@ mod test {
     #[test]
     fn test_symbols_one_gear() {
         let sample = "..*..";
-        let coords = read_symbols(2, sample);
+        let coords = read_symbols(2, &chars(sample));
         let mut expected: HashSet<Symbol> = HashSet::new();
         expected.insert(Symbol {
             is_gear: true,
-            coord: Coord { row: 2, col: 2 }
+            position: Position { row: 2, column: 2 }
         });
 
         assert_eq!(coords, expected);
@@ -175,9 +233,9 @@ mod test {
         fn symbol_strategy(row: u8)(is_gear in ANY_BOOL, col in ..50usize) -> Symbol {
             Symbol {
                 is_gear,
-                coord: Coord {
+                position: Position {
                     row: row as usize,
-                    col
+                    column: col
                 }
             }
         }
@@ -190,8 +248,8 @@ mod test {
             let mut s = symbols;
             // we need to dedup by column, note that the is_gear might be different so we have to do it manually
             // rather than by just using a set.
-            s.sort_by_key(|x| x.coord.col);
-            s.dedup_by_key(|x1| x1.coord.col);
+            s.sort_by_key(|x| x.position.column);
+            s.dedup_by_key(|x1| x1.position.column);
             (row, HashSet::from_iter(s))
         }
     }
@@ -202,7 +260,7 @@ mod test {
             let (row, symbols) = row_and_symbols;
             let mut sample = ["."; 50];
             for i in &symbols {
-                sample[i.coord.col] = if i.is_gear {
+                sample[i.position.column] = if i.is_gear {
                     "*"
                 } else {
                     "#"
@@ -211,7 +269,7 @@ mod test {
             let result_string = sample.join("");
             println!("{}", result_string);
 
-            let result = read_symbols(row as usize, result_string.as_str());
+            let result = read_symbols(row as usize, &chars(&result_string));
             prop_assert_eq!(symbols, result);
         }
     }
@@ -219,87 +277,82 @@ mod test {
     #[test]
     fn test_coord_surroundings_normal() {
         let expected = vec![
-            Coord { row: 0, col: 0 },
-            Coord { row: 0, col: 1 },
-            Coord { row: 0, col: 2 },
-            Coord { row: 1, col: 0 },
-            Coord { row: 1, col: 1 },
-            Coord { row: 1, col: 2 },
-            Coord { row: 2, col: 0 },
-            Coord { row: 2, col: 1 },
-            Coord { row: 2, col: 2 }
+            Position { row: 0, column: 0 },
+            Position { row: 0, column: 1 },
+            Position { row: 0, column: 2 },
+            Position { row: 1, column: 0 },
+            Position { row: 1, column: 1 },
+            Position { row: 1, column: 2 },
+            Position { row: 2, column: 0 },
+            Position { row: 2, column: 1 },
+            Position { row: 2, column: 2 }
         ];
-        assert_eq!(Coord { row: 1, col: 1 }.surrounding(), expected);
+        assert_eq!(surrounding(Position { row: 1, column: 1 }), expected);
     }
 
     #[test]
     fn test_coord_surroundings_top() {
         let expected = vec![
-            Coord { row: 0, col: 0 },
-            Coord { row: 0, col: 1 },
-            Coord { row: 0, col: 2 },
-            Coord { row: 1, col: 0 },
-            Coord { row: 1, col: 1 },
-            Coord { row: 1, col: 2 }
+            Position { row: 0, column: 0 },
+            Position { row: 0, column: 1 },
+            Position { row: 0, column: 2 },
+            Position { row: 1, column: 0 },
+            Position { row: 1, column: 1 },
+            Position { row: 1, column: 2 }
         ];
-        assert_eq!(Coord { row: 0, col: 1 }.surrounding(), expected);
+        assert_eq!(surrounding(Position { row: 0, column: 1 }), expected);
     }
 
     #[test]
     fn test_coord_surroundings_left() {
         let expected = vec![
-            Coord { row: 0, col: 0 },
-            Coord { row: 0, col: 1 },
-            Coord { row: 1, col: 0 },
-            Coord { row: 1, col: 1 },
-            Coord { row: 2, col: 0 },
-            Coord { row: 2, col: 1 }
+            Position { row: 0, column: 0 },
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 },
+            Position { row: 1, column: 1 },
+            Position { row: 2, column: 0 },
+            Position { row: 2, column: 1 }
         ];
-        assert_eq!(Coord { row: 1, col: 0 }.surrounding(), expected);
+        assert_eq!(surrounding(Position { row: 1, column: 0 }), expected);
     }
 
     #[test]
     fn test_coord_surroundings_top_left() {
         let expected = vec![
-            Coord { row: 0, col: 0 },
-            Coord { row: 0, col: 1 },
-            Coord { row: 1, col: 0 },
-            Coord { row: 1, col: 1 }
+            Position { row: 0, column: 0 },
+            Position { row: 0, column: 1 },
+            Position { row: 1, column: 0 },
+            Position { row: 1, column: 1 }
         ];
-        assert_eq!(Coord { row: 0, col: 0 }.surrounding(), expected);
+        assert_eq!(surrounding(Position { row: 0, column: 0 }), expected);
     }
 
     #[test]
     fn test_read_numbers() {
         let line = "..123..*32..$.45..";
         let expected: Vec<Code> = vec![
-            Code { code: 123, positions: vec![Coord { row: 0, col: 2 }, Coord { row: 0, col: 3 }, Coord { row: 0, col: 4 }] },
-            Code { code: 32, positions: vec![Coord { row: 0, col: 8 }, Coord { row: 0, col: 9 }]},
-            Code { code: 45, positions: vec![Coord { row: 0, col: 14 }, Coord { row: 0, col: 15 }] },
+            Code { code: 123, positions: vec![Position { row: 0, column: 2 }, Position { row: 0, column: 3 }, Position { row: 0, column: 4 }] },
+            Code { code: 32, positions: vec![Position { row: 0, column: 8 }, Position { row: 0, column: 9 }]},
+            Code { code: 45, positions: vec![Position { row: 0, column: 14 }, Position { row: 0, column: 15 }] },
         ];
-        assert_eq!(read_numbers(0, line), expected);
+        assert_eq!(read_numbers(0, &chars(line)), Ok(expected));
     }
 
-    const INPUT_EXAMPLE: &str = "467..114..\n\
-                                ...*......\n\
-                                ..35..633.\n\
-                                ......#...\n\
-                                617*......\n\
-                                .....+.58.\n\
-                                ..592.....\n\
-                                ......755.\n\
-                                ...$.*....\n\
-                                .664.598..";
+    #[test]
+    fn test_load_data_errors() {
+        assert_eq!(load_data("467..\n..*ab"), Err(ParseError::new(2, 4, "..*ab", "unexpected 'a'")));
+        assert_eq!(load_data("*.99999999999"), Err(ParseError::new(1, 3, "*.99999999999", "expected a number, found \"99999999999\"")));
+    }
 
     #[test]
     fn test_day03a() {
-        let (codes, _, symbol_coords) = load_data(INPUT_EXAMPLE);
+        let (codes, _, symbol_coords) = load_data(INPUT_EXAMPLE).unwrap();
         assert_eq!(day03a(&codes, &symbol_coords), 4361);
     }
 
     #[test]
     fn test_day03b() {
-        let (codes, symbol_locations, _) = load_data(INPUT_EXAMPLE);
+        let (codes, symbol_locations, _) = load_data(INPUT_EXAMPLE).unwrap();
         assert_eq!(day03b(&codes, &symbol_locations), 467835);
     }
 
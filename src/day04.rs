@@ -1,29 +1,65 @@
-use crate::common::load_from;
+//! Day 4: Scratchcards
+//!
+//! Scores the winning numbers on each card, then counts the copies won by each card in part two.
 
-pub fn run_day() {
-    let string = load_from("day04.txt");
-    let cards = parse_lines(string.as_str());
-    println!("Part 1: {}", day04a(&cards));
-    println!("Part 2: {}", day04b(&cards));
-}
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines};
+use crate::solution::Solution;
+
+/// Sums the points on the cards, then counts the total number of cards won.
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Parsed = Vec<Card>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<Card>, ParseError> {
+        parse_lines(input)
+    }
+
+    fn part_one(&self, cards: &Vec<Card>) -> u32 {
+        day04a(cards)
+    }
 
-fn parse_lines(string: &str) -> Vec<Card> {
-    string.lines().map(|x| parse_line(x)).collect()
+    fn part_two(&self, cards: &Vec<Card>) -> u32 {
+        day04b(cards)
+    }
 }
 
-fn parse_line(line: &str) -> Card {
-    let (card, numbers) = line.split_once(":").unwrap();
-    let (wins, potentials) = numbers.split_once("|").unwrap();
-    let card_no_string = card.split_whitespace().last().unwrap();
-    Card {
-        index: str::parse::<u32>(card_no_string).unwrap(),
-        winning_numbers: wins.trim().split_whitespace().map(|x| str::parse::<u32>(x).unwrap()).collect(),
-        card_numbers: potentials.trim().split_whitespace().map(|x| str::parse::<u32>(x).unwrap()).collect()
+fn parse_lines(string: &str) -> Result<Vec<Card>, ParseError> {
+    let lines: Vec<Line> = lines(string).collect();
+    let cards: Vec<Card> = lines.iter().map(|line| parse_line(*line)).collect::<Result<_, _>>()?;
+    // A card can't win copies of cards past the end of the table
+    for (line, card) in lines.iter().zip(cards.iter()) {
+        if line.index + card.wins() as usize >= cards.len() && card.wins() > 0 {
+            return Err(line.error(line.text, "the card wins copies of cards past the end of the table"));
+        }
     }
+    Ok(cards)
 }
 
+fn parse_line(line: Line) -> Result<Card, ParseError> {
+    let (card, numbers) = line.split_once(line.text, ":", "expected a card and its numbers, like Card 1: 41 48 | 83 86")?;
+    let (wins, potentials) = line.split_once(numbers, "|", "expected the winning numbers and the numbers on the card, with a | between them")?;
+    let card_no = line.prefixed(card, "Card", "expected a card, like Card 1")?.trim_start();
+    Ok(Card {
+        index: line.number(card_no)?,
+        winning_numbers: line.numbers(wins)?,
+        card_numbers: line.numbers(potentials)?
+    })
+}
+
+/// A scratchcard's winning numbers and the numbers on it.
 #[derive(Debug, PartialEq)]
-struct Card {
+pub struct Card {
     index: u32,
     winning_numbers: Vec<u32>,
     card_numbers: Vec<u32>
@@ -67,35 +103,59 @@ fn get_card_distribution(input: &Vec<Card>) -> Vec<u32> {
     card_numbers
 }
 
+/// Generates `size` cards with ten winning numbers and twenty five numbers each. No card wins copies of cards past the
+/// end, and the wins are kept down so that the total number of cards fits in a u32.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(1);
+    let width = size.to_string().len();
+    // How many copies of each card there will be, so far
+    let mut copies = vec![1u64; size];
+    let mut total = size as u64;
+    (0..size).map(|index| {
+        let mut wins = if rng.gen_bool(0.5) { 0 } else { rng.gen_range(1..=10usize).min(size - 1 - index) };
+        while wins > 0 && total + copies[index] * wins as u64 > 1_000_000_000 {
+            wins -= 1;
+        }
+        for next in index + 1..=index + wins {
+            copies[next] += copies[index];
+        }
+        total += copies[index] * wins as u64;
+
+        // The first ten numbers are the winning ones, and the card has `wins` of them
+        let numbers: Vec<usize> = sample(rng, 99, 35).into_iter().map(|n| n + 1).collect();
+        let mut card: Vec<usize> = numbers[..wins].iter().chain(numbers[10..35 - wins].iter()).copied().collect();
+        card.shuffle(rng);
+        let format = |numbers: &[usize]| numbers.iter().map(|n| format!("{:>2}", n)).join(" ");
+        format!("Card {:>width$}: {} | {}", index + 1, format(&numbers[..10]), format(&card), width = width)
+    }).join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
     use proptest::proptest;
     use proptest::collection::{hash_set, vec as prop_vec};
+    use rstest::rstest;
+    use crate::common::ParseError;
+    use crate::common::parse::Line;
     use crate::day04::{Card, day04a, day04b, get_card_distribution, parse_line, parse_lines};
-
-    const TEST_DATA: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\n\
-                             Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n\
-                             Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1\n\
-                             Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83\n\
-                             Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36\n\
-                             Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+    use crate::examples::DAY04 as TEST_DATA;
 
     #[test]
     fn test_day_04a() {
-        let cards = parse_lines(TEST_DATA);
+        let cards = parse_lines(TEST_DATA).unwrap();
         assert_eq!(day04a(&cards), 13);
     }
 
     #[test]
     fn test_day_04b() {
-        let cards = parse_lines(TEST_DATA);
+        let cards = parse_lines(TEST_DATA).unwrap();
         assert_eq!(day04b(&cards), 30);
     }
 
     #[test]
     fn test_get_card_distribution() {
-        let cards = parse_lines(TEST_DATA);
+        let cards = parse_lines(TEST_DATA).unwrap();
         let expected: Vec<u32> = vec![
             1,
             2,
@@ -141,7 +201,17 @@ mod tests {
                 card_numbers: vec![74, 77, 10, 23, 35, 67, 36, 11]
             }
         ];
-        assert_eq!(parse_lines(TEST_DATA), expected);
+        assert_eq!(parse_lines(TEST_DATA).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("Card 1: 41 48 | 83 86\nCard 2 13 32 | 61 30", ParseError::new(2, 1, "Card 2 13 32 | 61 30", "expected a card and its numbers, like Card 1: 41 48 | 83 86"))]
+    #[case("Card 1: 41 48 83 86", ParseError::new(1, 8, "Card 1: 41 48 83 86", "expected the winning numbers and the numbers on the card, with a | between them"))]
+    #[case("Game 1: 41 48 | 83 86", ParseError::new(1, 1, "Game 1: 41 48 | 83 86", "expected a card, like Card 1"))]
+    #[case("Card 1: 41 4x | 83 86", ParseError::new(1, 12, "Card 1: 41 4x | 83 86", "expected a number, found \"4x\""))]
+    #[case("Card 1: 13 | 61\nCard 2: 41 48 | 41 86", ParseError::new(2, 1, "Card 2: 41 48 | 41 86", "the card wins copies of cards past the end of the table"))]
+    fn test_parse_lines_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_lines(input), Err(expected));
     }
 
     fn join_numbers(numbers: &Vec<u32>) -> String {
@@ -157,7 +227,7 @@ mod tests {
                 winning_numbers: winning,
                 card_numbers: cards
             };
-            assert_eq!(parse_line(line.as_str()), expected);
+            assert_eq!(parse_line(Line::new(0, line.as_str())), Ok(expected));
         }
     }
 
@@ -1,46 +1,95 @@
-use std::collections::VecDeque;
-use std::ops::Range;
-use crate::common::load_from;
-
-pub fn run_day() {
-    let data = load_from("day05.txt");
-    let parsed_data: ParsedData = parse_lines(data.as_str());
-    println!("Part 1: {}", day05a(&parsed_data));
-    println!("Part 2: {}", day05b(&parsed_data));
-}
-
-fn parse_lines(str: &str) -> ParsedData {
-    let lines = str.lines();
-
-    // seeds = line 1
-    let mut seeds: Vec<u64> = vec![];
+//! Day 5: If You Give A Seed A Fertilizer
+//!
+//! Follows each seed through the almanac's maps. Part two follows whole ranges of seeds through them at once, cutting the
+//! ranges up wherever a map's ranges start and end.
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::cancel;
+use crate::common::ParseError;
+use crate::common::parse::{blocks, missing};
+use crate::common::interval::{Interval, IntervalSet};
+use crate::differential::Comparison;
+use crate::solution::Solution;
+
+/// Finds the lowest location for the seeds, and for the seed ranges in part two.
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Parsed = ParsedData;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<ParsedData, ParseError> {
+        parse_lines(input)
+    }
 
-    let mut mappings: VecDeque<Vec<Mapping>> = VecDeque::new();
+    fn part_one(&self, parsed_data: &ParsedData) -> u64 {
+        day05a(parsed_data)
+    }
 
-    for line in lines {
-        if line.starts_with("seeds: ") {
-            seeds = line.split_once(":").unwrap().1.trim().split_whitespace().map(|x| str::parse::<u64>(x).unwrap()).collect();
-        } else if line.contains("map:") {
-            mappings.push_back(vec![]);
-        } else if !line.is_empty() {
-            let [final_no, initial_no, range] = line.split_whitespace().map(|x| str::parse::<u64>(x).unwrap()).collect::<Vec<u64>>()[0..3] else { panic!("should not happen") };
-            let m = mappings.back_mut().unwrap();
-            m.push(Mapping { initial_range: initial_no..(initial_no + range), final_start: final_no });
-        }
+    fn part_two(&self, parsed_data: &ParsedData) -> u64 {
+        day05b(parsed_data)
     }
+}
 
-    // to ensure the borrow checker doesn't complain, we pop the vector from the enclosing vector, moving
-    // the values to here.
-    ParsedData {
-        seeds,
-        seed_to_soil: mappings.pop_front().unwrap(),
-        soil_to_fertilizer: mappings.pop_front().unwrap(),
-        fertilizer_to_water: mappings.pop_front().unwrap(),
-        water_to_light: mappings.pop_front().unwrap(),
-        light_to_temperature: mappings.pop_front().unwrap(),
-        temperature_to_humidity: mappings.pop_front().unwrap(),
-        humidity_to_location: mappings.pop_front().unwrap()
+fn parse_lines(str: &str) -> Result<ParsedData, ParseError> {
+    let blocks = blocks(str);
+    let Some((first, maps)) = blocks.split_first() else {
+        return Err(missing(str, "expected the seeds"));
+    };
+    // The seeds are on a line of their own, with the maps after them
+    let seeds_line = &first[0];
+    let listed = seeds_line.prefixed(seeds_line.text, "seeds: ", "expected the seeds, like seeds: 79 14")?;
+    let seeds = seeds_line.words(listed, |word| Ok((word, seeds_line.number::<u64>(word)?)))?;
+    if seeds.is_empty() {
+        return Err(seeds_line.error(listed, "expected at least one seed"));
     }
+    // Part two reads them as pairs of the first seed and how many there are
+    let seed_ranges = seeds.iter().tuples().map(|(&(_, first), &(word, length))| {
+        first.checked_add(length).map(|end| Interval::new(first, end))
+            .ok_or_else(|| seeds_line.error(word, "the range of seeds runs past the largest number there can be"))
+    }).collect::<Result<IntervalSet<u64>, ParseError>>()?;
+    if let Some(line) = first.get(1) {
+        return Err(line.error(line.text, "found a mapping before the first map"));
+    }
+
+    let mappings = maps.iter().map(|map| {
+        let (title, lines) = map.split_first().unwrap();
+        title.split_once(title.text, " map:", "expected the name of a map, like seed-to-soil map:")?;
+        lines.iter().map(|line| {
+            let numbers = line.words(line.text, |word| Ok((word, line.number::<u64>(word)?)))?;
+            let [(_, final_no), (_, initial_no), (word, range)] = numbers[..] else {
+                return Err(line.error(line.text, "expected the destination start, source start and range length"));
+            };
+            // Both ends of the range have to fit, so that the numbers can be moved from one to the other
+            let too_long = || line.error(word, "the range runs past the largest number there can be");
+            let initial_end = initial_no.checked_add(range).ok_or_else(too_long)?;
+            final_no.checked_add(range).ok_or_else(too_long)?;
+            Ok(Mapping { initial_range: Interval::new(initial_no, initial_end), final_start: final_no })
+        }).collect::<Result<Vec<Mapping>, ParseError>>()
+    }).collect::<Result<Vec<Vec<Mapping>>, ParseError>>()?;
+
+    let found = mappings.len();
+    let Ok([seed_to_soil, soil_to_fertilizer, fertilizer_to_water, water_to_light, light_to_temperature, temperature_to_humidity, humidity_to_location]) = <[Vec<Mapping>; 7]>::try_from(mappings) else {
+        return Err(missing(str, format!("expected 7 maps, found {}", found)));
+    };
+    Ok(ParsedData {
+        seeds: seeds.into_iter().map(|(_, seed)| seed).collect(),
+        seed_ranges,
+        seed_to_soil,
+        soil_to_fertilizer,
+        fertilizer_to_water,
+        water_to_light,
+        light_to_temperature,
+        temperature_to_humidity,
+        humidity_to_location
+    })
 }
 
 fn walk_data(seed: u64, parsed_data: &ParsedData) -> u64 {
@@ -62,34 +111,51 @@ fn get_mapping(value: &u64, mappings: &Vec<Mapping>) -> u64 {
     value.clone()
 }
 
-fn seed_pairs(seeds: &Vec<u64>) -> Vec<Range<u64>> {
-    let steps = seeds[1..].iter().step_by(2);
-    seeds.iter().step_by(2).zip(steps).map(|(first, second)| {
-        first.clone()..(first.clone()+second.clone())
-    }).collect()
-}
-
 fn day05a(parsed_data: &ParsedData) -> u64 {
-    parsed_data.seeds.iter().map(|x| walk_data(x.clone(), parsed_data)).min().unwrap()
+    parsed_data.seeds.iter().map(|&seed| walk_data(seed, parsed_data)).min().expect("there are no seeds")
 }
 
 fn day05b(parsed_data: &ParsedData) -> u64 {
-    let seeds = seed_pairs(&parsed_data.seeds);
-    walk_backwards(&seeds, parsed_data)
+    let locations = parsed_data.maps().iter().fold(parsed_data.seed_ranges.clone(), |values, mappings| map_ranges(&values, mappings));
+    locations.min().expect("there are no seeds")
 }
 
-fn walk_backwards(seeds: &Vec<Range<u64>>, parsed_data: &ParsedData) -> u64 {
+// Where the numbers in the set go with the map. Each mapping takes whatever's in its range that no mapping before it
+// took, and anything no mapping takes stays where it is.
+fn map_ranges(values: &IntervalSet<u64>, mappings: &[Mapping]) -> IntervalSet<u64> {
+    let mut unmapped = values.clone();
+    let mut mapped = IntervalSet::new();
+    for mapping in mappings {
+        let (below, rest) = unmapped.split_at(mapping.initial_range.start);
+        let (inside, above) = rest.split_at(mapping.initial_range.end);
+        mapped = mapped.union(&inside.translate(mapping.initial_range.start, mapping.final_start));
+        unmapped = below.union(&above);
+    }
+    mapped.union(&unmapped)
+}
+
+// The simple way to do part two: walk backwards from location 0 until it lands in a seed range. This only works if
+// every number maps to exactly one other, as it does in the real almanac.
+fn walk_backwards(seeds: &IntervalSet<u64>, parsed_data: &ParsedData) -> u64 {
     let reverse_mappings = parsed_data.reverse();
 
     for i in 0..u64::MAX {
+        cancel::check();
         let seed = walk_backwards_with_data(&i, &reverse_mappings);
-        if seeds.iter().any(|x| x.contains(&seed)) {
+        if seeds.contains(seed) {
             return i
         }
     }
     panic!("Couldn't find a thing")
 }
 
+/// Part two by following the ranges through the maps, and then by walking backwards from location 0, for the
+/// differential tests.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let parsed_data = parse_lines(input)?;
+    Ok(Some((day05b(&parsed_data).to_string(), walk_backwards(&parsed_data.seed_ranges, &parsed_data).to_string())))
+}
+
 fn walk_backwards_with_data(location: &u64, parsed_data: &ReverseParsedData) -> u64 {
     let humidity = get_mapping(location, &parsed_data.location_to_humidity);
     let temperature = get_mapping(&humidity, &parsed_data.humidity_to_temperature);
@@ -100,9 +166,12 @@ fn walk_backwards_with_data(location: &u64, parsed_data: &ReverseParsedData) ->
     get_mapping(&soil, &parsed_data.soil_to_seed)
 }
 
+/// The seeds, the ranges of seeds they make for part two, and each of the almanac's maps, in the order they are
+/// followed.
 #[derive(PartialEq, Debug)]
-struct ParsedData {
+pub struct ParsedData {
     seeds: Vec<u64>,
+    seed_ranges: IntervalSet<u64>,
     seed_to_soil: Vec<Mapping>,
     soil_to_fertilizer: Vec<Mapping>,
     fertilizer_to_water: Vec<Mapping>,
@@ -114,6 +183,22 @@ struct ParsedData {
 
 impl ParsedData {
 
+    /// The seeds listed at the top of the almanac.
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// The location that a seed ends up at after following every map.
+    pub fn location(&self, seed: u64) -> u64 {
+        walk_data(seed, self)
+    }
+
+    // The maps in the order they're followed
+    fn maps(&self) -> [&Vec<Mapping>; 7] {
+        [&self.seed_to_soil, &self.soil_to_fertilizer, &self.fertilizer_to_water, &self.water_to_light,
+            &self.light_to_temperature, &self.temperature_to_humidity, &self.humidity_to_location]
+    }
+
     fn reverse(&self) -> ReverseParsedData {
         fn generate_mappings(forward_mappings: &Vec<Mapping>) -> Vec<Mapping> {
             let mut reverse_mappings: Vec<Mapping> = Vec::new();
@@ -150,117 +235,151 @@ struct ReverseParsedData {
 
 #[derive(PartialEq, Debug)]
 struct Mapping {
-    initial_range: Range<u64>,
+    initial_range: Interval<u64>,
     final_start: u64
 }
 
 impl Mapping {
     fn map_if_valid(&self, initial: &u64) -> Option<u64> {
-        self.initial_range.contains(initial).then(|| self.final_start + (initial - self.initial_range.start))
+        self.initial_range.contains(*initial).then(|| self.final_start + (initial - self.initial_range.start))
     }
 
     fn reverse_mapping(&self) -> Mapping {
         Mapping {
-            initial_range: self.final_start..self.final_start+(self.initial_range.end-self.initial_range.start),
+            initial_range: self.initial_range.translate(self.initial_range.start, self.final_start),
             final_start: self.initial_range.start
         }
     }
 }
 
+/// Generates an almanac with four seed ranges, where each map cuts the numbers below 1000 * `size` into `size` ranges
+/// and moves them about. Every number maps to exactly one other, as in the real almanac, which the simple way of doing
+/// part two relies on when it walks backwards.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const MAPS: [&str; 7] = ["seed-to-soil", "soil-to-fertilizer", "fertilizer-to-water", "water-to-light",
+        "light-to-temperature", "temperature-to-humidity", "humidity-to-location"];
+    let size = size.max(1);
+    let limit = 1000 * size;
+
+    let seeds = (0..4).map(|_| {
+        let start = rng.gen_range(0..limit);
+        format!("{} {}", start, rng.gen_range(1..=(limit - start).min(limit / 10).max(1)))
+    }).join(" ");
+
+    let maps = MAPS.iter().map(|name| {
+        let mut cuts: Vec<usize> = sample(rng, limit - 1, size - 1).into_iter().map(|cut| cut + 1).collect();
+        cuts.extend([0, limit]);
+        cuts.sort();
+        let mut pieces: Vec<(usize, usize)> = cuts.windows(2).map(|cut| (cut[0], cut[1] - cut[0])).collect();
+        pieces.shuffle(rng);
+        let mut destination = 0;
+        let mut lines: Vec<String> = pieces.iter().map(|(source, length)| {
+            let line = format!("{} {} {}", destination, source, length);
+            destination += length;
+            line
+        }).collect();
+        lines.shuffle(rng);
+        format!("{} map:\n{}", name, lines.join("\n"))
+    }).join("\n\n");
+
+    format!("seeds: {}\n\n{}", seeds, maps)
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
     use once_cell::sync::Lazy;
     use rstest::rstest;
-    use crate::day05::{day05a, day05b, get_mapping, Mapping, parse_lines, ParsedData, walk_data};
-
-    const TEST_DATA: &str = "seeds: 79 14 55 13\n\
-                            \n\
-                            seed-to-soil map:\n\
-                            50 98 2\n\
-                            52 50 48\n\
-                            \n\
-                            soil-to-fertilizer map:\n\
-                            0 15 37\n\
-                            37 52 2\n\
-                            39 0 15\n\
-                            \n\
-                            fertilizer-to-water map:\n\
-                            49 53 8\n\
-                            0 11 42\n\
-                            42 0 7\n\
-                            57 7 4\n\
-                            \n\
-                            water-to-light map:\n\
-                            88 18 7\n\
-                            18 25 70\n\
-                            \n\
-                            light-to-temperature map:\n\
-                            45 77 23\n\
-                            81 45 19\n\
-                            68 64 13\n\
-                            \n\
-                            temperature-to-humidity map:\n\
-                            0 69 1\n\
-                            1 0 69\n\
-                            \n\
-                            humidity-to-location map:\n\
-                            60 56 37\n\
-                            56 93 4\n\
-                            ";
+    use crate::common::ParseError;
+    use crate::common::interval::Interval;
+    use crate::common::interval::IntervalSet;
+    use crate::day05::{check_shortcut, day05a, day05b, get_mapping, map_ranges, Mapping, parse_lines, ParsedData, walk_data};
+    use crate::examples::DAY05 as TEST_DATA;
 
     static PARSED_DATA: Lazy<ParsedData> = Lazy::new(|| ParsedData {
         seeds: vec![79, 14, 55, 13],
+        seed_ranges: [Interval::new(79, 93), Interval::new(55, 68)].into_iter().collect(),
         seed_to_soil: vec![
-            Mapping { initial_range: 98..100, final_start: 50 },
-            Mapping { initial_range: 50..98, final_start: 52 }
+            Mapping { initial_range: Interval::new(98, 100), final_start: 50 },
+            Mapping { initial_range: Interval::new(50, 98), final_start: 52 }
         ],
         soil_to_fertilizer: vec![
-            Mapping { initial_range: 15..52, final_start: 0 },
-            Mapping { initial_range: 52..54, final_start: 37 },
-            Mapping { initial_range: 0..15, final_start: 39 }
+            Mapping { initial_range: Interval::new(15, 52), final_start: 0 },
+            Mapping { initial_range: Interval::new(52, 54), final_start: 37 },
+            Mapping { initial_range: Interval::new(0, 15), final_start: 39 }
         ],
         fertilizer_to_water: vec![
-            Mapping { initial_range: 53..61, final_start: 49 },
-            Mapping { initial_range: 11..53, final_start: 0 },
-            Mapping { initial_range: 0..7, final_start: 42 },
-            Mapping { initial_range: 7..11, final_start: 57 }
+            Mapping { initial_range: Interval::new(53, 61), final_start: 49 },
+            Mapping { initial_range: Interval::new(11, 53), final_start: 0 },
+            Mapping { initial_range: Interval::new(0, 7), final_start: 42 },
+            Mapping { initial_range: Interval::new(7, 11), final_start: 57 }
         ],
         water_to_light: vec![
-            Mapping { initial_range: 18..25, final_start: 88 },
-            Mapping { initial_range: 25..95, final_start: 18 }
+            Mapping { initial_range: Interval::new(18, 25), final_start: 88 },
+            Mapping { initial_range: Interval::new(25, 95), final_start: 18 }
         ],
         light_to_temperature: vec![
-            Mapping { initial_range: 77..100, final_start: 45 },
-            Mapping { initial_range: 45..64, final_start: 81 },
-            Mapping { initial_range: 64..77, final_start: 68 }
+            Mapping { initial_range: Interval::new(77, 100), final_start: 45 },
+            Mapping { initial_range: Interval::new(45, 64), final_start: 81 },
+            Mapping { initial_range: Interval::new(64, 77), final_start: 68 }
         ],
         temperature_to_humidity: vec![ // nice
-            Mapping { initial_range: 69..70, final_start: 0 },
-            Mapping { initial_range: 0..69, final_start: 1 }
+            Mapping { initial_range: Interval::new(69, 70), final_start: 0 },
+            Mapping { initial_range: Interval::new(0, 69), final_start: 1 }
         ],
         humidity_to_location: vec![
-            Mapping { initial_range: 56..93, final_start: 60 },
-            Mapping { initial_range: 93..97, final_start: 56 }
+            Mapping { initial_range: Interval::new(56, 93), final_start: 60 },
+            Mapping { initial_range: Interval::new(93, 97), final_start: 56 }
         ]
     });
 
     #[test]
     fn test_day05a() {
-        let data = parse_lines(TEST_DATA);
+        let data = parse_lines(TEST_DATA).unwrap();
         assert_eq!(35, day05a(&data));
     }
 
     #[test]
     fn test_day05b() {
-        let data = parse_lines(TEST_DATA);
+        let data = parse_lines(TEST_DATA).unwrap();
         assert_eq!(46, day05b(&data));
     }
 
+    #[test]
+    fn test_map_ranges() {
+        // 0..10 stays where it is, 10..20 goes to 50..60, 20..25 goes to 0..5, and 25..30 is left alone
+        let mappings = vec![
+            Mapping { initial_range: Interval::new(10, 20), final_start: 50 },
+            Mapping { initial_range: Interval::new(20, 25), final_start: 0 }
+        ];
+        let values: IntervalSet<u64> = [Interval::new(5, 30)].into_iter().collect();
+        let expected: IntervalSet<u64> = [Interval::new(0, 5), Interval::new(5, 10), Interval::new(25, 30), Interval::new(50, 60)].into_iter().collect();
+        assert_eq!(map_ranges(&values, &mappings), expected);
+    }
+
+    #[test]
+    fn test_check_shortcut() {
+        assert_eq!(check_shortcut(TEST_DATA), Ok(Some((String::from("46"), String::from("46")))));
+    }
 
     #[test]
     fn test_parse_lines() {
-        assert_eq!(parse_lines(TEST_DATA), *PARSED_DATA.deref());
+        assert_eq!(parse_lines(TEST_DATA).unwrap(), *PARSED_DATA.deref());
+    }
+
+    #[rstest]
+    #[case("seeds: 79 14\n\nseed-to-soil map:\n50 98\n", ParseError::new(4, 1, "50 98", "expected the destination start, source start and range length"))]
+    #[case("seeds: 79 1a\n", ParseError::new(1, 11, "seeds: 79 1a", "expected a number, found \"1a\""))]
+    #[case("seeds: 79 14\n50 98 2\n", ParseError::new(2, 1, "50 98 2", "found a mapping before the first map"))]
+    #[case("seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n", ParseError::new(4, 1, "", "expected 7 maps, found 1"))]
+    #[case("seeds: 79 14\n\nseed-to-soil\n50 98 2\n", ParseError::new(3, 1, "seed-to-soil", "expected the name of a map, like seed-to-soil map:"))]
+    #[case("79 14\n", ParseError::new(1, 1, "79 14", "expected the seeds, like seeds: 79 14"))]
+    #[case("seeds: \n\nseed-to-soil map:\n50 98 2\n", ParseError::new(1, 8, "seeds: ", "expected at least one seed"))]
+    #[case("seeds: 18446744073709551615 1\n", ParseError::new(1, 29, "seeds: 18446744073709551615 1", "the range of seeds runs past the largest number there can be"))]
+    #[case("seeds: 79 14\n\nseed-to-soil map:\n18446744073709551615 0 2\n", ParseError::new(4, 24, "18446744073709551615 0 2", "the range runs past the largest number there can be"))]
+    #[case("seeds: 79 14\n\nseed-to-soil map:\n0 18446744073709551614 2\n", ParseError::new(4, 24, "0 18446744073709551614 2", "the range runs past the largest number there can be"))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_lines(input), Err(expected));
     }
 
     #[rstest]
@@ -270,7 +389,7 @@ mod test {
     #[case(90, None)]
     fn test_map_if_value(#[case] input: u64, #[case] expected: Option<u64>) {
         let mapping = Mapping {
-            initial_range: 14..90,
+            initial_range: Interval::new(14, 90),
             final_start: 17
         };
 
@@ -284,7 +403,7 @@ mod test {
     #[case(90, 90)]
     fn get_mapping_with_one(#[case] input: u64, #[case] expected: u64) {
         let mapping = vec![Mapping {
-            initial_range: 14..90,
+            initial_range: Interval::new(14, 90),
             final_start: 17
         }];
 
@@ -298,11 +417,11 @@ mod test {
     #[case(90, 90)]
     fn get_mapping_with_two(#[case] input: u64, #[case] expected: u64) {
         let mapping = vec![Mapping {
-            initial_range: 70..90,
+            initial_range: Interval::new(70, 90),
             final_start: 73
         },
         Mapping {
-            initial_range: 1..15,
+            initial_range: Interval::new(1, 15),
             final_start: 10
         }];
 
@@ -318,14 +437,20 @@ mod test {
         assert_eq!(walk_data(seed, PARSED_DATA.deref()), expected);
     }
 
+    #[test]
+    fn test_parsed_data_accessors() {
+        assert_eq!(PARSED_DATA.seeds(), &[79, 14, 55, 13]);
+        assert_eq!(PARSED_DATA.location(79), 82);
+    }
+
     #[test]
     fn test_reverse_mapping() {
         let mapping = Mapping {
-            initial_range: 5..10, // 5, 6, 7, 8, 9
+            initial_range: Interval::new(5, 10), // 5, 6, 7, 8, 9
             final_start: 10
         };
         let expected = Mapping {
-            initial_range: 10..15,
+            initial_range: Interval::new(10, 15),
             final_start: 5
         };
         assert_eq!(mapping.reverse_mapping(), expected);
@@ -1,56 +1,97 @@
-use crate::common::load_from;
+//! Day 6: Wait For It
+//!
+//! Counts the ways to beat the record in each boat race, treating the input as one long race in part two.
 
-pub fn run_day() {
-    let data = load_from("day06.txt");
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines, missing};
+use crate::solution::Solution;
 
-    println!("Part 1: {}", day06a(data.as_str()));
-    println!("Part 2: {}", day06b(data.as_str()));
+/// Multiplies the number of ways to win each race, then counts the ways to win the single long race.
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Parsed = Races;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Races, ParseError> {
+        parse_data(input)
+    }
+
+    fn part_one(&self, races: &Races) -> u64 {
+        day06a(&races.races)
+    }
+
+    fn part_two(&self, races: &Races) -> u64 {
+        day06b(&races.long_race)
+    }
 }
 
-fn day06a(data: &str) -> u64 {
-    parse_data_part_a(data)
+fn day06a(races: &[TimeDistanceRecords]) -> u64 {
+    races
         .iter()
         .map(determine_records)
         .product()
 }
 
-fn day06b(data: &str) -> u64 {
-    let record = parse_data_part_b(data);
-    determine_records(&record)
+fn day06b(record: &TimeDistanceRecords) -> u64 {
+    determine_records(record)
 }
 
 fn determine_records(record: &TimeDistanceRecords) -> u64 {
-    // find the lowest and the highest value
-    let first = (0..record.time).filter(|t| t * (record.time - t) > record.distance).next().unwrap();
-    let last = (0..record.time).rev().filter(|t| t * (record.time - t) > record.distance).next().unwrap();
+    // find the lowest and the highest value, in u128 as holding the button for half a long race goes a long way
+    let wins = |t: &u64| *t as u128 * (record.time - t) as u128 > record.distance as u128;
+    let Some(first) = (0..record.time).find(wins) else {
+        return 0;
+    };
+    let last = (0..record.time).rev().find(wins).expect("there's a first way to win, so there's a last");
     last - first + 1 // need this to be inclusive
 }
 
-fn parse_data_part_a(data: &str) -> Vec<TimeDistanceRecords> {
-    let mut lines = data.lines();
-    let times = parse_line_part_a(lines.next().expect("First line was missing"));
-    let distance = parse_line_part_a(lines.next().expect("Second line was missing"));
-
-    times.iter().zip(distance).map(|x| TimeDistanceRecords { time: x.0.clone(), distance: x.1.clone() }).collect()
+fn parse_data(data: &str) -> Result<Races, ParseError> {
+    Ok(Races { races: parse_data_part_a(data)?, long_race: parse_data_part_b(data)? })
 }
 
-fn parse_data_part_b(data: &str) -> TimeDistanceRecords {
-    let mut lines = data.lines();
-    let time = parse_line_part_b(lines.next().expect("First line was missing"));
-    let distance = parse_line_part_b(lines.next().expect("Second line was missing"));
-
-    TimeDistanceRecords { time, distance }
+fn parse_data_part_a(data: &str) -> Result<Vec<TimeDistanceRecords>, ParseError> {
+    let (times, distances) = parse_rows(data, |line, numbers| line.numbers::<u64>(numbers))?;
+    if times.len() != distances.len() {
+        let line = lines(data).nth(1).expect("the distances were read from the second line");
+        return Err(line.error(line.text, format!("expected {} distances, one for each time, found {}", times.len(), distances.len())));
+    }
+    Ok(times.into_iter().zip(distances).map(|(time, distance)| TimeDistanceRecords { time, distance }).collect())
 }
 
-fn parse_line_part_a(data: &str) -> Vec<u64> {
-    data.split_whitespace().skip(1).map(|x| str::parse::<u64>(x).unwrap()).collect()
+fn parse_data_part_b(data: &str) -> Result<TimeDistanceRecords, ParseError> {
+    let (time, distance) = parse_rows(data, |line, numbers| {
+        // Check they're all numbers first, so an error can point at the one that isn't
+        line.numbers::<u64>(numbers)?;
+        let digits: String = numbers.split_whitespace().collect();
+        digits.parse().map_err(|_| line.error(numbers, "expected the numbers to make one number when joined up"))
+    })?;
+    Ok(TimeDistanceRecords { time, distance })
 }
 
-fn parse_line_part_b(data: &str) -> u64 {
-    let string_no = data.split_whitespace().skip(1).collect::<Vec<&str>>().join("");
-    str::parse::<u64>(string_no.as_str()).unwrap()
+// What `read` makes of the numbers after the label on each of the two lines
+fn parse_rows<'a, T>(data: &'a str, mut read: impl FnMut(Line<'a>, &'a str) -> Result<T, ParseError>) -> Result<(T, T), ParseError> {
+    let mut lines = lines(data);
+    let mut row = |label: &str| {
+        let line = lines.next().ok_or_else(|| missing(data, format!("expected a line starting with {}", label)))?;
+        read(line, line.prefixed(line.text, label, format!("expected the line to start with {}", label))?)
+    };
+    Ok((row("Time:")?, row("Distance:")?))
 }
 
+/// The races as part one reads them, and the one long race that part two reads them as.
+#[derive(Debug, PartialEq)]
+pub struct Races {
+    races: Vec<TimeDistanceRecords>,
+    long_race: TimeDistanceRecords
+}
 
 #[derive(Debug, PartialEq)]
 struct TimeDistanceRecords {
@@ -58,15 +99,32 @@ struct TimeDistanceRecords {
     distance: u64
 }
 
+/// Generates `size` races, up to four of them, as part two joins the races into one and tries every time for it. The
+/// record for each race, and for the joined race, can always be beaten.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    loop {
+        let races: Vec<(u64, u64)> = (0..size.clamp(1, 4)).map(|_| {
+            let time = rng.gen_range(10..100);
+            (time, rng.gen_range(1..time * time / 4))
+        }).collect();
+        let join = |values: Vec<u64>| values.iter().join("").parse::<u128>().unwrap();
+        let time = join(races.iter().map(|race| race.0).collect());
+        let distance = join(races.iter().map(|race| race.1).collect());
+        if distance < time * time / 4 {
+            let row = |label: &str, values: Vec<u64>| format!("{:<9}{}", label, values.iter().map(|v| format!("{:>7}", v)).join(""));
+            return format!("{}\n{}", row("Time:", races.iter().map(|race| race.0).collect()), row("Distance:", races.iter().map(|race| race.1).collect()));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Deref;
     use once_cell::sync::Lazy;
     use rstest::rstest;
-    use crate::day06::{day06a, day06b, determine_records, parse_data_part_a, parse_data_part_b, TimeDistanceRecords};
-
-    const TEST_DATA: &str = "Time:      7  15   30\n\
-                             Distance:  9  40  200";
+    use crate::common::ParseError;
+    use crate::day06::{day06a, day06b, determine_records, parse_data, parse_data_part_a, parse_data_part_b, TimeDistanceRecords};
+    use crate::examples::DAY06 as TEST_DATA;
 
     static PARSED_DATA_PART_A: Lazy<Vec<TimeDistanceRecords>> = Lazy::new(|| vec![
         TimeDistanceRecords {
@@ -91,22 +149,22 @@ mod tests {
 
     #[test]
     fn test_parse_data_part_a() {
-        assert_eq!(parse_data_part_a(TEST_DATA), *PARSED_DATA_PART_A.deref());
+        assert_eq!(parse_data_part_a(TEST_DATA).unwrap(), *PARSED_DATA_PART_A.deref());
     }
 
     #[test]
     fn test_parse_data_part_b() {
-        assert_eq!(parse_data_part_b(TEST_DATA), *PARSED_DATA_PART_B.deref());
+        assert_eq!(parse_data_part_b(TEST_DATA).unwrap(), *PARSED_DATA_PART_B.deref());
     }
 
     #[test]
     fn test_day06a_aoc_soln() {
-        assert_eq!(day06a(TEST_DATA), 288);
+        assert_eq!(day06a(&PARSED_DATA_PART_A), 288);
     }
 
     #[test]
     fn test_day06b_aoc_soln() {
-        assert_eq!(day06b(TEST_DATA), 71503);
+        assert_eq!(day06b(&PARSED_DATA_PART_B), 71503);
     }
 
     #[rstest]
@@ -114,9 +172,21 @@ mod tests {
     #[case(15, 40, 8)]
     #[case(30, 200, 9)]
     #[case(71530, 940200, 71503)]
+    #[case(7, 12, 0)]
+    #[case(u64::MAX, u64::MAX, u64::MAX - 3)]
     fn test_determine_records(#[case] time: u64, #[case] distance: u64, #[case] expected: u64) {
         let data = TimeDistanceRecords { time, distance };
         assert_eq!(determine_records(&data), expected);
     }
 
+    #[rstest]
+    #[case("Time: 7 15\n", ParseError::new(1, 1, "", "expected a line starting with Distance:"))]
+    #[case("Time: 7 15\nDistance 9 40", ParseError::new(2, 1, "Distance 9 40", "expected the line to start with Distance:"))]
+    #[case("Time: 7 1x\nDistance: 9 40", ParseError::new(1, 9, "Time: 7 1x", "expected a number, found \"1x\""))]
+    #[case("Time: 7 15\nDistance: 99999999999 99999999999", ParseError::new(2, 10, "Distance: 99999999999 99999999999", "expected the numbers to make one number when joined up"))]
+    #[case("Time: 7 15\nDistance: 9", ParseError::new(2, 1, "Distance: 9", "expected 2 distances, one for each time, found 1"))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_data(input), Err(expected));
+    }
+
 }
\ No newline at end of file
@@ -1,15 +1,40 @@
+//! Day 7: Camel Cards
+//!
+//! Ranks the hands of Camel Cards by type and then by card, with jacks becoming jokers in part two.
+
 use std::cmp::Ordering;
 use std::collections::hash_map::RandomState;
-use std::collections::HashMap;
-use crate::common::load_from;
+use std::collections::{HashMap, HashSet};
+use rand::rngs::StdRng;
+use rand::seq::IteratorRandom;
+use rand::Rng;
+use crate::common::{parse_number, ParseError};
+use crate::solution::Solution;
 use crate::day07::Card::*;
 
-pub fn run_day() {
-    let data = load_from("day07.txt");
-    let mut hands = parse_all_hands(data.as_str());
-    rank_hands(&mut hands);
-    println!("Part 1: {}", day07a(&hands));
-    println!("Part 2: {}", day07b(&hands));
+/// Sums the winnings of each hand. Parsing ranks the hands, so part one can use them as they are.
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+
+    type Parsed = Vec<Hand>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Hand>, ParseError> {
+        let mut hands = parse_all_hands(input)?;
+        rank_hands(&mut hands);
+        Ok(hands)
+    }
+
+    fn part_one(&self, hands: &Vec<Hand>) -> u64 {
+        day07a(hands)
+    }
+
+    fn part_two(&self, hands: &Vec<Hand>) -> u64 {
+        day07b(hands)
+    }
 }
 
 fn day07a(data: &Vec<Hand>) -> u64 {
@@ -36,15 +61,23 @@ fn rank_hands(hands: &mut Vec<Hand>) {
     hands.sort()
 }
 
-fn parse_all_hands(lines: &str) -> Vec<Hand> {
-    lines.lines().map(parse_hand).collect()
+fn parse_all_hands(lines: &str) -> Result<Vec<Hand>, ParseError> {
+    lines.lines().enumerate().map(|(index, line)| parse_hand(index, line)).collect()
 }
 
-fn parse_hand(line: &str) -> Hand {
+fn parse_hand(index: usize, line: &str) -> Result<Hand, ParseError> {
     let inputs: Vec<&str> = line.split_whitespace().collect();
-    let cards: Vec<Card> = inputs[0].chars().map(|c| string_to_card(&c)).collect();
-    let bid: u64 = str::parse::<u64>(inputs[1]).unwrap();
-    create_hand_from_cards_and_bid(cards, bid)
+    let [card_string, bid_string] = inputs[..] else {
+        return Err(ParseError::in_line(index, line, line, "expected five cards and a bid"));
+    };
+    let cards: Vec<Card> = card_string.char_indices().map(|(i, c)| {
+        string_to_card(&c).ok_or_else(|| ParseError::in_line(index, line, &card_string[i..], format!("{} is not a card", c)))
+    }).collect::<Result<Vec<Card>, ParseError>>()?;
+    if cards.len() != 5 {
+        return Err(ParseError::in_line(index, line, card_string, format!("expected five cards, found {}", cards.len())));
+    }
+    let bid: u64 = parse_number(index, line, bid_string)?;
+    Ok(create_hand_from_cards_and_bid(cards, bid))
 }
 
 fn create_hand_from_cards_and_bid(cards: Vec<Card>, bid: u64) -> Hand {
@@ -112,8 +145,8 @@ fn determine_hand(cards: &Vec<Card>) -> HandType {
     }
 }
 
-fn string_to_card(c: &char) -> Card {
-    match c {
+fn string_to_card(c: &char) -> Option<Card> {
+    let card = match c {
         '2' => Two,
         '3' => Three,
         '4' => Four,
@@ -127,12 +160,14 @@ fn string_to_card(c: &char) -> Card {
         'Q' => Queen,
         'K' => King,
         'A' => Ace,
-        _   => panic!("This should not happen")
-    }
+        _   => return None
+    };
+    Some(card)
 }
 
+/// A hand of five cards, its type and the bid.
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Hand {
+pub struct Hand {
     cards: Vec<Card>,
     hand_type: HandType,
     bid: u64
@@ -182,6 +217,28 @@ enum Card {
 }
 
 
+/// Generates `size` different hands, each with a bid of up to 1000. Cards are often repeated within a hand, so there
+/// are plenty of pairs and better.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const CARDS: [char; 13] = ['2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A'];
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut hands: Vec<String> = Vec::new();
+    while hands.len() < size.clamp(1, 13usize.pow(5)) {
+        let mut hand = String::new();
+        while hand.len() < 5 {
+            let card = match hand.chars().choose(rng) {
+                Some(card) if rng.gen_bool(0.4) => card,
+                _ => CARDS[rng.gen_range(0..CARDS.len())]
+            };
+            hand.push(card);
+        }
+        if seen.insert(hand.clone()) {
+            hands.push(format!("{} {}", hand, rng.gen_range(1..=1000)));
+        }
+    }
+    hands.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Ordering;
@@ -189,14 +246,10 @@ mod tests {
     use once_cell::sync::Lazy;
     use rand::seq::SliceRandom;
     use rstest::rstest;
-    use crate::day07::{Card, day07a, day07b, determine_hand, Hand, HandType, parse_all_hands, rank_hands};
+    use crate::common::ParseError;
+    use crate::day07::{Card, day07a, day07b, determine_hand, Hand, HandType, parse_all_hands, parse_hand, rank_hands};
     use crate::day07::Card::*;
-
-    const TEST_DATA: &str = "32T3K 765\n\
-                             T55J5 684\n\
-                             KK677 28\n\
-                             KTJJT 220\n\
-                             QQQJA 483";
+    use crate::examples::DAY07 as TEST_DATA;
 
     static PARSED_DATA: Lazy<Vec<Hand>> = Lazy::new(|| {
         vec![
@@ -269,7 +322,16 @@ mod tests {
 
     #[test]
     fn test_parse_all_hands() {
-        assert_eq!(parse_all_hands(TEST_DATA), *PARSED_DATA.deref())
+        assert_eq!(parse_all_hands(TEST_DATA).unwrap(), *PARSED_DATA.deref())
+    }
+
+    #[rstest]
+    #[case("32X3K 765", ParseError::new(1, 3, "32X3K 765", "X is not a card"))]
+    #[case("32T3 765", ParseError::new(1, 1, "32T3 765", "expected five cards, found 4"))]
+    #[case("32T3K", ParseError::new(1, 1, "32T3K", "expected five cards and a bid"))]
+    #[case("32T3K -5", ParseError::new(1, 7, "32T3K -5", "expected a number, found \"-5\""))]
+    fn test_parse_hand_errors(#[case] line: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_hand(0, line), Err(expected));
     }
 
     #[test]
@@ -1,32 +1,71 @@
+//! Day 8: Haunted Wasteland
+//!
+//! Follows the left/right instructions through the network. Part two finds the loop each ghost's path ends up in, and
+//! works out when the loops first have every ghost on a Z at once.
+
 use std::collections::hash_map::RandomState;
-use std::collections::HashMap;
-use num::integer::lcm;
-use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::iter::once;
+use num::integer::{ExtendedGcd, Integer};
 use regex::Regex;
 use tailcall::tailcall;
-use crate::common::load_from;
-use crate::day08::Direction::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::cycle;
+use crate::common::cycle::Cycle;
+use crate::common::point::Turn;
+use crate::common::point::Turn::*;
+use crate::common::ParseError;
+use crate::common::parse::{blocks, Line, missing};
+use crate::differential::Comparison;
+use crate::solution::{Part, Solution};
+use crate::{debug, info};
+
+/// Counts the steps from AAA to ZZZ, then the steps until every ghost is on a node ending in Z.
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Parsed = (Vec<Turn>, HashMap<String, Node>);
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<(Vec<Turn>, HashMap<String, Node>), ParseError> {
+        parse_data(input)
+    }
 
-static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"([0-9A-Z]{3}) = \(([0-9A-Z]{3}), ([0-9A-Z]{3})\)").unwrap());
+    // The example for part two has no AAA, so it's only part one that needs it
+    fn check(&self, input: &str, parsed: &(Vec<Turn>, HashMap<String, Node>), part: Part) -> Result<(), ParseError> {
+        match part {
+            Part::One => ["AAA", "ZZZ"].iter()
+                .find(|label| !parsed.1.contains_key(**label))
+                .map_or(Ok(()), |label| Err(missing(input, format!("there's no {} node for part one", label)))),
+            Part::Two => Ok(())
+        }
+    }
+
+    fn part_one(&self, parsed: &(Vec<Turn>, HashMap<String, Node>)) -> u64 {
+        day08a(&parsed.0, &parsed.1)
+    }
 
-pub fn run_day() {
-    let data = load_from("day08.txt");
-    let (directions, nodes) = parse_data(data.as_str());
-    println!("Part 1: {}", day08a(&directions, &nodes));
-    println!("Part 2: {}", day08b(&directions, &nodes));
+    fn part_two(&self, parsed: &(Vec<Turn>, HashMap<String, Node>)) -> u64 {
+        day08b(&parsed.0, &parsed.1)
+    }
 }
 
-fn day08a(directions: &Vec<Direction>, nodes: &HashMap<String, Node>) -> u64 {
+fn day08a(directions: &Vec<Turn>, nodes: &HashMap<String, Node>) -> u64 {
     walk_nodes(directions, nodes)
 }
 
-fn day08b(directions: &Vec<Direction>, nodes: &HashMap<String, Node>) -> u64 {
+fn day08b(directions: &Vec<Turn>, nodes: &HashMap<String, Node>) -> u64 {
     walk_nodes_simultaneously(directions, nodes)
 }
 
 #[tailcall]
-fn walk(directions: &Vec<Direction>, nodes: &HashMap<String, Node>, current: &Node, end_pattern: Regex, count: u64) -> u64 {
-    let direction: &Direction = directions.get(count as usize % directions.len()).unwrap();
+fn walk(directions: &Vec<Turn>, nodes: &HashMap<String, Node>, current: &Node, end_pattern: Regex, count: u64) -> u64 {
+    let direction: &Turn = directions.get(count as usize % directions.len()).unwrap();
     let new = match *direction {
         Left => &current.left,
         Right => &current.right
@@ -34,72 +73,265 @@ fn walk(directions: &Vec<Direction>, nodes: &HashMap<String, Node>, current: &No
     if end_pattern.is_match(new) {
         count + 1
     } else {
-        walk(directions, nodes, nodes.get(new).unwrap(), end_pattern, count+1)
+        walk(directions, nodes, nodes.get(new).expect("every node was checked when it was parsed"), end_pattern, count+1)
     }
 }
 
-fn walk_nodes(directions: &Vec<Direction>, nodes: &HashMap<String, Node>) -> u64 {
-    walk(directions, nodes, nodes.get(&String::from("AAA")).unwrap(), Regex::new("ZZZ").unwrap(), 0)
+fn walk_nodes(directions: &Vec<Turn>, nodes: &HashMap<String, Node>) -> u64 {
+    walk(directions, nodes, nodes.get("AAA").expect("part one was checked for AAA"), Regex::new("ZZZ").unwrap(), 0)
 }
 
 
-fn walk_nodes_simultaneously(directions: &Vec<Direction>, nodes: &HashMap<String, Node>) -> u64 {
-    // It turns out that the steps from start to finish loop, see example data.
-    // So, we need to get the number of steps in a loop for each input.
-    // Each loop ENDS with a -Z
-    let loop_sizes: Vec<u64> = nodes.iter().filter_map(|(label, node)| {
-        if label.ends_with("A") {
-            Some(walk(directions, nodes, node, Regex::new("[A-Z0-9]{2}Z").unwrap(), 0))
-        } else {
-            None
-        }
+fn walk_nodes_simultaneously(directions: &Vec<Turn>, nodes: &HashMap<String, Node>) -> u64 {
+    // Each ghost ends up going round a loop, which starts once it's back on a node at the same place in the
+    // instructions
+    let ghosts: Vec<(&String, Cycle<(&str, usize)>)> = nodes.keys().filter(|label| label.ends_with('A')).map(|label| {
+        let ghost = cycle::find_by_hashing((label.as_str(), 0), |&(label, step)| {
+            let node = &nodes[label];
+            let next = match directions[step] {
+                Left => &node.left,
+                Right => &node.right
+            };
+            (next.as_str(), (step + 1) % directions.len())
+        });
+        debug!("{} loops every {} steps after {}", label, ghost.period, ghost.prefix);
+        (label, ghost)
     }).collect();
 
-    // get the lowest common multiple of these limits
-    loop_sizes.iter().map(|x| x.clone()).reduce(|x, y| lcm(x, y)).unwrap()
+    // Until they're all in their loops, just walk them
+    let prefix = ghosts.iter().map(|(_, ghost)| ghost.prefix).max().unwrap_or(0);
+    if let Some(steps) = walk_every_ghost(directions, nodes, prefix as u64) {
+        return steps;
+    }
+
+    // After that, each ghost is on a Z a certain number of steps round its loop, so the answer is the first step past
+    // the prefix that's one of those for every ghost
+    let (every, steps) = ghosts.iter().fold((1i128, vec![0i128]), |(every, steps), (label, ghost)| {
+        let period = ghost.period as i128;
+        let on_z: Vec<i128> = ghost.states.iter().enumerate()
+            .filter(|(_, (label, _))| label.ends_with('Z'))
+            .map(|(index, _)| (ghost.prefix + index) as i128 % period)
+            .collect();
+        debug!("{} is on a Z {} times round its loop", label, on_z.len());
+        let mut lined_up: Vec<(i128, i128)> = steps.iter()
+            .flat_map(|a| on_z.iter().filter_map(move |b| line_up((*a, every), (*b, period))))
+            .collect();
+        lined_up.sort();
+        lined_up.dedup();
+        (every / every.gcd(&period) * period, lined_up.into_iter().map(|(step, _)| step).collect())
+    });
+    let prefix = prefix as i128;
+    let first = steps.iter()
+        .map(|step| step + Integer::div_ceil(&(prefix - step).max(0), &every) * every)
+        .min()
+        .expect("the ghosts are never all on a Z at the same time");
+    info!("the ghosts all line up every {} steps, first after {}", every, first);
+    u64::try_from(first).expect("the ghosts take too many steps to line up to count")
 }
 
-fn parse_data(data: &str) -> (Vec<Direction>, HashMap<String, Node>) {
-    let mut lines = data.lines();
-    let directions = parse_directions(lines.next().unwrap());
+// The steps that are `a` more than a multiple of `n` and also `b` more than a multiple of `m`, as how many more than a
+// multiple of their LCM they are. None if there are no such steps.
+fn line_up((a, n): (i128, i128), (b, m): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = n.extended_gcd(&m);
+    if (b - a) % gcd != 0 {
+        return None;
+    }
+    let lcm = n / gcd * m;
+    // x lots of n is 1 more than a multiple of m / gcd, so adding that (b - a) / gcd times makes up the difference
+    let times = ((b - a) / gcd).rem_euclid(m / gcd) * x.rem_euclid(m / gcd) % (m / gcd);
+    Some(((a + n * times).rem_euclid(lcm), lcm))
+}
 
-    // ignore the next line
-    lines.next();
+fn parse_data(data: &str) -> Result<(Vec<Turn>, HashMap<String, Node>), ParseError> {
+    let blocks = blocks(data);
+    let [first, network] = &blocks[..] else {
+        return Err(missing(data, "expected the instructions, a blank line and then the network"));
+    };
+    if let Some(line) = first.get(1) {
+        return Err(line.error(line.text, "expected a blank line after the instructions"));
+    }
+    let directions = parse_directions(first[0])?;
 
     let mut map: HashMap<String, Node, RandomState> = HashMap::new();
-    while let Some(line) = lines.next() {
-        if let Some(captures) = REGEX.captures(line) {
-            match captures.extract() {
-                (_, [current, left, right]) => map.insert(String::from(current), Node { left: String::from(left), right: String::from(right) })
-            };
+    let mut leads_to: Vec<(Line, &str)> = Vec::new();
+    for line in network {
+        let (current, left, right) = parse_node(*line)?;
+        if map.insert(String::from(current), Node { left: String::from(left), right: String::from(right) }).is_some() {
+            return Err(line.error(current, format!("there's already a node called {}", current)));
         }
+        leads_to.extend([(*line, left), (*line, right)]);
+    }
+    if let Some((line, name)) = leads_to.into_iter().find(|(_, name)| !map.contains_key(*name)) {
+        return Err(line.error(name, format!("there's no node called {}", name)));
     }
 
-    (directions, map)
+    Ok((directions, map))
 }
 
-fn parse_directions(line: &str) -> Vec<Direction> {
-    line.chars().map(|c| {
+fn parse_directions(line: Line) -> Result<Vec<Turn>, ParseError> {
+    line.text.char_indices().map(|(i, c)| {
         match c {
-            'L' => Left,
-            'R' => Right,
-            _ => panic!("Not a direction")
+            'L' => Ok(Left),
+            'R' => Ok(Right),
+            _ => Err(line.error(&line.text[i..], "expected L or R"))
         }
     }).collect()
 }
 
-#[derive(PartialEq, Debug)]
-enum Direction {
-    Left,
-    Right
+fn parse_node<'a>(line: Line<'a>) -> Result<(&'a str, &'a str, &'a str), ParseError> {
+    const EXPECTED: &str = "expected a node and the nodes to its left and right, like AAA = (BBB, CCC)";
+    let (current, next) = line.split_once(line.text, " = ", EXPECTED)?;
+    let (left, right) = line.split_once(line.delimited(next, "(", ")", EXPECTED)?, ", ", EXPECTED)?;
+    for name in [current, left, right] {
+        if name.len() != 3 || !name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Err(line.error(name, format!("expected a node's name, three letters or digits, found \"{}\"", name)));
+        }
+    }
+    Ok((current, left, right))
 }
 
+/// A node in the network and the nodes to its left and right.
 #[derive(PartialEq, Debug)]
-struct Node {
+pub struct Node {
     left: String,
     right: String
 }
 
+// The simple way to do part two: move every ghost at once until they're all on a Z. None if that takes more than
+// `limit` steps.
+fn walk_every_ghost(directions: &[Turn], nodes: &HashMap<String, Node>, limit: u64) -> Option<u64> {
+    let mut positions: Vec<&String> = nodes.keys().filter(|label| label.ends_with('A')).collect();
+    for step in 0..limit {
+        if positions.iter().all(|position| position.ends_with('Z')) {
+            return Some(step);
+        }
+        let direction = &directions[step as usize % directions.len()];
+        for position in positions.iter_mut() {
+            let node = &nodes[*position];
+            *position = match direction {
+                Left => &node.left,
+                Right => &node.right
+            };
+        }
+    }
+    None
+}
+
+/// Part two by lining up the ghosts' loops, and by walking every ghost at once, for the differential tests. None if
+/// the ghosts take too long to line up.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let (directions, nodes) = parse_data(input)?;
+    let Some(reference) = walk_every_ghost(&directions, &nodes, 10_000_000) else {
+        return Ok(None);
+    };
+    Ok(Some((day08b(&directions, &nodes).to_string(), reference.to_string())))
+}
+
+const CHARACTERS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const OTHER_ENDINGS: &[u8] = b"0123456789BCDEFGHIJKLMNOPQRSTUVWXY";
+
+// A name that hasn't been used yet, ending with one of `endings`
+fn name(rng: &mut StdRng, used: &mut HashSet<String>, endings: &[u8]) -> String {
+    loop {
+        let name: String = [CHARACTERS, CHARACTERS, endings].iter().map(|c| *c.choose(rng).unwrap() as char).collect();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// Generates a network of about `size` nodes, with up to six ghosts. As in the real network, each ghost's path is a
+/// loop that ends on its Z node and then starts again, every loop is a whole number of passes through the
+/// instructions, and AAA's loop ends at ZZZ.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let size = size.clamp(10, 30000);
+    let ghosts = (size / 50).clamp(1, 6);
+    let per_ghost = size / ghosts;
+    // The instructions are long enough that the loops, and so their LCM, stay small enough for a u64
+    let longest = ((per_ghost as f64).sqrt() as usize).max(2);
+    let instructions: Vec<char> = (0..rng.gen_range(longest / 2..=longest))
+        .map(|_| if rng.gen_bool(0.5) { 'L' } else { 'R' })
+        .collect();
+    let passes = per_ghost / instructions.len();
+
+    // Each ghost's start, then the nodes of its loop, finishing with its Z node
+    let mut used: HashSet<String> = HashSet::from([String::from("AAA"), String::from("ZZZ")]);
+    let loops: Vec<(String, Vec<String>)> = (0..ghosts).map(|ghost| {
+        let length = rng.gen_range((passes / 2).max(1)..=passes) * instructions.len();
+        let mut nodes: Vec<String> = (1..length).map(|_| name(rng, &mut used, OTHER_ENDINGS)).collect();
+        if ghost == 0 {
+            nodes.push(String::from("ZZZ"));
+            (String::from("AAA"), nodes)
+        } else {
+            nodes.push(name(rng, &mut used, b"Z"));
+            (name(rng, &mut used, b"A"), nodes)
+        }
+    }).collect();
+
+    // The way the ghost goes is decided by the instruction it's on, the other way can go anywhere
+    let every_node: Vec<&String> = loops.iter().flat_map(|(start, nodes)| once(start).chain(nodes.iter())).collect();
+    let mut lines: Vec<String> = Vec::new();
+    for (start, nodes) in &loops {
+        for (step, node) in once(start).chain(nodes.iter()).enumerate() {
+            let next = &nodes[step % nodes.len()];
+            let other = every_node.choose(rng).unwrap();
+            let (left, right) = match instructions[step % instructions.len()] {
+                'L' => (next, *other),
+                _ => (*other, next)
+            };
+            lines.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+    lines.shuffle(rng);
+    format!("{}\n\n{}", instructions.iter().collect::<String>(), lines.join("\n"))
+}
+
+/// Generates a network of about `size` nodes, with up to three ghosts, that has none of the structure of the real
+/// one. Each ghost wanders through some nodes before it gets to its loop, the loop can be any length, and there can be
+/// Z nodes anywhere along the way, so a ghost can be on a Z several times round its loop, or before it. There's no AAA,
+/// so this is only for part two.
+pub fn generate_loose(rng: &mut StdRng, size: usize) -> String {
+    let size = size.clamp(10, 30000);
+    let ghosts = (size / 100).clamp(1, 3);
+    let per_ghost = size / ghosts;
+    let instructions: Vec<char> = (0..rng.gen_range(1..=8)).map(|_| if rng.gen_bool(0.5) { 'L' } else { 'R' }).collect();
+
+    let mut used: HashSet<String> = HashSet::new();
+    let mut lines: Vec<String> = Vec::new();
+    let mut every_node: Vec<String> = Vec::new();
+    // Each ghost's path, with where its loop starts
+    let paths: Vec<(Vec<String>, usize)> = (0..ghosts).map(|_| {
+        let length = rng.gen_range(2..=per_ghost);
+        let loop_start = rng.gen_range(1..length);
+        let mut on_z: Vec<bool> = (0..length).map(|_| rng.gen_bool(2.0 / length as f64)).collect();
+        on_z[0] = false;
+        on_z[rng.gen_range(loop_start..length)] = true;
+        let path: Vec<String> = on_z.iter().enumerate().map(|(step, z)| match (step, z) {
+            (0, _) => name(rng, &mut used, b"A"),
+            (_, true) => name(rng, &mut used, b"Z"),
+            (_, false) => name(rng, &mut used, OTHER_ENDINGS)
+        }).collect();
+        every_node.extend(path.iter().cloned());
+        (path, loop_start)
+    }).collect();
+
+    // Before the loop, the way the ghost goes is decided by the instruction it's on, and the other way can go
+    // anywhere. In the loop the ghost comes back to each node on different instructions, so both ways go on round it.
+    for (path, loop_start) in &paths {
+        for (step, node) in path.iter().enumerate() {
+            let next = path.get(step + 1).unwrap_or(&path[*loop_start]);
+            let (left, right) = match (step < *loop_start, instructions[step % instructions.len()]) {
+                (true, 'L') => (next, every_node.choose(rng).unwrap()),
+                (true, _) => (every_node.choose(rng).unwrap(), next),
+                (false, _) => (next, next)
+            };
+            lines.push(format!("{} = ({}, {})", node, left, right));
+        }
+    }
+    lines.shuffle(rng);
+    format!("{}\n\n{}", instructions.iter().collect::<String>(), lines.join("\n"))
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::hash_map::RandomState;
@@ -107,44 +339,26 @@ mod tests {
     use std::ops::Deref;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day08::{Direction, parse_data, parse_directions, Node, day08a, day08b};
-    use crate::day08::Direction::*;
-
-    const TEST_DATA_1: &str = "RL\n\
-                               \n\
-                               AAA = (BBB, CCC)\n\
-                               BBB = (DDD, EEE)\n\
-                               CCC = (ZZZ, GGG)\n\
-                               DDD = (DDD, DDD)\n\
-                               EEE = (EEE, EEE)\n\
-                               GGG = (GGG, GGG)\n\
-                               ZZZ = (ZZZ, ZZZ)";
-
-    const TEST_DATA_2: &str = "LLR\n\
-                               \n\
-                               AAA = (BBB, BBB)\n\
-                               BBB = (AAA, ZZZ)\n\
-                               ZZZ = (ZZZ, ZZZ)";
-
-    const TEST_DATA_3: &str = "LR\n\
-                               \n\
-                               11A = (11B, XXX)\n\
-                               11B = (XXX, 11Z)\n\
-                               11Z = (11B, XXX)\n\
-                               22A = (22B, XXX)\n\
-                               22B = (22C, 22C)\n\
-                               22C = (22Z, 22Z)\n\
-                               22Z = (22B, 22B)\n\
-                               XXX = (XXX, XXX)";
+    use crate::common::ParseError;
+    use crate::common::parse::{Line, missing};
+    use crate::common::point::Turn;
+    use crate::common::point::Turn::*;
+    use num::integer::lcm;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use regex::Regex;
+    use crate::solution::{Part, Solution};
+    use crate::day08::{generate_loose, line_up, parse_data, parse_directions, Day08, Node, day08a, day08b, walk, walk_every_ghost};
+    use crate::examples::{DAY08_1 as TEST_DATA_1, DAY08_2 as TEST_DATA_2, DAY08_3 as TEST_DATA_3};
 
     lazy_static! {
-        static ref DIRECTION_1: Vec<Direction> = vec![Right, Left];
+        static ref DIRECTION_1: Vec<Turn> = vec![Right, Left];
     }
     lazy_static! {
-        static ref DIRECTION_2: Vec<Direction> = vec![Left, Left, Right];
+        static ref DIRECTION_2: Vec<Turn> = vec![Left, Left, Right];
     }
     lazy_static! {
-        static ref DIRECTION_3: Vec<Direction> = vec![Left, Right];
+        static ref DIRECTION_3: Vec<Turn> = vec![Left, Right];
     }
     lazy_static! {
         static ref NODES_1: HashMap<String, Node, RandomState> = HashMap::from([
@@ -181,31 +395,52 @@ mod tests {
     #[case("LRLRLR", vec![Left, Right, Left, Right, Left, Right])]
     #[case("LLRR", vec![Left, Left, Right, Right])]
     #[case("RRLRLLR", vec![Right, Right, Left, Right, Left, Left, Right])]
-    fn test_parse_direction(#[case] string: &str, #[case] expected: Vec<Direction>) {
-        assert_eq!(parse_directions(string), expected);
+    fn test_parse_direction(#[case] string: &str, #[case] expected: Vec<Turn>) {
+        assert_eq!(parse_directions(Line::new(0, string)), Ok(expected));
+    }
+
+    #[rstest]
+    #[case("LR\nRL\n\nAAA = (BBB, CCC)", ParseError::new(2, 1, "RL", "expected a blank line after the instructions"))]
+    #[case("LXR\n\nAAA = (BBB, CCC)", ParseError::new(1, 2, "LXR", "expected L or R"))]
+    #[case("LR\n\nAAA = (BBB, CCC\n", ParseError::new(3, 7, "AAA = (BBB, CCC", "expected a node and the nodes to its left and right, like AAA = (BBB, CCC)"))]
+    #[case("LR\n\nAAA = (BBB, cc)\n", ParseError::new(3, 13, "AAA = (BBB, cc)", "expected a node's name, three letters or digits, found \"cc\""))]
+    #[case("LR\n", ParseError::new(1, 1, "", "expected the instructions, a blank line and then the network"))]
+    #[case("LR\n\nAAA = (AAA, BBB)", ParseError::new(3, 13, "AAA = (AAA, BBB)", "there's no node called BBB"))]
+    #[case("LR\n\nAAA = (AAA, AAA)\nAAA = (AAA, AAA)", ParseError::new(4, 1, "AAA = (AAA, AAA)", "there's already a node called AAA"))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_data(input), Err(expected));
     }
 
     #[test]
     fn test_parse_data_1() {
-        let (directions, nodes) = parse_data(TEST_DATA_1);
+        let (directions, nodes) = parse_data(TEST_DATA_1).unwrap();
         assert_eq!(directions, *DIRECTION_1.deref());
         assert_eq!(nodes, *NODES_1.deref());
     }
 
     #[test]
     fn test_parse_data_2() {
-        let (directions, nodes) = parse_data(TEST_DATA_2);
+        let (directions, nodes) = parse_data(TEST_DATA_2).unwrap();
         assert_eq!(directions, *DIRECTION_2.deref());
         assert_eq!(nodes, *NODES_2.deref());
     }
 
     #[test]
     fn test_parse_data_3() {
-        let (directions, nodes) = parse_data(TEST_DATA_3);
+        let (directions, nodes) = parse_data(TEST_DATA_3).unwrap();
         assert_eq!(directions, *DIRECTION_3.deref());
         assert_eq!(nodes, *NODES_3.deref());
     }
 
+    #[test]
+    fn test_check() {
+        let parsed = parse_data(TEST_DATA_3).unwrap();
+        assert_eq!(Day08.check(TEST_DATA_3, &parsed, Part::One), Err(missing(TEST_DATA_3, "there's no AAA node for part one")));
+        assert_eq!(Day08.check(TEST_DATA_3, &parsed, Part::Two), Ok(()));
+        let parsed = parse_data("L\n\nAAA = (AAA, AAA)").unwrap();
+        assert_eq!(Day08.check("L\n\nAAA = (AAA, AAA)", &parsed, Part::One), Err(missing("L\n\nAAA = (AAA, AAA)", "there's no ZZZ node for part one")));
+    }
+
     #[test]
     fn test_day08a() {
         assert_eq!(day08a(DIRECTION_1.deref(), NODES_1.deref()), 2);
@@ -217,4 +452,46 @@ mod tests {
         assert_eq!(day08b(DIRECTION_3.deref(), NODES_3.deref()), 6);
     }
 
+    #[test]
+    fn test_day08b_with_z_partway_round_the_loops() {
+        // 11A is on a Z after 2, 5, 8... steps and 22A after 1, 5, 9..., so the LCM of 2 and 1 would be wrong
+        let input = "L\n\n11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (11C, XXX)\n11C = (11D, XXX)\n11D = (11Z, XXX)\n\
+            22A = (22Z, XXX)\n22Z = (22B, XXX)\n22B = (22C, XXX)\n22C = (22D, XXX)\n22D = (22Z, XXX)\nXXX = (XXX, XXX)";
+        let (directions, nodes) = parse_data(input).unwrap();
+        assert_eq!(day08b(&directions, &nodes), 5);
+        assert_eq!(walk_every_ghost(&directions, &nodes, 100), Some(5));
+    }
+
+    #[test]
+    fn test_generate_loose_breaks_the_lcm() {
+        // The LCM of the steps each ghost takes to its first Z is only right when each ghost's loop ends on its only Z
+        let wrong = (0..10).filter(|seed| {
+            let input = generate_loose(&mut StdRng::seed_from_u64(*seed), 300);
+            let (directions, nodes) = parse_data(input.as_str()).unwrap();
+            let Some(reference) = walk_every_ghost(&directions, &nodes, 1_000_000) else {
+                return false;
+            };
+            assert_eq!(day08b(&directions, &nodes), reference);
+            let first_z = nodes.iter().filter(|(label, _)| label.ends_with('A'))
+                .map(|(_, node)| walk(&directions, &nodes, node, Regex::new("[A-Z0-9]{2}Z").unwrap(), 0));
+            first_z.reduce(lcm).unwrap() != reference
+        }).count();
+        assert!(wrong > 0);
+    }
+
+    #[rstest]
+    #[case((2, 3), (3, 5), Some((8, 15)))]
+    #[case((0, 1), (4, 6), Some((4, 6)))]
+    #[case((1, 4), (3, 6), Some((9, 12)))]
+    #[case((1, 4), (2, 6), None)]
+    fn test_line_up(#[case] first: (i128, i128), #[case] second: (i128, i128), #[case] expected: Option<(i128, i128)>) {
+        assert_eq!(line_up(first, second), expected);
+    }
+
+    #[test]
+    fn test_walk_every_ghost() {
+        assert_eq!(walk_every_ghost(DIRECTION_3.deref(), NODES_3.deref(), 100), Some(6));
+        assert_eq!(walk_every_ghost(DIRECTION_3.deref(), NODES_3.deref(), 5), None);
+    }
+
 }
\ No newline at end of file
@@ -1,11 +1,37 @@
+//! Day 9: Mirage Maintenance
+//!
+//! Extrapolates each sequence of readings forwards, and then backwards in part two.
+
 use std::collections::HashSet;
-use crate::common::load_from;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::lines;
+use crate::differential::Comparison;
+use crate::solution::Solution;
+
+/// Sums the next value of each sequence, then the value before each one.
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Parsed = Vec<Vec<i128>>;
+    type PartOne = i128;
+    type PartTwo = i128;
 
-pub fn run_day() {
-    let data = load_from("day09.txt");
-    let input: Vec<Vec<i128>> = parse_lines_to_numbers(data.as_str());
-    println!("Part 1: {}", day09a(&input));
-    println!("Part 2: {}", day09b(&input));
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i128>>, ParseError> {
+        parse_lines_to_numbers(input)
+    }
+
+    fn part_one(&self, input: &Vec<Vec<i128>>) -> i128 {
+        day09a(input)
+    }
+
+    fn part_two(&self, input: &Vec<Vec<i128>>) -> i128 {
+        day09b(input)
+    }
 }
 
 fn day09a(data: &Vec<Vec<i128>>) -> i128 {
@@ -25,10 +51,15 @@ fn calculate(data: &Vec<(usize, Function)>) -> i128 {
     data.iter().map(|(next_idx, func)| func.get(*next_idx)).sum()
 }
 
-fn parse_lines_to_numbers(lines: &str) -> Vec<Vec<i128>> {
-    lines.lines().map(|line| line.split_whitespace().map(|x| {
-        str::parse::<i128>(x).unwrap()
-    }).collect()).collect()
+// Each history needs two readings to have a difference between them
+fn parse_lines_to_numbers(data: &str) -> Result<Vec<Vec<i128>>, ParseError> {
+    lines(data).map(|line| {
+        let numbers: Vec<i128> = line.numbers(line.text)?;
+        if numbers.len() < 2 {
+            return Err(line.error(line.text, format!("expected at least two readings, found {}", numbers.len())));
+        }
+        Ok(numbers)
+    }).collect()
 }
 
 fn parse_line(numbers: &Vec<i128>) -> (usize, Function) {
@@ -73,15 +104,39 @@ impl Function {
     }
 }
 
+// The simple way: keep taking the differences until they're all zero, then add up the last number of each row.
+fn extrapolate(numbers: &[i128]) -> i128 {
+    if numbers.iter().all(|n| *n == 0) {
+        0
+    } else {
+        let differences: Vec<i128> = numbers.windows(2).map(|pair| pair[1] - pair[0]).collect();
+        numbers.last().unwrap() + extrapolate(&differences)
+    }
+}
+
+/// Both parts with `Function`, and by extrapolating the table of differences, for the differential tests.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let histories = parse_lines_to_numbers(input)?;
+    let forwards: i128 = histories.iter().map(|history| extrapolate(history)).sum();
+    let backwards: i128 = histories.iter().map(|history| extrapolate(&history.iter().rev().copied().collect::<Vec<i128>>())).sum();
+    Ok(Some((format!("{} and {}", day09a(&histories), day09b(&histories)), format!("{} and {}", forwards, backwards))))
+}
+
+/// Generates `size` histories of 21 readings, each following a polynomial of degree six or less.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1)).map(|_| {
+        let coefficients: Vec<i64> = (0..=rng.gen_range(0..=6)).map(|_| rng.gen_range(-5..=5)).collect();
+        (0..21i64).map(|x| coefficients.iter().rev().fold(0, |value, c| value * x + c)).join(" ")
+    }).join("\n")
+}
+
 #[cfg(test)]
 mod test {
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day09::{calculate, day09a, day09b, Function, parse_line, parse_lines_to_numbers};
-
-    const TEST_DATA: &str = "0 3 6 9 12 15\n\
-                             1 3 6 10 15 21\n\
-                             10 13 16 21 30 45";
+    use crate::common::ParseError;
+    use crate::day09::{calculate, day09a, day09b, extrapolate, Function, parse_line, parse_lines_to_numbers};
+    use crate::examples::DAY09 as TEST_DATA;
 
     const TEST_DATA_2: &str = "16 22 27 23 4 -16 23 267 1025 2943 7407 17445 39670 88339 193607 417868 887273 1851158 3792224 7628687";
 
@@ -186,6 +241,15 @@ mod test {
         assert_eq!(day09b(&data), 2);
     }
 
+    #[rstest]
+    #[case(&[0, 3, 6, 9, 12, 15], 18)]
+    #[case(&[10, 13, 16, 21, 30, 45], 68)]
+    #[case(&[45, 30, 21, 16, 13, 10], 5)]
+    #[case(&[7], 7)]
+    fn test_extrapolate(#[case] numbers: &[i128], #[case] expected: i128) {
+        assert_eq!(extrapolate(numbers), expected);
+    }
+
     #[test]
     fn test_calculate() {
         let data: Vec<(usize, Function)> = vec![
@@ -198,7 +262,7 @@ mod test {
 
     #[test]
     fn test_parse_lines() {
-        let data: Vec<(usize, Function)> = parse_lines_to_numbers(TEST_DATA).iter().map(|x| parse_line(x)).collect();
+        let data: Vec<(usize, Function)> = parse_lines_to_numbers(TEST_DATA).unwrap().iter().map(parse_line).collect();
         assert_eq!(vec![
             (6, TEST_FUNCTION_1.clone()),
             (6, TEST_FUNCTION_2.clone()),
@@ -206,9 +270,17 @@ mod test {
         ], data);
     }
 
+    #[rstest]
+    #[case("0 3 6\n1 3 x 10", ParseError::new(2, 5, "1 3 x 10", "expected a number, found \"x\""))]
+    #[case("0 3 6\n\n1 3", ParseError::new(2, 1, "", "expected at least two readings, found 0"))]
+    #[case("7", ParseError::new(1, 1, "7", "expected at least two readings, found 1"))]
+    fn test_parse_lines_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_lines_to_numbers(input), Err(expected));
+    }
+
     #[test]
     fn test_line_from_puzzle() {
-        let data = parse_lines_to_numbers(TEST_DATA_2).first().unwrap().iter().map(|x| x.clone()).collect(); // cloning
+        let data = parse_lines_to_numbers(TEST_DATA_2).unwrap().first().unwrap().clone();
         assert_eq!(day09a(&vec![data]), 15083115);
     }
 
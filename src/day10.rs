@@ -1,156 +1,206 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::{Hash, Hasher};
-use num::abs;
-use tailcall::tailcall;
-use crate::common::load_from;
-
-pub fn run_day() {
-    let data = load_from("day10.txt");
-    let nodes = generate_node_map(data.as_str());
-    let path = get_nodes_in_path(&nodes);
-    println!("Part 1: {}", run_day10a(&path));
-    println!("Part 2: {}", run_day10b(&path, &nodes));
-}
+//! Day 10: Pipe Maze
+//!
+//! Finds the loop of pipes that goes through the start, and the number of tiles it encloses.
 
-fn run_day10a(nodes: &Vec<(usize, usize)>) -> u64 {
-    nodes.len() as u64 / 2
-}
+use std::collections::HashSet;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::geometry::Polygon;
+use crate::common::grid::Grid;
+use crate::common::point::{Direction, Point};
+use crate::common::point::Direction::*;
+use crate::common::ParseError;
+use crate::differential::Comparison;
+use crate::solution::Solution;
+use crate::generate::random_loop;
 
-fn run_day10b(nodes: &Vec<(usize, usize)>, node_map: &HashMap<(usize, usize), Node>) -> u64 {
-    // shoelace then pick's
-    let corners: Vec<(usize, usize)> = nodes.iter().filter(|x| node_map.get(x).unwrap().is_angle).map(|x| x.clone()).collect();
-    let corners_1: Vec<(usize, usize)> = corners.iter().skip(1).chain(corners.iter().next()).map(|x| x.clone()).collect();
-
-    // shoelace to get the area
-    let area = abs::<i64>(corners
-        .iter()
-        .zip(corners_1.iter())
-        .map(|((x1, y1), (x2, y2))| (x1 * y2) as i64 - (x2 * y1) as i64)
-        .sum::<i64>() / 2) as u64;
-
-    // picks' theorem
-    let b = nodes.len() as u64;
-    area + 1 - b/2
-}
+/// Parses the pipes and follows the loop from the start. Part one is the furthest point on the loop, part two the
+/// number of tiles inside it.
+pub struct Day10;
 
-fn get_nodes_in_path(nodes: &HashMap<(usize, usize), Node>) -> Vec<(usize, usize)> {
-    let start_node = nodes.values().find(|x| x.is_start).unwrap();
-    let mut path_nodes: Vec<(usize, usize)> = Vec::new();
+impl Solution for Day10 {
+    const DAY: u8 = 10;
 
-    #[tailcall]
-    fn iterate(node: &Node, prev: (usize, usize), start: (usize, usize), path_nodes: &mut Vec<(usize, usize)>, nodes: &HashMap<(usize, usize), Node>) {
-        let next = node.connections.iter().filter(|x| **x != prev).next().unwrap().clone();
-        path_nodes.push(next.clone());
-        if start != next {
-            iterate(nodes.get(&next).unwrap(), node.coord(), start, path_nodes, nodes);
-        }
+    type Parsed = Field;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Field, ParseError> {
+        parse_field(input)
+    }
+
+    fn part_one(&self, field: &Field) -> u64 {
+        run_day10a(field)
     }
 
-    iterate(start_node, start_node.connections.iter().next().unwrap().clone(), start_node.coord(), &mut path_nodes, nodes);
-    path_nodes
+    fn part_two(&self, field: &Field) -> u64 {
+        run_day10b(field)
+    }
 }
 
-fn generate_node_map(data: &str) -> HashMap<(usize, usize), Node> {
-    let mut nodes: HashMap<(usize, usize), Node> = HashMap::new();
-    let mut start_nodes: Vec<(usize, usize)> = Vec::new();
-    for (y, line) in data.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if let Some(node) = create_node(x, y, c) {
-                if node.is_start {
-                    start_nodes.push((x, y));
-                }
-                nodes.insert((x, y), node);
-            }
-        }
+fn run_day10a(field: &Field) -> u64 {
+    field.path.len() as u64 / 2
+}
+
+fn run_day10b(field: &Field) -> u64 {
+    // The loop only turns at its corners, so they're enough for the polygon, and Pick's theorem counts the tiles
+    // inside it
+    let corners = field.path.iter()
+        .filter(|&&position| field.tiles[position].is_some_and(Pipe::is_corner))
+        .map(|&(row, column)| Point::new(row as isize, column as isize))
+        .collect();
+    Polygon::new(corners).interior_points().expect("the loop is too big to measure") as u64
+}
+
+/// The pipe on each tile, if there is one, with S replaced by the pipe that joins up with its neighbours, and the
+/// tiles of the loop in the order they're followed from S, ending at S.
+#[derive(PartialEq, Debug)]
+pub struct Field {
+    tiles: Grid<Option<Pipe>>,
+    path: Vec<(usize, usize)>
+}
+
+// The two directions a pipe connects
+#[derive(PartialEq, Debug, Clone, Copy)]
+struct Pipe([Direction; 2]);
+
+impl Pipe {
+    fn connects(self, direction: Direction) -> bool {
+        self.0.contains(&direction)
     }
 
-    // now, we go back and add the start connections
-    for (x1, y1) in start_nodes {
-        let mut v: Vec<(usize, usize)> = vec![
-            (x1+1, y1),
-            (x1, y1+1)
-        ];
-        if let Some(x2) = x1.checked_sub(1) {
-            v.push((x2, y1));
-        }
-        if let Some(y2) = y1.checked_sub(1) {
-            v.push((x1, y2));
-        }
+    // The end of the pipe that isn't `from`
+    fn other(self, from: Direction) -> Direction {
+        if self.0[0] == from { self.0[1] } else { self.0[0] }
+    }
 
-        let connections: HashSet<(usize, usize)> = v.iter().flat_map(|x| {
-            nodes.get(x)
-        }).filter_map(|r| {
-            if r.connections.contains(&(x1, y1)) {
-                Some((r.x, r.y))
-            } else {
-                None
-            }
-        }).collect();
-        let mut iter = connections.iter();
-        let (first_x, first_y) = iter.next().unwrap();
-        let (second_x, second_y) = iter.next().unwrap();
-        let is_angle = first_x != second_x && first_y != second_y;
-        nodes.insert((x1, y1), Node { x: x1, y: y1, connections, is_start: true, is_angle });
+    fn is_corner(self) -> bool {
+        self.0[0] != self.0[1].reverse()
     }
-    nodes
 }
 
-fn create_node(x: usize, y: usize, c: char) -> Option<Node> {
-    // '|' is a vertical pipe connecting north and south.
-    // '-' is a horizontal pipe connecting east and west.
-    // 'L' is a 90-degree bend connecting north and east.
-    // 'J' is a 90-degree bend connecting north and west.
-    // '7' is a 90-degree bend connecting south and west.
-    // 'F' is a 90-degree bend connecting south and east.
+fn pipe(c: char) -> Option<Pipe> {
     match c {
-        '|' => y.checked_sub(1).map(|y1| Node { x, y, connections:  HashSet::from([(x, y1), (x, y+1)]), is_start: false, is_angle: false}),
-        '-' => x.checked_sub(1).map(|x1| Node { x, y, connections:  HashSet::from([(x+1, y), (x1, y)]), is_start: false, is_angle: false}),
-        'L' => y.checked_sub(1).map(|y1| Node { x, y, connections:  HashSet::from([(x+1, y), (x, y1)]), is_start: false, is_angle: true}),
-        'J' => {
-            y.checked_sub(1)
-                .map(|y1| x.checked_sub(1)
-                    .map(|x1| Node { x, y, connections:  HashSet::from([(x1, y), (x, y1)]), is_start: false, is_angle: true})).flatten()
-        },
-        '7' => x.checked_sub(1).map(|x1| Node { x, y, connections:  HashSet::from([(x, y+1), (x1, y)]), is_start: false, is_angle: true}),
-        'F' => Some(Node { x, y, connections:  HashSet::from([(x, y+1), (x+1, y)]), is_start: false, is_angle: true}),
-        'S'  => Some(Node { x, y, connections: HashSet::new(), is_start: true, is_angle: false}), // no coords means start
+        '|' => Some(Pipe([North, South])),
+        '-' => Some(Pipe([East, West])),
+        'L' => Some(Pipe([North, East])),
+        'J' => Some(Pipe([North, West])),
+        '7' => Some(Pipe([South, West])),
+        'F' => Some(Pipe([East, South])),
         _ => None
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Node {
-    x: usize,
-    y: usize,
-    connections: HashSet<(usize, usize)>,
-    is_start: bool,
-    is_angle: bool
-}
+// Reads the pipes, works out which pipe S is, and follows the loop from S until it gets back there, or says where
+// there's no S or the loop isn't closed
+fn parse_field(data: &str) -> Result<Field, ParseError> {
+    let error = |(row, column): (usize, usize), message: String| {
+        ParseError::new(row + 1, column + 1, data.lines().nth(row).unwrap_or_default(), message)
+    };
+    let symbols = Grid::parse(data, |c| "|-LJ7FS.".contains(c).then_some(c))?;
+    let mut starts = symbols.iter().filter(|(_, &c)| c == 'S').map(|(position, _)| position);
+    let start = starts.next().ok_or_else(|| error((0, 0), "there's no S to start from".to_string()))?;
+    if let Some(other) = starts.next() {
+        return Err(error(other, "there's more than one S".to_string()));
+    }
 
-impl Node {
-    fn coord(&self) -> (usize, usize) {
-        (self.x, self.y)
+    let mut tiles = symbols.map(|&c| pipe(c));
+    let joins = |tiles: &Grid<Option<Pipe>>, position, direction: Direction| {
+        tiles.next(position, direction).filter(|&next| tiles[next].is_some_and(|pipe| pipe.connects(direction.reverse())))
+    };
+    let connections = Direction::ALL.into_iter().filter(|&direction| joins(&tiles, start, direction).is_some()).collect_vec();
+    let Some((first, second)) = connections.iter().copied().collect_tuple() else {
+        return Err(error(start, format!("S connects to {} pipes, not 2", connections.len())));
+    };
+    tiles[start] = Some(Pipe([first, second]));
+
+    // S sets off towards the first of its two connections, and each pipe has to lead to one that leads back to it,
+    // or the loop isn't closed
+    let mut path = Vec::new();
+    let (mut position, mut heading) = (start, first);
+    loop {
+        let next = joins(&tiles, position, heading)
+            .ok_or_else(|| error(position, "the pipe doesn't join up with another, so the loop isn't closed".to_string()))?;
+        path.push(next);
+        if next == start {
+            return Ok(Field { tiles, path });
+        }
+        heading = tiles[next].expect("the next tile joins up, so it's a pipe").other(heading.reverse());
+        position = next;
     }
 }
 
-impl Hash for Node {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.x.hash(state);
-        self.y.hash(state);
-        self.is_start.hash(state);
-        for i in &self.connections {
-            i.hash(state);
+// The simple way to count the tiles inside the loop: go along each row, and a tile is inside if the loop has been
+// crossed an odd number of times before it. Only the pipes that go north count as crossing it, so that following the
+// loop along the row doesn't.
+fn count_inside(field: &Field) -> u64 {
+    let on_loop: HashSet<&(usize, usize)> = field.path.iter().collect();
+    let (min_row, max_row) = field.path.iter().map(|(row, _)| *row).minmax().into_option().unwrap();
+    let (min_column, max_column) = field.path.iter().map(|(_, column)| *column).minmax().into_option().unwrap();
+    let mut inside = 0;
+    for row in min_row..=max_row {
+        let mut crossings = 0;
+        for column in min_column..=max_column {
+            if on_loop.contains(&(row, column)) {
+                if field.tiles[(row, column)].is_some_and(|pipe| pipe.connects(North)) {
+                    crossings += 1;
+                }
+            } else if crossings % 2 == 1 {
+                inside += 1;
+            }
         }
     }
+    inside
+}
+
+/// Part two with the shoelace formula and Pick's theorem, and by counting the crossings along each row, for the
+/// differential tests.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let field = parse_field(input)?;
+    Ok(Some((run_day10b(&field).to_string(), count_inside(&field).to_string())))
+}
+
+/// Generates a `size` by `size` field with a single loop through S. The rest of the field is random pipes, apart from
+/// next to S, so that S only connects to the two pipes of the loop.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const TILES: [char; 7] = ['.', '|', '-', 'L', 'J', '7', 'F'];
+    let size = size.max(5);
+    let mut field: Vec<Vec<char>> = (0..size).map(|_| (0..size).map(|_| {
+        if rng.gen_bool(0.5) { '.' } else { TILES[rng.gen_range(0..TILES.len())] }
+    }).collect()).collect();
+
+    let path = random_loop(rng, size, size);
+    for (i, &(x, y)) in path.iter().enumerate() {
+        let towards = |(to_x, to_y): (usize, usize)| if to_y < y { 'N' } else if to_y > y { 'S' } else if to_x > x { 'E' } else { 'W' };
+        let before = towards(path[(i + path.len() - 1) % path.len()]);
+        let after = towards(path[(i + 1) % path.len()]);
+        field[y][x] = match (before.min(after), before.max(after)) {
+            ('N', 'S') => '|',
+            ('E', 'W') => '-',
+            ('E', 'N') => 'L',
+            ('N', 'W') => 'J',
+            ('S', 'W') => '7',
+            _ => 'F'
+        };
+    }
+
+    let (x, y) = path[rng.gen_range(0..path.len())];
+    let neighbours = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+    for (nx, ny) in neighbours.into_iter().filter(|n| n.0 < size && n.1 < size && !path.contains(n)) {
+        field[ny][nx] = '.';
+    }
+    field[y][x] = 'S';
+    field.iter().map(|row| row.iter().collect::<String>()).join("\n")
 }
 
 #[cfg(test)]
 mod test {
-    use std::collections::{HashSet, HashMap};
-    use std::ops::Deref;
     use rstest::rstest;
-    use structopt::lazy_static::lazy_static;
-    use crate::day10::{run_day10a, run_day10b, Node, generate_node_map, get_nodes_in_path};
+    use crate::common::grid::Grid;
+    use crate::common::point::Direction::*;
+    use crate::day10::{count_inside, parse_field, pipe, run_day10a, run_day10b, Field, Pipe};
+    use crate::examples::{DAY10_1, DAY10_2, DAY10_3, DAY10_4, DAY10_5};
 
     const TEST_DATA_1: &str = ".....\n\
                                .F-7.\n\
@@ -158,103 +208,68 @@ mod test {
                                .L-J.\n\
                                .....";
 
-    const TEST_DATA_2: &str = ".....\n\
-                               .S-7.\n\
-                               .|.|.\n\
-                               .L-J.\n\
-                               .....";
-
-    const TEST_DATA_3: &str = "7-F7-\n\
-                               .FJ|7\n\
-                               SJLL7\n\
-                               |F--J\n\
-                               LJ.LJ";
-
-    const TEST_DATA_4: &str = "...........\n\
-                               .S-------7.\n\
-                               .|F-----7|.\n\
-                               .||.....||.\n\
-                               .||.....||.\n\
-                               .|L-7.F-J|.\n\
-                               .|..|.|..|.\n\
-                               .L--J.L--J.\n\
-                               ...........";
-
-    const TEST_DATA_5: &str = ".F----7F7F7F7F-7....\n\
-                               .|F--7||||||||FJ....\n\
-                               .||.FJ||||||||L7....\n\
-                               FJL7L7LJLJ||LJ.L-7..\n\
-                               L--J.L7...LJS7F-7L7.\n\
-                               ....F-J..F7FJ|L7L7L7\n\
-                               ....L7.F7||L7|.L7L7|\n\
-                               .....|FJLJ|FJ|F7|.LJ\n\
-                               ....FJL-7.||.||||...\n\
-                               ....L---J.LJ.LJLJ...";
-
-    const TEST_DATA_6: &str = "FF7FSF7F7F7F7F7F---7\n\
-                               L|LJ||||||||||||F--J\n\
-                               FL-7LJLJ||||||LJL-77\n\
-                               F--JF--7||LJLJ7F7FJ-\n\
-                               L---JF-JLJ.||-FJLJJ7\n\
-                               |F|F-JF---7F7-L7L|7|\n\
-                               |FFJF7L7F-JF7|JL---7\n\
-                               7-L-JL7||F7|L7F-7F7|\n\
-                               L.L7LFJ|||||FJL7||LJ\n\
-                               L7JLJL-JLJLJL--JLJ.L";
-
-    lazy_static! {
-        static ref NODES_1: HashMap<(usize, usize), Node> = HashMap::from([
-            ((1,1), Node { x: 1, y: 1, connections: HashSet::from([(2, 1), (1, 2)]), is_start: false, is_angle: true}),
-            ((2,1), Node { x: 2, y: 1, connections: HashSet::from([(1, 1), (3, 1)]), is_start: false, is_angle: false}),
-            ((3,1), Node { x: 3, y: 1, connections: HashSet::from([(2, 1), (3, 2)]), is_start: false, is_angle: true}),
-            ((3,2), Node { x: 3, y: 2, connections: HashSet::from([(3, 1), (3, 3)]), is_start: false, is_angle: false}),
-            ((3,3), Node { x: 3, y: 3, connections: HashSet::from([(2, 3), (3, 2)]), is_start: false, is_angle: true}),
-            ((2,3), Node { x: 2, y: 3, connections: HashSet::from([(1, 3), (3, 3)]), is_start: false, is_angle: false}),
-            ((1,3), Node { x: 1, y: 3, connections: HashSet::from([(1, 2), (2, 3)]), is_start: false, is_angle: true}),
-            ((1,2), Node { x: 1, y: 2, connections: HashSet::from([(1, 1), (1, 3)]), is_start: false, is_angle: false}),
-        ]);
+    #[test]
+    fn test_pipes() {
+        let tiles = Grid::parse(TEST_DATA_1, |c| Some(pipe(c))).unwrap();
+        assert_eq!(tiles[(1, 1)], Some(Pipe([East, South])));
+        assert_eq!(tiles[(1, 2)], Some(Pipe([East, West])));
+        assert_eq!(tiles[(1, 3)], Some(Pipe([South, West])));
+        assert_eq!(tiles[(2, 3)], Some(Pipe([North, South])));
+        assert_eq!(tiles[(3, 3)], Some(Pipe([North, West])));
+        assert_eq!(tiles[(3, 1)], Some(Pipe([North, East])));
+        assert_eq!(tiles.iter().filter(|(_, tile)| tile.is_some()).count(), 8);
     }
 
-    lazy_static! {
-        static ref NODES_2: HashMap<(usize, usize), Node> = HashMap::from([
-            ((1,1), Node { x: 1, y: 1, connections: HashSet::from([(2, 1), (1, 2)]), is_start: true, is_angle: true}),
-            ((2,1), Node { x: 2, y: 1, connections: HashSet::from([(1, 1), (3, 1)]), is_start: false, is_angle: false}),
-            ((3,1), Node { x: 3, y: 1, connections: HashSet::from([(2, 1), (3, 2)]), is_start: false, is_angle: true}),
-            ((3,2), Node { x: 3, y: 2, connections: HashSet::from([(3, 1), (3, 3)]), is_start: false, is_angle: false}),
-            ((3,3), Node { x: 3, y: 3, connections: HashSet::from([(2, 3), (3, 2)]), is_start: false, is_angle: true}),
-            ((2,3), Node { x: 2, y: 3, connections: HashSet::from([(1, 3), (3, 3)]), is_start: false, is_angle: false}),
-            ((1,3), Node { x: 1, y: 3, connections: HashSet::from([(1, 2), (2, 3)]), is_start: false, is_angle: true}),
-            ((1,2), Node { x: 1, y: 2, connections: HashSet::from([(1, 1), (1, 3)]), is_start: false, is_angle: false}),
-        ]);
+    #[test]
+    fn test_parse_field() {
+        // S is the same F as in the first sample, and the loop sets off east from it
+        let expected = Field {
+            tiles: Grid::parse(TEST_DATA_1, |c| Some(pipe(c))).unwrap(),
+            path: vec![(1, 2), (1, 3), (2, 3), (3, 3), (3, 2), (3, 1), (2, 1), (1, 1)]
+        };
+        assert_eq!(parse_field(DAY10_1), Ok(expected));
     }
 
     #[test]
-    fn test_create_nodes_1() {
-        assert_eq!(generate_node_map(TEST_DATA_1), *NODES_1.deref())
+    fn test_start_must_join_the_loop() {
+        let error = parse_field("...\n.S-\n...").unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (2, 2, "S connects to 1 pipes, not 2"));
     }
 
-    #[test]
-    fn test_create_nodes_2() {
-        assert_eq!(generate_node_map(TEST_DATA_2), *NODES_2.deref())
+    #[rstest]
+    #[case("F7\nLJ", 1, 1, "there's no S to start from")]
+    #[case("S7\n|.", 1, 2, "the pipe doesn't join up with another, so the loop isn't closed")]
+    #[case("S-S\n|.|\nL-J", 1, 3, "there's more than one S")]
+    fn test_path_errors(#[case] input: &str, #[case] line: usize, #[case] column: usize, #[case] message: &str) {
+        let error = parse_field(input).unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (line, column, message));
     }
 
     #[test]
     fn test_part_1() {
-        let node_map = generate_node_map(TEST_DATA_3);
-        let path = get_nodes_in_path(&node_map);
-        assert_eq!(run_day10a(&path), 8);
+        let field = parse_field(DAY10_2).unwrap();
+        assert_eq!(run_day10a(&field), 8);
     }
 
     #[rstest]
-    #[case(TEST_DATA_2, 1)]
-    #[case(TEST_DATA_3, 1)]
-    #[case(TEST_DATA_4, 4)]
-    #[case(TEST_DATA_5, 8)]
-    #[case(TEST_DATA_6, 10)]
+    #[case(DAY10_1, 1)]
+    #[case(DAY10_2, 1)]
+    #[case(DAY10_3, 4)]
+    #[case(DAY10_4, 8)]
+    #[case(DAY10_5, 10)]
     fn test_part_2(#[case] test: &str, #[case] expected: u64) {
-        let node_map = generate_node_map(test);
-        let path = get_nodes_in_path(&node_map);
-        assert_eq!(run_day10b(&path, &node_map), expected);
+        let field = parse_field(test).unwrap();
+        assert_eq!(run_day10b(&field), expected);
+    }
+
+    #[rstest]
+    #[case(DAY10_1, 1)]
+    #[case(DAY10_3, 4)]
+    #[case(DAY10_4, 8)]
+    #[case(DAY10_5, 10)]
+    fn test_count_inside(#[case] test: &str, #[case] expected: u64) {
+        let field = parse_field(test).unwrap();
+        assert_eq!(count_inside(&field), expected);
     }
 
-}
\ No newline at end of file
+}
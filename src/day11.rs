@@ -1,27 +1,50 @@
-use crate::common::load_from;
-
-pub fn run_day() {
-    let data = load_from("day11.txt");
-    let observed_gal = build_observed_galaxy(&data);
-    let expanded_gal = expand_galaxy(&observed_gal, 2);
-    let silly_expanded_gal = expand_galaxy(&observed_gal, 1_000_000);
-    println!("Part 1: {}", day11a(&expanded_gal));
-    println!("Part 2: {}", day11a(&silly_expanded_gal));
+//! Day 11: Cosmic Expansion
+//!
+//! Sums the distances between each pair of galaxies after the empty rows and columns have grown.
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::grid::Grid;
+use crate::common::ParseError;
+use crate::solution::Solution;
+
+/// Sums the shortest paths between the galaxies when empty space is twice as big, then a million times as big.
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Parsed = Vec<(usize, usize)>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+        build_observed_galaxy(input)
+    }
+
+    fn part_one(&self, observed_gal: &Vec<(usize, usize)>) -> u64 {
+        day11a(&expand_galaxy(observed_gal, 2))
+    }
+
+    fn part_two(&self, observed_gal: &Vec<(usize, usize)>) -> u64 {
+        day11a(&expand_galaxy(observed_gal, 1_000_000))
+    }
 }
 
 fn day11a(gal: &Vec<(usize, usize)>) -> u64 {
     determine_pairs(gal).iter().sum::<usize>() as u64
 }
 
-fn build_observed_galaxy(data: &str) -> Vec<(usize, usize)> {
-    data.lines().enumerate().flat_map(|(y, line)| {
-        line.char_indices().filter_map(move |(x, char)| {
-            match char {
-                '#' => Some((x, y.clone())),
-                _ => None
-            }
-        })
-    }).collect()
+fn build_observed_galaxy(data: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    let image = Grid::parse(data, |char| {
+        match char {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None
+        }
+    })?;
+    Ok(image.iter().filter(|(_, galaxy)| **galaxy).map(|((y, x), _)| (x, y)).collect())
 }
 
 fn expand_galaxy(observed: &Vec<(usize, usize)>, replace_with: usize) -> Vec<(usize, usize)> {
@@ -30,9 +53,9 @@ fn expand_galaxy(observed: &Vec<(usize, usize)>, replace_with: usize) -> Vec<(us
     let populated_x: Vec<usize> = observed.iter().map(|(x, _)| x.clone()).collect();
     let populated_y: Vec<usize> = observed.iter().map(|(_, y)| y.clone()).collect();
 
-    // in the observed universe, figure out what lines are empty
-    let expand_x: Vec<usize> = (0usize..*populated_x.iter().max().unwrap()).filter(|entry| !populated_x.contains(entry)).collect();
-    let expand_y: Vec<usize> = (0usize..*populated_y.iter().max().unwrap()).filter(|entry| !populated_y.contains(entry)).collect();
+    // in the observed universe, figure out what lines are empty. With no galaxies at all, nothing needs to move.
+    let expand_x: Vec<usize> = (0usize..populated_x.iter().max().copied().unwrap_or(0)).filter(|entry| !populated_x.contains(entry)).collect();
+    let expand_y: Vec<usize> = (0usize..populated_y.iter().max().copied().unwrap_or(0)).filter(|entry| !populated_y.contains(entry)).collect();
 
     // now, we need to adjust each observed parameter based on the above.
     observed.iter().map(|(x, y)| {
@@ -44,27 +67,35 @@ fn expand_galaxy(observed: &Vec<(usize, usize)>, replace_with: usize) -> Vec<(us
 }
 
 fn determine_pairs(expanded: &Vec<(usize, usize)>) -> Vec<usize> {
-    expanded.iter().enumerate().take(expanded.len() - 1).flat_map(|(idx, (x1, y1))| {
+    expanded.iter().enumerate().flat_map(|(idx, (x1, y1))| {
         expanded.iter().skip(idx+1).map(|(x2, y2)| x1.abs_diff(*x2) + y1.abs_diff(*y2))
     }).collect()
 }
 
+/// Generates a `size` by `size` image. About one row and column in ten is empty, and elsewhere about one place in
+/// twelve has a galaxy. There are always at least two galaxies, so that there's a pair of them, even if that means
+/// putting one in an empty row or column of a small image.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let size = size.max(2);
+    let empty_rows: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.1)).collect();
+    let empty_columns: Vec<bool> = (0..size).map(|_| rng.gen_bool(0.1)).collect();
+    let mut image: Vec<Vec<char>> = (0..size).map(|row| (0..size).map(|column| {
+        if !empty_rows[row] && !empty_columns[column] && rng.gen_bool(1.0 / 12.0) { '#' } else { '.' }
+    }).collect()).collect();
+    while image.iter().flatten().filter(|c| **c == '#').count() < 2 {
+        image[rng.gen_range(0..size)][rng.gen_range(0..size)] = '#';
+    }
+    image.iter().map(|row| row.iter().collect::<String>()).join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use std::ops::Deref;
     use structopt::lazy_static::lazy_static;
-    use crate::day11::{build_observed_galaxy, day11a, determine_pairs, expand_galaxy};
-
-    const TEST_DATA_1: &str = "...#......\n\
-                               .......#..\n\
-                               #.........\n\
-                               ..........\n\
-                               ......#...\n\
-                               .#........\n\
-                               .........#\n\
-                               ..........\n\
-                               .......#..\n\
-                               #...#.....";
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::day11::{build_observed_galaxy, day11a, determine_pairs, expand_galaxy, generate};
+    use crate::examples::DAY11 as TEST_DATA_1;
 
     lazy_static! {
         static ref OBESERVABLE_GAL_PAIRS: Vec<(usize, usize)> = vec![
@@ -112,7 +143,7 @@ mod tests {
 
     #[test]
     fn test_build_observed_galaxy() {
-        assert_eq!(build_observed_galaxy(TEST_DATA_1), *OBESERVABLE_GAL_PAIRS.deref())
+        assert_eq!(build_observed_galaxy(TEST_DATA_1).as_ref(), Ok(OBESERVABLE_GAL_PAIRS.deref()))
     }
 
     #[test]
@@ -133,6 +164,22 @@ mod tests {
         assert_eq!(determine_pairs(&vec![(0, 11), (5, 11)]), vec![5]); // 8 and 9
     }
 
+    #[test]
+    fn test_empty_universe() {
+        let observed = build_observed_galaxy("...\n...").unwrap();
+        assert_eq!(day11a(&expand_galaxy(&observed, 2)), 0);
+        let lonely = build_observed_galaxy("...\n.#.").unwrap();
+        assert_eq!(day11a(&expand_galaxy(&lonely, 2)), 0);
+    }
+
+    #[test]
+    fn test_generate_has_a_pair_of_galaxies() {
+        for (size, seed) in (0..4).flat_map(|size| (0..20).map(move |seed| (size, seed))) {
+            let image = generate(&mut StdRng::seed_from_u64(seed), size);
+            assert!(build_observed_galaxy(image.as_str()).unwrap().len() >= 2, "size {} seed {}: {}", size, seed, image);
+        }
+    }
+
     #[test]
     fn test_day11a() {
         assert_eq!(day11a(EXPANDED_GAL_PAIRS.deref()), 374);
@@ -1,13 +1,39 @@
+//! Day 12: Hot Springs
+//!
+//! Counts the arrangements of damaged springs that match each row's groups, with the rows unfolded five times in
+//! part two.
+
 use std::collections::HashMap;
 use std::iter::once;
-use crate::common::load_from;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines};
+use crate::solution::Solution;
 use crate::day12::Entry::{Damaged, Operational, Unknown};
 
-pub fn run_day() {
-    let data = load_from("day12.txt");
-    let rows = parse_lines(data.as_str());
-    println!("Part 1: {}", day12a(&rows));
-    println!("Part 2: {}", day12b(&rows));
+/// Sums the number of possible arrangements of each row, folded and then unfolded.
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+
+    type Parsed = Vec<Row>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Row>, ParseError> {
+        parse_lines(input)
+    }
+
+    fn part_one(&self, rows: &Vec<Row>) -> u64 {
+        day12a(rows)
+    }
+
+    fn part_two(&self, rows: &Vec<Row>) -> u64 {
+        day12b(rows)
+    }
 }
 
 fn day12a(rows:  &Vec<Row>) -> u64 {
@@ -103,26 +129,27 @@ fn find_memoized_combinations(row: &Row) -> u64 {
     check_next(row.entries.clone(), None, row.contiguous.clone(), &mut combinations_for)
 }
 
-fn parse_lines(string: &str) -> Vec<Row> {
-    string.lines().map(parse_line).collect()
+fn parse_lines(string: &str) -> Result<Vec<Row>, ParseError> {
+    lines(string).map(parse_line).collect()
 }
 
-fn parse_line(line: &str) -> Row {
-    let mut split = line.split_whitespace();
-    let entries: Vec<Entry> = split.next().unwrap().chars().map(|c| {
+fn parse_line(line: Line) -> Result<Row, ParseError> {
+    let (springs, groups) = line.split_once(line.text, " ", "expected the springs and the groups of damaged ones, like ???.### 1,1,3")?;
+    let entries: Vec<Entry> = springs.char_indices().map(|(index, c)| {
         match c {
-            '.' => Operational,
-            '#' => Damaged,
-            '?' => Unknown,
-            a@_ => panic!("Unexpected char: {}", a)
+            '.' => Ok(Operational),
+            '#' => Ok(Damaged),
+            '?' => Ok(Unknown),
+            _ => Err(line.error(&springs[index..], format!("unexpected {:?}", c)))
         }
-    }).collect();
-    let contiguous: Vec<u64> = split.next().unwrap().split(",").map(|e| str::parse::<u64>(e).unwrap()).collect();
-    Row { entries, contiguous }
+    }).collect::<Result<_, _>>()?;
+    let contiguous: Vec<u64> = line.list(groups, ",", |group| line.number(group))?;
+    Ok(Row { entries, contiguous })
 }
 
+/// A row of springs, with the sizes of the groups of damaged springs.
 #[derive(PartialEq, Debug, Hash)]
-struct Row {
+pub struct Row {
     entries: Vec<Entry>,
     contiguous: Vec<u64>
 }
@@ -142,20 +169,31 @@ enum Entry {
     Unknown
 }
 
+/// Generates `size` rows of between three and twenty springs. Each row starts as an arrangement that works, with at
+/// least one damaged spring, and then about half of its springs are hidden behind a `?`.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1)).map(|_| {
+        let length = rng.gen_range(3..=20);
+        let mut springs: Vec<char> = (0..length).map(|_| if rng.gen_bool(0.4) { '#' } else { '.' }).collect();
+        if !springs.contains(&'#') {
+            springs[rng.gen_range(0..length)] = '#';
+        }
+        let groups = springs.split(|c| *c == '.').filter(|group| !group.is_empty()).map(|group| group.len()).join(",");
+        let row: String = springs.iter().map(|c| if rng.gen_bool(0.5) { '?' } else { *c }).collect();
+        format!("{} {}", row, groups)
+    }).join("\n")
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
+    use crate::common::ParseError;
+    use crate::common::parse::lines;
     use crate::day12::{Row, parse_line, parse_lines, day12a, find_memoized_combinations, day12b};
     use crate::day12::Entry::*;
-
-    const TEST_DATA_1: &str = "???.### 1,1,3\n\
-                               .??..??...?##. 1,1,3\n\
-                               ?#?#?#?#?#?#?#? 1,3,1,6\n\
-                               ????.#...#... 4,1,1\n\
-                               ????.######..#####. 1,6,5\n\
-                               ?###???????? 3,2,1";
+    use crate::examples::DAY12 as TEST_DATA_1;
 
     lazy_static! {
         static ref PARSED_DATA_1: Vec<Row> = vec![
@@ -209,14 +247,22 @@ mod test {
 
     #[test]
     fn test_parse_line() {
-        for (idx, line) in TEST_DATA_1.lines().enumerate() {
-            assert_eq!(parse_line(line), *PARSED_DATA_1.deref().get(idx).unwrap());
+        for (idx, line) in lines(TEST_DATA_1).enumerate() {
+            assert_eq!(parse_line(line).as_ref(), Ok(PARSED_DATA_1.deref().get(idx).unwrap()));
         }
     }
 
     #[test]
     fn test_parse_lines() {
-        assert_eq!(parse_lines(TEST_DATA_1), *PARSED_DATA_1.deref());
+        assert_eq!(parse_lines(TEST_DATA_1).unwrap(), *PARSED_DATA_1.deref());
+    }
+
+    #[rstest]
+    #[case("???.### 1,1,3\n.??..??...?##.", ParseError::new(2, 1, ".??..??...?##.", "expected the springs and the groups of damaged ones, like ???.### 1,1,3"))]
+    #[case("??x.### 1,1,3", ParseError::new(1, 3, "??x.### 1,1,3", "unexpected 'x'"))]
+    #[case("???.### 1,,3", ParseError::new(1, 11, "???.### 1,,3", "expected a number, found \"\""))]
+    fn test_parse_lines_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_lines(input), Err(expected));
     }
 
 }
\ No newline at end of file
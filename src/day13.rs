@@ -1,66 +1,87 @@
-use std::str::Lines;
-use std::iter::Peekable;
+//! Day 13: Point of Incidence
+//!
+//! Finds the line of reflection in each pattern, and in part two the one that appears once a smudge is fixed.
+
 use itertools::Itertools;
-use crate::common::load_from;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::grid::Grid;
+use crate::common::ParseError;
+use crate::solution::Solution;
+use crate::{debug, info, trace};
+
+/// Summarises the reflections in the patterns, without and then with the smudge.
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+
+    type Parsed = Vec<GroundMap>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<GroundMap>, ParseError> {
+        parse_lines(input)
+    }
 
-pub fn run_day() {
-    let data = load_from("day13.txt");
-    let maps = parse_lines(data.as_str());
-    println!("Part 1: {}", day13a(&maps));
-    println!("Part 2: {}", day13b(&maps));
+    fn part_one(&self, maps: &Vec<GroundMap>) -> u64 {
+        day13a(maps)
+    }
+
+    fn part_two(&self, maps: &Vec<GroundMap>) -> u64 {
+        day13b(maps)
+    }
 }
 
 fn day13a(maps: &Vec<GroundMap>) -> u64 {
-    maps.iter().map(|x| {
-        x.find_reflection().unwrap_or_else(|| x.transpose().find_reflection().unwrap())
+    maps.iter().enumerate().map(|(index, x)| {
+        let summary = x.find_reflection().unwrap_or_else(|| x.transpose().find_reflection().unwrap());
+        info!("pattern {} summarises to {}", index + 1, summary);
+        summary
     }).sum()
 }
 
 fn day13b(maps: &Vec<GroundMap>) -> u64 {
-    maps.iter().map(find_and_fix_smudge).sum()
+    maps.iter().enumerate().map(|(index, x)| {
+        let summary = find_and_fix_smudge(x);
+        info!("pattern {} summarises to {} once the smudge is fixed", index + 1, summary);
+        summary
+    }).sum()
 }
 
 fn find_and_fix_smudge(map: &GroundMap) -> u64 {
     map.fix_smudge().unwrap_or_else(|| map.transpose().fix_smudge().unwrap())
 }
 
-fn parse_lines(test_data: &str) -> Vec<GroundMap> {
-    let mut lines = test_data.lines().peekable();
+fn parse_lines(test_data: &str) -> Result<Vec<GroundMap>, ParseError> {
+    let lines: Vec<&str> = test_data.lines().collect();
     let mut maps: Vec<GroundMap> = Vec::new();
-    while let Some(x) = lines.peek() {
+    let mut start = 0;
+    while start < lines.len() {
         // Ignore the blank lines
-        if !x.is_empty() {
-            maps.push(parse_map(&mut lines));
-        } else {
-            lines.next(); // forces the next line to iterate.
+        if lines[start].is_empty() {
+            start += 1;
+            continue;
         }
+        let end = lines[start..].iter().position(|x| x.is_empty()).map_or(lines.len(), |length| start + length);
+        // The grid counts its lines from the start of the pattern, not the start of the input
+        let map = parse_map(lines[start..end].join("\n").as_str()).map_err(|e| ParseError { line: e.line + start, ..e })?;
+        maps.push(map);
+        start = end;
     }
-    maps
-}
-
-fn parse_map(data: &mut Peekable<Lines>) -> GroundMap {
-    let mut rows: Vec<Vec<Ground>> = Vec::new();
-    while let Some(x) = data.next_if(|x| !(*x).is_empty()) {
-        rows.push(parse_line(x));
-    }
-    let no_of_columns = rows.first().unwrap().len();
-    let no_of_rows = rows.len();
-    GroundMap {
-        rows,
-        no_of_columns,
-        no_of_rows,
-        is_transposed: false
-    }
+    Ok(maps)
 }
 
-fn parse_line(line: &str) -> Vec<Ground> {
-    line.chars().map(|x| {
+fn parse_map(data: &str) -> Result<GroundMap, ParseError> {
+    let ground = Grid::parse(data, |x| {
         match x {
-            '#' => Ground::Rock,
-            '.' => Ground::Ash,
-            _ => panic!("Unexpected character")
+            '#' => Some(Ground::Rock),
+            '.' => Some(Ground::Ash),
+            _ => None
         }
-    }).collect()
+    })?;
+    Ok(GroundMap { ground, is_transposed: false })
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -70,32 +91,26 @@ enum Ground {
 }
 
 
+/// One pattern of ash and rocks.
 #[derive(PartialEq, Debug, Clone)]
-struct GroundMap {
-    rows: Vec<Vec<Ground>>,
-    no_of_rows: usize,
-    no_of_columns: usize,
+pub struct GroundMap {
+    ground: Grid<Ground>,
     is_transposed: bool
 }
 
 impl GroundMap {
     fn transpose(&self) -> GroundMap {
-        let mut rows: Vec<Vec<Ground>> = Vec::new();
-        for col in 0..self.no_of_columns {
-            let mut new_row: Vec<Ground> = Vec::new();
-            for row in 0..self.no_of_rows {
-                unsafe { // We know this will all be here.
-                    new_row.push(self.rows.get_unchecked(row).get_unchecked(col).clone());
-                }
-            }
-            rows.push(new_row);
-        }
-        GroundMap { rows, no_of_rows: self.no_of_columns, no_of_columns: self.no_of_rows, is_transposed: !self.is_transposed }
+        GroundMap { ground: self.ground.transpose(), is_transposed: !self.is_transposed }
+    }
+
+    // What the rows of this map are in the original pattern, for logging
+    fn line_kind(&self) -> &'static str {
+        if self.is_transposed { "column" } else { "row" }
     }
 
     fn find_reflection(&self) -> Option<u64> {
-        let rows_1 = self.rows.iter().take(self.no_of_rows - 1);
-        let rows_2 = self.rows.iter().skip(1);
+        let rows_1 = self.ground.rows().take(self.ground.height() - 1);
+        let rows_2 = self.ground.rows().skip(1);
         let potential_reflections: Vec<usize> = rows_1
             .zip(rows_2)
             .enumerate()
@@ -107,10 +122,15 @@ impl GroundMap {
                 }
             })
             .collect();
+        debug!("there could be a line of reflection after {}s {:?}", self.line_kind(), potential_reflections);
 
         // if reflection is between zero and one, we get 1, so we need to do
         // idx * 2 with a reverse iterator.
-        potential_reflections.iter().filter(|x| self.check_reflection_around(**x, None)).next().map(|x| if self.is_transposed {
+        potential_reflections.iter().find(|x| {
+            let reflects = self.check_reflection_around(**x, None);
+            trace!("the line after {} {} {}", self.line_kind(), x, if reflects { "reflects" } else { "doesn't reflect all the way" });
+            reflects
+        }).map(|x| if self.is_transposed {
             x.clone() as u64
         } else {
             (*x as u64) * 100
@@ -118,14 +138,14 @@ impl GroundMap {
     }
 
     fn check_reflection_around(&self, reflection_line: usize, ignore: Option<(usize, usize)>) -> bool {
-        let (actual_reflection_line, rows_to_check, no_of_rows): (usize, Vec<Vec<Ground>>, usize) = if let Some((first, second)) = ignore {
+        let (actual_reflection_line, rows_to_check, no_of_rows): (usize, Vec<&[Ground]>, usize) = if let Some((first, second)) = ignore {
             (
                 reflection_line - 1,
-                self.rows.iter().enumerate().filter(|(idx, _)| *idx != first && *idx != second).map(|x| x.1.clone()).collect(),
-                self.no_of_rows - 2
+                self.ground.rows().enumerate().filter(|(idx, _)| *idx != first && *idx != second).map(|x| x.1).collect(),
+                self.ground.height() - 2
             )
         } else {
-            (reflection_line, self.rows.clone(), self.no_of_rows)
+            (reflection_line, self.ground.rows().collect(), self.ground.height())
         };
 
         if actual_reflection_line == 0 || actual_reflection_line == no_of_rows {
@@ -140,17 +160,17 @@ impl GroundMap {
                 0
             };
 
-            let list: Vec<&Vec<Ground>> = rows_to_check.iter().skip(start).take(window * 2).collect();
-            let reverse_list: Vec<&Vec<Ground>> = rows_to_check.iter().skip(start).take(window * 2).rev().collect();
+            let list: Vec<&&[Ground]> = rows_to_check.iter().skip(start).take(window * 2).collect();
+            let reverse_list: Vec<&&[Ground]> = rows_to_check.iter().skip(start).take(window * 2).rev().collect();
             list.eq(&reverse_list)
         }
     }
 
     fn fix_smudge(&self) -> Option<u64> {
         fn check_for_reflection(s: &GroundMap, row_idx_1: usize) -> Option<u64> {
-            ((row_idx_1 + 1)..s.no_of_rows).step_by(2).filter_map(|row_idx_2| {  // by 2 as the reflection must have 0, 2, 4 between
-                let candidate: Vec<_> = s.rows.get(row_idx_1).unwrap().iter()
-                    .zip_eq(s.rows.get(row_idx_2).unwrap().iter())
+            ((row_idx_1 + 1)..s.ground.height()).step_by(2).filter_map(|row_idx_2| {  // by 2 as the reflection must have 0, 2, 4 between
+                let candidate: Vec<_> = s.ground.row(row_idx_1).iter()
+                    .zip_eq(s.ground.row(row_idx_2).iter())
                     .enumerate()
                     .filter_map(|(idx, (first, second))| {
                         if first.eq(second) {
@@ -161,6 +181,7 @@ impl GroundMap {
                     })
                     .collect();
                 if candidate.len() == 1 {
+                    trace!("{}s {} and {} only differ at {}, which could be the smudge", s.line_kind(), row_idx_1, row_idx_2, candidate[0]);
                     // reflection line
                     // The +1 is due to the fact that the lines are always an odd number apart.
                     // If 0 and 1 are the removed lines, the line is at 1
@@ -168,6 +189,7 @@ impl GroundMap {
                     // If 0 and 5 are the removed lines, the line is at 3
                     let original_reflection_line = row_idx_1 + (row_idx_2 - row_idx_1 + 1) / 2;
                     if s.check_reflection_around(original_reflection_line, Some((row_idx_1, row_idx_2))) {
+                        debug!("fixing the smudge between {}s {} and {} gives a reflection after {} {}", s.line_kind(), row_idx_1, row_idx_2, s.line_kind(), original_reflection_line);
                         Some(original_reflection_line as u64)
                     } else {
                         None
@@ -179,7 +201,7 @@ impl GroundMap {
 
         }
 
-        (0..self.no_of_rows)
+        (0..self.ground.height())
             .filter_map(|idx| check_for_reflection(self, idx))
             .next()
             .map(|x| {
@@ -199,34 +221,75 @@ impl GroundMap {
 //     }).join("")
 // }
 
+/// Generates `size` patterns. Each one has exactly one perfect line of reflection and exactly one other line that is
+/// only out by the smudge, which is what the two parts look for.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1)).map(|_| {
+        let pattern = loop {
+            if let Some(pattern) = random_pattern(rng) {
+                break pattern;
+            }
+        };
+        pattern.iter().map(|row| row.iter().collect::<String>()).join("\n")
+    }).join("\n\n")
+}
+
+// A pattern that reflects perfectly across a column, and across a row apart from one smudge, turned on its side half
+// the time. None if it happens to reflect anywhere else as well.
+fn random_pattern(rng: &mut StdRng) -> Option<Vec<Vec<char>>> {
+    let (height, width) = (rng.gen_range(5..=17), rng.gen_range(5..=17));
+    let mut rows: Vec<Vec<char>> = (0..height).map(|_| (0..width).map(|_| if rng.gen_bool(0.5) { '#' } else { '.' }).collect()).collect();
+
+    let column = rng.gen_range(1..width);
+    let columns_reflected = column.min(width - column);
+    for row in rows.iter_mut() {
+        for k in 0..columns_reflected {
+            row[column + k] = row[column - 1 - k];
+        }
+    }
+    let line = rng.gen_range(1..height);
+    let rows_reflected = line.min(height - line);
+    for k in 0..rows_reflected {
+        rows[line + k] = rows[line - 1 - k].clone();
+    }
+
+    // The smudge goes in a column with nothing to reflect it, so it can't spoil the perfect line
+    let spare: Vec<usize> = (0..width).filter(|x| x + columns_reflected < column || *x >= column + columns_reflected).collect();
+    let x = *spare.choose(rng)?;
+    let y = rng.gen_range(line - rows_reflected..line + rows_reflected);
+    rows[y][x] = if rows[y][x] == '#' { '.' } else { '#' };
+
+    let turned: Vec<Vec<char>> = (0..width).map(|x| rows.iter().map(|row| row[x]).collect()).collect();
+    let differences: Vec<usize> = differences_across(&rows).into_iter().chain(differences_across(&turned)).collect();
+    if differences.iter().filter(|d| **d == 0).count() != 1 || differences.iter().filter(|d| **d == 1).count() != 1 {
+        return None;
+    }
+    Some(if rng.gen_bool(0.5) { turned } else { rows })
+}
+
+// How many places differ across each line between two rows
+fn differences_across(rows: &[Vec<char>]) -> Vec<usize> {
+    (1..rows.len()).map(|line| {
+        (0..line.min(rows.len() - line))
+            .map(|k| rows[line - 1 - k].iter().zip(&rows[line + k]).filter(|(a, b)| a != b).count())
+            .sum()
+    }).collect()
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
+    use crate::common::grid::Grid;
     use crate::day13::{GroundMap, parse_lines, day13a, day13b, find_and_fix_smudge};
     use crate::day13::Ground::*;
-
-    const TEST_DATA: &str = "#.##..##.\n\
-                             ..#.##.#.\n\
-                             ##......#\n\
-                             ##......#\n\
-                             ..#.##.#.\n\
-                             ..##..##.\n\
-                             #.#.##.#.\n\
-                             \n\
-                             #...##..#\n\
-                             #....#..#\n\
-                             ..##..###\n\
-                             #####.##.\n\
-                             #####.##.\n\
-                             ..##..###\n\
-                             #....#..#";
+    use crate::examples::DAY13 as TEST_DATA;
 
     lazy_static! {
         static ref PARSED_DATA: Vec<GroundMap> = vec![
             GroundMap {
-                rows: vec![
+                ground: Grid::from_rows(vec![
                     vec![Rock, Ash, Rock, Rock, Ash, Ash, Rock, Rock, Ash],
                     vec![Ash, Ash, Rock, Ash, Rock, Rock, Ash, Rock, Ash],
                     vec![Rock, Rock, Ash, Ash, Ash, Ash, Ash, Ash, Rock],
@@ -234,13 +297,11 @@ mod test {
                     vec![Ash, Ash, Rock, Ash, Rock, Rock, Ash, Rock, Ash],
                     vec![Ash, Ash, Rock, Rock, Ash, Ash, Rock, Rock, Ash],
                     vec![Rock, Ash, Rock, Ash, Rock, Rock, Ash, Rock, Ash]
-                ],
-                no_of_rows: 7,
-                no_of_columns: 9,
+                ]),
                 is_transposed: false
             },
             GroundMap {
-                rows: vec![
+                ground: Grid::from_rows(vec![
                     vec![Rock, Ash, Ash, Ash, Rock, Rock, Ash, Ash, Rock],
                     vec![Rock, Ash, Ash, Ash, Ash, Rock, Ash, Ash, Rock],
                     vec![Ash, Ash, Rock, Rock, Ash, Ash, Rock, Rock, Rock],
@@ -248,9 +309,7 @@ mod test {
                     vec![Rock, Rock, Rock, Rock, Rock, Ash, Rock, Rock, Ash],
                     vec![Ash, Ash, Rock, Rock, Ash, Ash, Rock, Rock, Rock],
                     vec![Rock, Ash, Ash, Ash, Ash, Rock, Ash, Ash, Rock],
-                ],
-                no_of_rows: 7,
-                no_of_columns: 9,
+                ]),
                 is_transposed: false
             },
         ];
@@ -259,7 +318,7 @@ mod test {
     lazy_static! {
         static ref TRANSPOSED_PARSED_DATA_1: GroundMap =
             GroundMap {
-                rows: vec![
+                ground: Grid::from_rows(vec![
                     vec![Rock, Ash, Rock, Rock, Ash, Ash, Rock],
                     vec![Ash, Ash, Rock, Rock, Ash, Ash, Ash],
                     vec![Rock, Rock, Ash, Ash, Rock, Rock, Rock],
@@ -269,16 +328,20 @@ mod test {
                     vec![Rock, Ash, Ash, Ash, Ash, Rock, Ash],
                     vec![Rock, Rock, Ash, Ash, Rock, Rock, Rock],
                     vec![Ash, Ash, Rock, Rock, Ash, Ash, Ash]
-                ],
-                no_of_rows: 9,
-                no_of_columns: 7,
+                ]),
                 is_transposed: true
             };
     }
 
     #[test]
     fn test_parse_lines() {
-        assert_eq!(parse_lines(TEST_DATA), *PARSED_DATA.deref());
+        assert_eq!(parse_lines(TEST_DATA).as_ref(), Ok(PARSED_DATA.deref()));
+    }
+
+    #[test]
+    fn test_parse_lines_error_line() {
+        let error = parse_lines("#.\n.#\n\n#.\n.x").unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (5, 2, "unexpected 'x'"));
     }
 
     #[test]
@@ -320,9 +383,7 @@ mod test {
     fn test_find_and_fix_smudge_1() {
         let gm = &PARSED_DATA.deref().get(0).unwrap().transpose();
         let sut = GroundMap {
-            rows: gm.rows.clone(),
-            no_of_rows: gm.no_of_rows,
-            no_of_columns: gm.no_of_columns,
+            ground: gm.ground.clone(),
             is_transposed: false
         };
         assert_eq!(find_and_fix_smudge(&sut), 3);
@@ -1,16 +1,39 @@
-use std::collections::HashMap;
-use std::iter::Peekable;
-use std::str::Lines;
+//! Day 14: Parabolic Reflector Dish
+//!
+//! Tilts the dish and weighs the load on the north beams. Part two spins it a billion times by finding the cycle.
+
 use itertools::Itertools;
-use tailcall::tailcall;
-use crate::common::load_from;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::cycle;
+use crate::common::grid::Grid;
+use crate::common::ParseError;
+use crate::differential::Comparison;
+use crate::solution::Solution;
+use crate::{debug, info};
 use crate::day14::RockType::{Cube, Rounded};
 
-pub fn run_day() {
-    let data = load_from("day14.txt");
-    let dish: Dish = Dish::parse(data.as_str());
-    println!("Part 1: {}", day14a(&dish));
-    println!("Part 2: {}", day14b(&dish));
+/// The load on the north beams after tilting north once, then after a billion spin cycles.
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+
+    type Parsed = Dish;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Dish, ParseError> {
+        Dish::parse(input)
+    }
+
+    fn part_one(&self, dish: &Dish) -> u64 {
+        day14a(dish)
+    }
+
+    fn part_two(&self, dish: &Dish) -> u64 {
+        day14b(dish)
+    }
 }
 
 fn day14a(dish: &Dish) -> u64 {
@@ -21,137 +44,102 @@ fn day14b(dish: &Dish) -> u64 {
     dish.cycle(1_000_000_000).calculate_load_north()
 }
 
+/// The rounded and cube-shaped rocks on the dish.
 #[derive(PartialEq, Debug)]
-struct Dish {
-
-    rock_locations: HashMap<(usize, usize), RockType>,
-    no_of_rows: usize,
-    no_of_columns: usize
-
+pub struct Dish {
+    rocks: Grid<Option<RockType>>
 }
 
 impl Dish {
 
-    fn parse(data: &str) -> Dish {
-        let mut lines: Peekable<Lines> = data.lines().peekable();
-        let no_of_columns = lines.peek().unwrap().len();
-        let no_of_rows = lines.count();
-
-        let rock_locations: HashMap<(usize, usize), RockType> = data.lines().enumerate().flat_map(|(y, line)| {
-            line.chars().enumerate().filter_map(move |(x, c)| {
-                match c {
-                    'O' => Some(((x.clone(), y.clone()), Rounded)),
-                    '#' => Some(((x.clone(), y.clone()), Cube)),
-                    _ => None
-                }
-            })
-        }).collect();
-
-        Dish {
-            rock_locations,
-            no_of_rows,
-            no_of_columns
-        }
+    fn parse(data: &str) -> Result<Dish, ParseError> {
+        let rocks = Grid::parse(data, |c| match c {
+            'O' => Some(Some(Rounded)),
+            '#' => Some(Some(Cube)),
+            '.' => Some(None),
+            _ => None
+        })?;
+        Ok(Dish { rocks })
     }
 
     fn cycle(&self, max_count: u64) -> Dish {
-        let mut cache: HashMap<Vec<(usize, usize)>, u64> = HashMap::new();
-
-        #[tailcall]
-        fn cycles(current_position: HashMap<(usize, usize), RockType>, cols: usize, rows: usize, count: u64, max_count: u64, cache: &mut HashMap<Vec<(usize, usize)>, u64>) -> HashMap<(usize, usize), RockType> {
-            let cycled = perform_cycle(&current_position, cols, rows);
-            let cache_value: Vec<(usize, usize)> = cycled.iter().filter_map(|((x, y), rock)| match rock {
-                Cube => None,
-                Rounded => Some((x.clone(), y.clone()))
-            }).sorted().collect();
-            if let Some(original_index) = cache.insert(cache_value, count) {
-                // we have a cycle
-                let cycle_length = count - original_index;
-                let left_to_go = (max_count - count) % cycle_length;
-
-                // reconstruct what we want (rounded rocks), we don't need the cube ones for our final calculation
-                cache.iter().find(|(_, value)| **value == original_index + left_to_go).unwrap().0.iter().map(|(x, y)| ((x.clone(), y.clone()), RockType::Rounded)).collect()
-            } else {
-                cycles(cycled, cols, rows, count + 1, max_count, cache)
+        let found = cycle::find_by_hashing(self.rocks.clone(), perform_cycle);
+        info!("spin cycle {} leaves the rocks where cycle {} did, so they repeat every {} cycles", found.prefix + found.period, found.prefix, found.period);
+        let rocks = match found.state_after(max_count as usize) {
+            Some(rocks) => {
+                debug!("cycle {} looks like cycle {}", max_count, found.prefix + (max_count as usize - found.prefix) % found.period);
+                rocks.clone()
             }
-        }
-
-        Dish {
-            rock_locations: cycles(self.rock_locations.clone(), self.no_of_columns, self.no_of_rows, 1, max_count, &mut cache),
-            no_of_rows: self.no_of_rows,
-            no_of_columns: self.no_of_columns
-        }
-
+            // it's done before it gets round to repeating
+            None => (0..max_count).fold(self.rocks.clone(), |rocks, _| perform_cycle(&rocks))
+        };
+        Dish { rocks }
     }
 
     fn tilt_north(&self) -> Dish {
         Dish {
-            rock_locations: tilt(&self.rock_locations, self.no_of_columns),
-            no_of_rows: self.no_of_rows,
-            no_of_columns: self.no_of_columns
+            rocks: tilt(&self.rocks)
         }
     }
 
     fn calculate_load_north(&self) -> u64 {
-        self.rock_locations.iter().filter_map(|((_, row), rock)| {
+        self.rocks.iter().filter_map(|((row, _), rock)| {
             match rock {
-                Rounded => Some((self.no_of_columns - row) as u64),
-                Cube => None
+                Some(Rounded) => Some((self.rocks.height() - row) as u64),
+                _ => None
             }
         }).sum()
     }
 }
 
-fn tilt(original_locations: &HashMap<(usize, usize), RockType>, cols: usize) -> HashMap<(usize, usize), RockType> {
-    let mut rock_locations: HashMap<(usize, usize), RockType> = HashMap::new();
-    // work on a per column basis:
-    for col in 0..cols {
-        // create the column
-        let vec: Vec<(usize, RockType)> = original_locations.iter().filter_map(|((x, y), rock_type)| {
-            if *x == col {
-                Some((y.clone(), rock_type.clone()))
-            } else {
-                None
-            }
-        })
-            .sorted_by_cached_key(|(idx, _)| idx.clone())
-            .collect();
-
+fn tilt(original: &Grid<Option<RockType>>) -> Grid<Option<RockType>> {
+    let mut rocks = original.clone();
+    // work on a per column basis, rolling each rounded rock up to the rock above it:
+    for col in 0..rocks.width() {
         let mut next_idx = 0;
-        for (idx, rock_type) in vec {
-            match rock_type {
-                Cube => {
-                    rock_locations.insert((col, idx), rock_type);
-                    next_idx = idx + 1;
+        for row in 0..rocks.height() {
+            match rocks[(row, col)] {
+                Some(Cube) => next_idx = row + 1,
+                Some(Rounded) => {
+                    rocks[(row, col)] = None;
+                    rocks[(next_idx, col)] = Some(Rounded);
+                    next_idx += 1;
                 },
-                Rounded => {
-                    rock_locations.insert((col, next_idx), rock_type);
-                    next_idx = next_idx + 1;
-                }
+                None => {}
             }
         }
     }
+    rocks
+}
 
-    rock_locations
+// Tilts north, west, south and then east. Turning the dish clockwise after each tilt brings the next side to the top,
+// and after four turns it's back the right way round.
+fn perform_cycle(current_position: &Grid<Option<RockType>>) -> Grid<Option<RockType>> {
+    (0..4).fold(current_position.clone(), |rocks, _| tilt(&rocks).rotate_clockwise())
 }
 
-fn rotate(x: usize, y: usize, cols_after_rotation: usize) -> (usize, usize) {
-    // rotating 90deg.
-    // x => y, y => max_cols - x - 1
-    // (0, 0) -> (9, 0)
-    // (1, 1) -> (8, 1)
-    (cols_after_rotation - y - 1, x)
+// The simple way: spin the dish every time, and note the load after each of the given numbers of spins, which must be
+// in order.
+fn spin_every_time(dish: &Dish, spins: &[u64]) -> Vec<u64> {
+    let mut rocks = dish.rocks.clone();
+    let mut loads = Vec::new();
+    for spin in 1..=*spins.last().unwrap() {
+        rocks = perform_cycle(&rocks);
+        if spins.contains(&spin) {
+            let spun = Dish { rocks: rocks.clone() };
+            loads.push(spun.calculate_load_north());
+        }
+    }
+    loads
 }
 
-fn perform_cycle(current_position: &HashMap<(usize, usize), RockType>, cols: usize, rows: usize) -> HashMap<(usize, usize), RockType> {
-    let north = tilt(current_position, cols);
-    let rotate_north_to_west = north.iter().map(|((x, y), rock)| (rotate(*x, *y, rows), rock.clone())).collect();
-    let west = tilt(&rotate_north_to_west, rows);
-    let rotate_west_to_south = west.iter().map(|((x, y), rock)| (rotate(*x, *y, cols), rock.clone())).collect();
-    let south = tilt(&rotate_west_to_south, cols);
-    let rotate_south_to_east = south.iter().map(|((x, y), rock)| (rotate(*x, *y, rows), rock.clone())).collect();
-    let east = tilt(&rotate_south_to_east, rows);
-    east.iter().map(|((x, y), rock)| (rotate(*x, *y, cols), rock.clone())).collect() // back to north
+/// The load after a few different numbers of spins, skipping the repeats and spinning every time, for the
+/// differential tests.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    const SPINS: [u64; 3] = [500, 999, 1234];
+    let dish = Dish::parse(input)?;
+    let skipped = SPINS.map(|spins| dish.cycle(spins).calculate_load_north());
+    Ok(Some((skipped.iter().join(", "), spin_every_time(&dish, &SPINS).iter().join(", "))))
 }
 
 #[derive(PartialEq, Debug, Eq, Hash, Clone, Copy)]
@@ -160,25 +148,24 @@ enum RockType {
     Rounded
 }
 
+/// Generates a `size` by `size` dish, where about one place in five has a rounded rock and one in seven a cube.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1)).map(|_| (0..size.max(1)).map(|_| match rng.gen_range(0..35) {
+        0..=6 => 'O',
+        7..=11 => '#',
+        _ => '.'
+    }).collect::<String>()).join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
     use std::ops::Deref;
-    use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day14::{day14a, day14b, Dish, perform_cycle, rotate};
+    use crate::common::grid::Grid;
+    use crate::day14::{day14a, day14b, Dish, perform_cycle, RockType, spin_every_time};
     use crate::day14::RockType::*;
-
-    const TEST_DATA: &str = "O....#....\n\
-                             O.OO#....#\n\
-                             .....##...\n\
-                             OO.#O....O\n\
-                             .O.....O#.\n\
-                             O.#..O.#.#\n\
-                             ..O..#O..O\n\
-                             .......O..\n\
-                             #....###..\n\
-                             #OO..#....";
+    use crate::examples::DAY14 as TEST_DATA;
 
     const ONE_CYCLE: &str = ".....#....\n\
                             ....#...O#\n\
@@ -224,9 +211,17 @@ mod tests {
                                           #....###..\n\
                                           #....#....";
 
+    // The rocks are keyed by (x, y)
+    fn dish(rows: usize, columns: usize, rock_locations: HashMap<(usize, usize), RockType>) -> Dish {
+        let mut rocks = Grid::filled(rows, columns, None);
+        for ((x, y), rock) in rock_locations {
+            rocks[(y, x)] = Some(rock);
+        }
+        Dish { rocks }
+    }
+
     lazy_static! {
-        static ref PARSED_DISH: Dish = Dish {
-            rock_locations: HashMap::from([
+        static ref PARSED_DISH: Dish = dish(10, 10, HashMap::from([
                 ((0, 0), Rounded),
                 ((5, 0), Cube),
                 ((0, 1), Rounded),
@@ -262,16 +257,12 @@ mod tests {
                 ((1, 9), Rounded),
                 ((2, 9), Rounded),
                 ((5, 9), Cube),
-            ]),
-            no_of_rows: 10,
-            no_of_columns: 10
-        };
+            ]));
     }
 
 
     lazy_static! {
-        static ref TILTED_NORTH_PARSED_DISH: Dish = Dish {
-            rock_locations: HashMap::from([
+        static ref TILTED_NORTH_PARSED_DISH: Dish = dish(10, 10, HashMap::from([
                 ((0, 0), Rounded),
                 ((1, 0), Rounded),
                 ((2, 0), Rounded),
@@ -307,20 +298,17 @@ mod tests {
                 ((7, 8), Cube),
                 ((0, 9), Cube),
                 ((5, 9), Cube),
-            ]),
-            no_of_rows: 10,
-            no_of_columns: 10
-        };
+            ]));
     }
 
     #[test]
     fn test_parse_data() {
-        assert_eq!(Dish::parse(TEST_DATA), *PARSED_DISH.deref());
+        assert_eq!(Dish::parse(TEST_DATA).as_ref(), Ok(PARSED_DISH.deref()));
     }
 
     #[test]
     fn test_parse_data_2() {
-        assert_eq!(Dish::parse(TILTED_NORTH_TEST_DATA), *TILTED_NORTH_PARSED_DISH.deref());
+        assert_eq!(Dish::parse(TILTED_NORTH_TEST_DATA).as_ref(), Ok(TILTED_NORTH_PARSED_DISH.deref()));
     }
 
     #[test]
@@ -336,27 +324,19 @@ mod tests {
 
     #[test]
     fn test_cycle() {
-        let original = &PARSED_DISH.rock_locations;
-        let first = perform_cycle(original, 10, 10);
-        let second = perform_cycle(&first, 10, 10);
-        let third = perform_cycle(&second, 10, 10);
-        assert_eq!(first, Dish::parse(ONE_CYCLE).rock_locations);
-        assert_eq!(second, Dish::parse(TWO_CYCLE).rock_locations);
-        assert_eq!(third, Dish::parse(THREE_CYCLE).rock_locations);
+        let original = &PARSED_DISH.rocks;
+        let first = perform_cycle(original);
+        let second = perform_cycle(&first);
+        let third = perform_cycle(&second);
+        assert_eq!(first, Dish::parse(ONE_CYCLE).unwrap().rocks);
+        assert_eq!(second, Dish::parse(TWO_CYCLE).unwrap().rocks);
+        assert_eq!(third, Dish::parse(THREE_CYCLE).unwrap().rocks);
     }
 
-    // 3 x 4 -> 4 x 3 clockwise 90 deg
-    //
-    // a b x x      x c a
-    // c x x x  ->  x x b
-    // x x x x      x x x
-    //              x x x
-    #[rstest]
-    #[case(0, 0, 2, 0)]
-    #[case(1, 0, 2, 1)]
-    #[case(0, 1, 1, 0)]
-    fn test_rotation_single(#[case] x_start: usize, #[case] y_start: usize, #[case] x_end: usize, #[case] y_end: usize) {
-        assert_eq!(rotate(x_start, y_start, 3), (x_end, y_end));
+    #[test]
+    fn test_spin_every_time() {
+        let loads = [ONE_CYCLE, THREE_CYCLE].map(|spun| Dish::parse(spun).unwrap().calculate_load_north());
+        assert_eq!(spin_every_time(PARSED_DISH.deref(), &[1, 3]), loads);
     }
 
     #[test]
@@ -1,27 +1,52 @@
+//! Day 15: Lens Library
+//!
+//! Runs the HASH algorithm over each step, then follows the steps to arrange the lenses in their boxes.
+
 use std::collections::HashMap;
 use itertools::Itertools;
-use crate::common::load_from;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines};
+use crate::solution::Solution;
+
+/// Sums the hash of each step, then the focusing power of the lenses once every step has been done.
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+
+    type Parsed = Vec<Step>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Step>, ParseError> {
+        parse_steps(input)
+    }
 
-pub fn run_day() {
-    let data = load_from("day15.txt");
-    let hashes = parse_hashes(data.as_str());
-    println!("Part 1: {}", day15a(&hashes));
-    println!("Part 2: {}", day15b(data.as_str()));
+    fn part_one(&self, steps: &Vec<Step>) -> u64 {
+        day15a(&parse_hashes(steps))
+    }
+
+    fn part_two(&self, steps: &Vec<Step>) -> u64 {
+        day15b(steps)
+    }
 }
 
 fn day15a(hashes: &Vec<u64>) -> u64 {
     hashes.iter().sum()
 }
 
-fn day15b(line: &str) -> u64 {
+fn day15b(steps: &[Step]) -> u64 {
     const NEW_VEC: Vec<(&str, u64)> = Vec::new();
     let mut boxes: [Vec<(&str, u64)>; 256] = [NEW_VEC; 256];
 
-    for entry in line.split(",") {
-        let (label, box_no, operation) = process_label_and_operation(entry);
-        let vec = boxes.get_mut(box_no as usize).unwrap();
+    for Step { label, box_no, operation, .. } in steps {
+        let label = label.as_str();
+        let vec = boxes.get_mut(*box_no as usize).unwrap();
         let existing_idx = vec.iter().find_position(|(l, _)| *l == label);
-        match operation {
+        match *operation {
             Operation::Remove => {
                 if let Some((idx, _)) = existing_idx {
                     vec.remove(idx);
@@ -46,27 +71,48 @@ fn day15b(line: &str) -> u64 {
         .sum()
 }
 
+/// One step of the initialization sequence, both as it's written and as what it does.
 #[derive(Debug, PartialEq)]
+pub struct Step {
+    text: String,
+    label: String,
+    box_no: u64,
+    operation: Operation
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
 enum Operation {
     Remove,
     Add(u64)
 }
 
-fn process_label_and_operation<'a>(label: &'a str) -> (&'a str, u64, Operation) {
-    let (label, operation): (&'a str, Operation) = if label.ends_with("-") {
-        (label.trim_end_matches("-"), Operation::Remove)
+fn parse_steps(input: &str) -> Result<Vec<Step>, ParseError> {
+    // The sequence can be wrapped onto more than one line, but the newlines aren't part of it
+    let lines: Vec<Vec<Step>> = lines(input).filter(|line| !line.text.is_empty()).map(|line| {
+        line.list(line.text, ",", |step| {
+            let (label, box_no, operation) = process_label_and_operation(line, step)?;
+            Ok(Step { text: step.to_string(), label: label.to_string(), box_no, operation })
+        })
+    }).collect::<Result<_, ParseError>>()?;
+    Ok(lines.into_iter().flatten().collect())
+}
+
+fn process_label_and_operation<'a>(line: Line<'a>, step: &'a str) -> Result<(&'a str, u64, Operation), ParseError> {
+    let (label, operation): (&'a str, Operation) = if let Some(label) = step.strip_suffix("-") {
+        (label, Operation::Remove)
     } else {
-        label.split_once("=").map(|(l, v)| (l, Operation::Add(str::parse::<u64>(v).unwrap()))).unwrap()
+        let (label, focal_length) = line.split_once(step, "=", "expected a label and then - or =, like cm- or rn=1")?;
+        (label, Operation::Add(line.number(focal_length)?))
     };
 
-    (label, parse_hash(label), operation)
+    Ok((label, parse_hash(label), operation))
 }
 
-fn parse_hashes<'a>(line: &'a str) -> Vec<u64> {
-    let mut cache = HashMap::<&'a str, u64>::new();
+fn parse_hashes(steps: &[Step]) -> Vec<u64> {
+    let mut cache = HashMap::<&str, u64>::new();
     let mut hashes: Vec<u64> = Vec::new();
-    for entry in line.split(",") {
-        hashes.push(cache.entry(entry).or_insert_with(|| parse_hash(entry)).clone());
+    for Step { text, .. } in steps {
+        hashes.push(*cache.entry(text.as_str()).or_insert_with(|| parse_hash(text)));
     }
 
     hashes
@@ -77,14 +123,31 @@ fn parse_hash(entry: &str) -> u64 {
     entry.chars().map(u64::from).fold(0, |acc, next| ((acc + next) * 17) % 256)
 }
 
+/// Generates `size` steps. The labels come from a pool about a third as big, so lenses are often replaced or taken out
+/// again.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let labels: Vec<String> = (0..(size / 3).max(1))
+        .map(|_| (0..rng.gen_range(2..=6)).map(|_| rng.gen_range('a'..='z')).collect())
+        .collect();
+    (0..size.max(1)).map(|_| {
+        let label = labels.choose(rng).unwrap();
+        if rng.gen_bool(0.3) {
+            format!("{}-", label)
+        } else {
+            format!("{}={}", label, rng.gen_range(1..=9))
+        }
+    }).join(",")
+}
+
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day15::{day15a, day15b, Operation, parse_hash, parse_hashes, process_label_and_operation};
-
-    const TEST_INPUT: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+    use crate::common::ParseError;
+    use crate::common::parse::Line;
+    use crate::day15::{day15a, day15b, Operation, parse_hash, parse_hashes, parse_steps, process_label_and_operation};
+    use crate::examples::DAY15 as TEST_INPUT;
 
     lazy_static! {
         static ref PARSED_HASHES: Vec<u64> = vec![
@@ -104,7 +167,7 @@ mod test {
 
     #[test]
     fn test_parse_hashes() {
-        assert_eq!(parse_hashes(TEST_INPUT), *PARSED_HASHES.deref());
+        assert_eq!(parse_hashes(&parse_steps(TEST_INPUT).unwrap()), *PARSED_HASHES.deref());
     }
 
     #[rstest]
@@ -125,7 +188,7 @@ mod test {
 
     #[test]
     fn test_day15a() {
-        assert_eq!(day15a(&parse_hashes(TEST_INPUT)), 1320);
+        assert_eq!(day15a(&parse_hashes(&parse_steps(TEST_INPUT).unwrap())), 1320);
     }
 
     #[rstest]
@@ -141,12 +204,20 @@ mod test {
     #[case("pc=6", "pc", 3, Operation::Add(6))]
     #[case("ot=7", "ot", 3, Operation::Add(7))]
     fn test_process_label_and_operation(#[case] input: &str, #[case] label: &str, #[case] box_no: u64, #[case] operation: Operation) {
-        assert_eq!(process_label_and_operation(input), (label, box_no, operation));
+        assert_eq!(process_label_and_operation(Line::new(0, input), input), Ok((label, box_no, operation)));
     }
 
     #[test]
     fn test_day15b() {
-        assert_eq!(day15b(TEST_INPUT), 145);
+        assert_eq!(day15b(&parse_steps(TEST_INPUT).unwrap()), 145);
+    }
+
+    #[rstest]
+    #[case("rn=1,cm", ParseError::new(1, 6, "rn=1,cm", "expected a label and then - or =, like cm- or rn=1"))]
+    #[case("rn=1,\ncm=x", ParseError::new(1, 6, "rn=1,", "expected a label and then - or =, like cm- or rn=1"))]
+    #[case("rn=1\ncm=x", ParseError::new(2, 4, "cm=x", "expected a number, found \"x\""))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_steps(input), Err(expected));
     }
 
 }
\ No newline at end of file
@@ -1,20 +1,46 @@
-use std::collections::{HashMap, HashSet};
+//! Day 16: The Floor Will Be Lava
+//!
+//! Traces the beam of light through the mirrors and splitters to count the energised tiles from each starting edge.
+
+use std::collections::HashSet;
 use tailcall::tailcall;
-use crate::common::load_from;
-use crate::day16::Direction::*;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::grid::Grid;
+use crate::common::ParseError;
+use crate::solution::Solution;
+use crate::common::point::Direction;
+use crate::common::point::Direction::*;
 use crate::day16::Object::{MirrorBLUR, MirrorULBR, SplitterH, SplitterV};
 
 type Vector = ((usize, usize), Direction);
 
-pub fn run_day() {
-    let data = load_from("day16.txt");
-    let board = create_board(data.as_str());
-    println!("Part 1: {}", day16a(&board));
-    println!("Part 2: {}", day16b(&board));
+/// Counts the energised tiles when the beam starts in the top left, then from the best place on any edge.
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+
+    type Parsed = Board;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(&self, input: &str) -> Result<Board, ParseError> {
+        create_board(input)
+    }
+
+    fn part_one(&self, board: &Board) -> usize {
+        day16a(board)
+    }
+
+    fn part_two(&self, board: &Board) -> usize {
+        day16b(board)
+    }
 }
 
 fn day16a(board: &Board) -> usize {
-    let steps = run_steps(board, ((0, 0), Right));
+    let steps = run_steps(board, ((0, 0), East));
     let set: HashSet<(usize, usize)> = steps.iter().map(|((x, y), _)| (x.clone(), y.clone())).collect();
     set.len()
 }
@@ -28,14 +54,14 @@ fn day16b(board: &Board) -> usize {
         set.len()
     }
 
-    for row in 0..board.rows {
-        max = max.max(get_steps(row, 0, Right, board));
-        max = max.max(get_steps(row, board.columns - 1, Left, board));
+    for row in 0..board.tiles.height() {
+        max = max.max(get_steps(row, 0, East, board));
+        max = max.max(get_steps(row, board.tiles.width() - 1, West, board));
     }
 
-    for col in 0..board.columns {
-        max = max.max(get_steps(0, col, Down, board));
-        max = max.max(get_steps(board.rows - 1, col, Up, board));
+    for col in 0..board.tiles.width() {
+        max = max.max(get_steps(0, col, South, board));
+        max = max.max(get_steps(board.tiles.height() - 1, col, North, board));
     }
 
     max
@@ -53,9 +79,9 @@ fn step(incoming: Vec<Vector>, board: &Board, steps: &mut HashSet<Vector>) {
     if !incoming.is_empty() {
         let mut outgoing: Vec<Vector> = Vec::new();
         for ((x1, y1), direction) in incoming {
-            let object = board.objects.get(&(x1, y1));
-            for d in direction.clone().strike_object_option(object) {
-                if let Some(next) = d.next(x1, y1, board.columns, board.rows) {
+            let object = board.tiles[(y1, x1)].as_ref();
+            for d in strike_object_option(direction, object) {
+                if let Some(next) = next(d, x1, y1, &board.tiles) {
                     if steps.insert(next.clone()) {
                         // next is the coords and incoming direction.
                         outgoing.push(next);
@@ -67,26 +93,9 @@ fn step(incoming: Vec<Vector>, board: &Board, steps: &mut HashSet<Vector>) {
     }
 }
 
-fn create_board(data: &str) -> Board {
-    let lines: Vec<&str> = data.lines().collect();
-    let rows = lines.len();
-    let columns = lines.first().unwrap().len();
-
-    let objects: HashMap<(usize, usize), Object> = lines
-        .iter()
-        .enumerate()
-        .flat_map(|(idx, line)| {
-            let y = idx;
-            // the y is unique to this iterator so we move it into here.
-            line.chars().enumerate().flat_map(move |(x1, c1)| {
-                let x = x1;
-                let c = c1;
-                get_object(&c).map(|obj| ((x.clone(), y.clone()), obj))
-            })
-        })
-        .collect();
-
-    Board { rows, columns, objects }
+fn create_board(data: &str) -> Result<Board, ParseError> {
+    let tiles = Grid::parse(data, |c| if c == '.' { Some(None) } else { get_object(&c).map(Some) })?;
+    Ok(Board { tiles })
 }
 
 fn get_object(c: &char) -> Option<Object> {
@@ -99,79 +108,48 @@ fn get_object(c: &char) -> Option<Object> {
     }
 }
 
-#[repr(u8)]
-#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
-enum Direction {
-    Left,
-    Down,
-    Right,
-    Up
+// The tile next to (x, y) in the direction, and the direction the beam is going as it gets there
+fn next(direction: Direction, x: usize, y: usize, tiles: &Grid<Option<Object>>) -> Option<Vector> {
+    tiles.next((y, x), direction).map(|(row, column)| ((column, row), direction))
 }
 
-impl Direction {
-
-    fn next(&self, x: usize, y: usize, x_max: usize, y_max: usize) -> Option<Vector> {
-        match *self {
-            Left => x.checked_sub(1).map(|r| ((r, y), self.clone())),
-            Down => {
-                let next_y = y + 1;
-                if next_y == y_max {
-                    None
-                } else {
-                    Some(((x, next_y), self.clone()))
-                }
-            }
-            Right => {
-                let next_x = x + 1;
-                if next_x == x_max {
-                    None
-                } else {
-                    Some(((next_x, y), self.clone()))
-                }
-            },
-            Up => y.checked_sub(1).map(|r| ((x, r), self.clone()))
-        }
-    }
-
-    fn strike_object_option(&self, object: Option<&Object>) -> Vec<Direction> {
-        object.map(|x| self.strike_object(x)).unwrap_or_else(|| vec![self.clone()])
-    }
+fn strike_object_option(direction: Direction, object: Option<&Object>) -> Vec<Direction> {
+    object.map(|x| strike_object(direction, x)).unwrap_or_else(|| vec![direction])
+}
 
-    fn strike_object(&self, object: &Object) -> Vec<Direction> {
-        match *object {
-            MirrorULBR => { // \
-                match self {
-                    Left => vec![Up],
-                    Down => vec![Right],
-                    Right => vec![Down],
-                    Up => vec![Left]
-                }
-            },
-            MirrorBLUR => { // /
-                match self {
-                    Right => vec![Up],
-                    Down => vec![Left],
-                    Left => vec![Down],
-                    Up => vec![Right]
-                }
-            },
-            SplitterH => { // -
-                match self {
-                    Down => vec![Left, Right],
-                    Up => vec![Left, Right],
-                    dir => vec![dir.clone()]
-                }
-            },
-            SplitterV => { // |
-                match self {
-                    Left => vec![Up, Down],
-                    Right => vec![Up, Down],
-                    dir => vec![dir.clone()]
-                }
+fn strike_object(direction: Direction, object: &Object) -> Vec<Direction> {
+    match *object {
+        MirrorULBR => { // \
+            match direction {
+                West => vec![North],
+                South => vec![East],
+                East => vec![South],
+                North => vec![West]
+            }
+        },
+        MirrorBLUR => { // /
+            match direction {
+                East => vec![North],
+                South => vec![West],
+                West => vec![South],
+                North => vec![East]
+            }
+        },
+        SplitterH => { // -
+            match direction {
+                South => vec![West, East],
+                North => vec![West, East],
+                dir => vec![dir]
+            }
+        },
+        SplitterV => { // |
+            match direction {
+                West => vec![North, South],
+                East => vec![North, South],
+                dir => vec![dir]
             }
         }
     }
-
 }
 
 #[repr(u8)]
@@ -183,42 +161,44 @@ enum Object {
     SplitterV
 }
 
+/// The contraption, with the mirror or splitter on each tile, if there is one.
 #[derive(PartialEq, Debug)]
-struct Board {
-    rows: usize,
-    columns: usize,
-    objects: HashMap<(usize, usize), Object>
+pub struct Board {
+    tiles: Grid<Option<Object>>
+}
+
+/// Generates a `size` by `size` contraption, with a mirror or a splitter in about one place in seven.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const OBJECTS: [char; 4] = ['/', '\\', '|', '-'];
+    (0..size.max(1)).map(|_| (0..size.max(1)).map(|_| {
+        if rng.gen_bool(1.0 / 7.0) { OBJECTS[rng.gen_range(0..OBJECTS.len())] } else { '.' }
+    }).collect::<String>()).join("\n")
 }
 
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
     use std::ops::Deref;
-    use indoc::indoc;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day16::{Board, create_board, day16a, day16b, Direction, Object};
+    use crate::common::grid::Grid;
+    use crate::common::point::Direction;
+    use crate::common::point::Direction::*;
+    use crate::day16::{Board, create_board, day16a, day16b, Object, strike_object};
     use crate::day16::Object::*;
-    use crate::day16::Direction::*;
-
-    const TEST_INPUT: &str = indoc! {
-        r#".|...\....
-           |.-.\.....
-           .....|-...
-           ........|.
-           ..........
-           .........\
-           ..../.\\..
-           .-.-/..|..
-           .|....-|.\
-           ..//.|...."#
-    };
+    use crate::examples::DAY16 as TEST_INPUT;
+
+    // The objects are keyed by (x, y)
+    fn board(rows: usize, columns: usize, objects: HashMap<(usize, usize), Object>) -> Board {
+        let mut tiles = Grid::new(rows, columns, (0..rows * columns).map(|_| None).collect());
+        for ((x, y), object) in objects {
+            tiles[(y, x)] = Some(object);
+        }
+        Board { tiles }
+    }
 
     lazy_static! {
-        static ref PARSED_INPUT: Board = Board {
-            rows: 10,
-            columns: 10,
-            objects: HashMap::from([
+        static ref PARSED_INPUT: Board = board(10, 10, HashMap::from([
                 ((1, 0), SplitterV),
                 ((5, 0), MirrorULBR),
                 ((0, 1), SplitterV),
@@ -242,34 +222,33 @@ mod test {
                 ((2, 9), MirrorBLUR),
                 ((3, 9), MirrorBLUR),
                 ((5, 9), SplitterV)
-            ])
-        };
+            ]));
     }
 
     #[test]
     fn test_create_board() {
-        assert_eq!(create_board(TEST_INPUT), *PARSED_INPUT.deref());
+        assert_eq!(create_board(TEST_INPUT).as_ref(), Ok(PARSED_INPUT.deref()));
     }
 
     #[rstest]
-    #[case(Down, MirrorULBR, vec![Right])]
-    #[case(Up, MirrorULBR, vec![Left])]
-    #[case(Right, MirrorULBR, vec![Down])]
-    #[case(Left, MirrorULBR, vec![Up])]
-    #[case(Down, MirrorBLUR, vec![Left])]
-    #[case(Left, MirrorBLUR, vec![Down])]
-    #[case(Up, MirrorBLUR, vec![Right])]
-    #[case(Right, MirrorBLUR, vec![Up])]
-    #[case(Right, SplitterH, vec![Right])]
-    #[case(Left, SplitterH, vec![Left])]
-    #[case(Up, SplitterH, vec![Left, Right])]
-    #[case(Down, SplitterH, vec![Left, Right])]
-    #[case(Down, SplitterV, vec![Down])]
-    #[case(Up, SplitterV, vec![Up])]
-    #[case(Left, SplitterV, vec![Up, Down])]
-    #[case(Right, SplitterV, vec![Up, Down])]
+    #[case(South, MirrorULBR, vec![East])]
+    #[case(North, MirrorULBR, vec![West])]
+    #[case(East, MirrorULBR, vec![South])]
+    #[case(West, MirrorULBR, vec![North])]
+    #[case(South, MirrorBLUR, vec![West])]
+    #[case(West, MirrorBLUR, vec![South])]
+    #[case(North, MirrorBLUR, vec![East])]
+    #[case(East, MirrorBLUR, vec![North])]
+    #[case(East, SplitterH, vec![East])]
+    #[case(West, SplitterH, vec![West])]
+    #[case(North, SplitterH, vec![West, East])]
+    #[case(South, SplitterH, vec![West, East])]
+    #[case(South, SplitterV, vec![South])]
+    #[case(North, SplitterV, vec![North])]
+    #[case(West, SplitterV, vec![North, South])]
+    #[case(East, SplitterV, vec![North, South])]
     fn test_direction_strike_object(#[case] input: Direction, #[case] object: Object, #[case] output: Vec<Direction>) {
-        assert_eq!(input.strike_object(&object), output);
+        assert_eq!(strike_object(input, &object), output);
     }
 
     #[test]
@@ -1,189 +1,112 @@
-use std::collections::HashMap;
-use tailcall::tailcall;
-use crate::common::load_from;
-use crate::day17::Direction::{East, North, South, West};
+//! Day 17: Clumsy Crucible
+//!
+//! Finds the path that loses the least heat, with crucibles that can only go so far in a straight line.
 
-pub fn run_day() {
-    let data = load_from("day17.txt");
-    let grid = Grid::from(data.as_str());
-    println!("Part 1: {}", day17a(&grid));
-    println!("Part 2: {}", day17b(&grid));
-}
-
-fn day17a(grid: &Grid) -> u32 {
-    dijkstra_ish(grid, vec![Visitor {
-        location: (0, 0),
-        current_heat: 0,
-        last_direction: North,
-        steps_in_direction: 0
-    }], false)
-}
-
-fn day17b(grid: &Grid) -> u32 {
-    dijkstra_ish(grid, vec![Visitor {
-        location: (0, 0),
-        current_heat: 0,
-        last_direction: North,
-        steps_in_direction: 0
-    }], true)
-}
-
-type CacheKey = (usize, usize, Direction, u8);
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::grid::Grid;
+use crate::common::point::Direction;
+use crate::common::point::Direction::{East, North, South, West};
+use crate::common::search::{search, SearchState};
+use crate::common::ParseError;
+use crate::debug;
+use crate::solution::Solution;
 
-fn dijkstra_ish(grid: &Grid, initial_visitor: Vec<Visitor>, ultra: bool) -> u32 {
-    let mut distances: Vec<Vec<u32>> = vec![vec![u32::MAX; grid.no_of_columns]; grid.no_of_rows];
-    let _ = std::mem::replace(&mut distances[0][0], 0u32);
-    let mut cache: HashMap<(usize, usize, Direction, u8), u32> = HashMap::new();
+/// The least heat lost by a normal crucible, then by an ultra crucible.
+pub struct Day17;
 
-    // our initial walk is from 0, 0
+impl Solution for Day17 {
+    const DAY: u8 = 17;
 
-    #[tailcall]
-    fn step(grid: &Grid, current: Vec<Visitor>, cache: &mut HashMap<CacheKey, u32>, mins: &mut Vec<Vec<u32>>, ultra: bool) {
-        let mut next_vectors: Vec<Visitor> = Vec::new();
-        for visitor in current {
-            let new_visitors: Vec<Visitor> = DIRECTIONS
-                .iter()
-                .filter(|x|
-                    if ultra {
-                        visitor.steps_in_direction == 0 || visitor.last_direction.can_go_ultra(x, visitor.steps_in_direction)
-                    } else {
-                        visitor.steps_in_direction == 0 || visitor.last_direction.can_go(x, visitor.steps_in_direction)
-                    }
-                )
-                .filter_map(|x| grid.visit(&visitor, x))
-                .collect();
+    type Parsed = Grid<u32>;
+    type PartOne = u32;
+    type PartTwo = u32;
 
-            for new_visit in new_visitors {
-                let (col, row) = new_visit.location;
-                let heat = new_visit.current_heat;
+    fn parse(&self, input: &str) -> Result<Grid<u32>, ParseError> {
+        parse_grid(input)
+    }
 
-                let key: CacheKey = (row, col, new_visit.last_direction.clone(), new_visit.steps_in_direction);
-                if cache.get(&key).filter(|x| **x <= heat).is_none() {
-                    cache.insert(key, new_visit.current_heat);
-                    next_vectors.push(new_visit);
-                    if heat <= mins[row][col] {
-                        mins[row][col] = heat;
-                    }
-                }
-            }
-        }
+    fn part_one(&self, grid: &Grid<u32>) -> u32 {
+        day17a(grid)
+    }
 
-        if !next_vectors.is_empty() {
-            step(grid, next_vectors, cache, mins, ultra)
-        }
+    fn part_two(&self, grid: &Grid<u32>) -> u32 {
+        day17b(grid)
     }
+}
 
-    step(grid, initial_visitor, &mut cache, &mut distances, ultra);
-    distances.last().unwrap().last().unwrap().clone()
+fn day17a(grid: &Grid<u32>) -> u32 {
+    least_heat_lost(grid, false)
 }
 
-#[derive(Debug, PartialEq, Clone, Hash, Eq)]
-enum Direction {
-    North,
-    West,
-    South,
-    East
+fn day17b(grid: &Grid<u32>) -> u32 {
+    least_heat_lost(grid, true)
 }
 
-const DIRECTIONS: [Direction; 4] = [North, West, South, East];
+fn least_heat_lost(grid: &Grid<u32>, ultra: bool) -> u32 {
+    // It hasn't gone anywhere yet, so it can start off in any direction
+    let start = Crucible { position: (0, 0), direction: North, steps_in_direction: 0, ultra };
+    let found = search(grid, [start]).expect("the factory can always be reached");
+    debug!("the crucible takes {} steps to get to the factory, losing {} heat", found.path.len() - 1, found.cost);
+    found.cost
+}
 
-impl Direction {
+/// A crucible somewhere in the city, with the way it's going and how many blocks it's gone that way.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+struct Crucible {
+    position: (usize, usize),
+    direction: Direction,
+    steps_in_direction: u8,
+    ultra: bool
+}
 
-    fn get_backwards(&self) -> Direction {
-        match *self {
-            North => South,
-            West => East,
-            South => North,
-            East => West
-        }
-    }
+impl SearchState for Crucible {
+    type Context = Grid<u32>;
+    type Cost = u32;
 
-    fn can_go(&self, direction: &Direction, steps_taken: u8) -> bool {
-        self.get_backwards() != *direction && (self != direction || steps_taken < 3)
+    fn successors(&self, grid: &Grid<u32>) -> Vec<(Crucible, u32)> {
+        DIRECTIONS.into_iter()
+            .filter(|direction| self.steps_in_direction == 0 || if self.ultra {
+                can_go_ultra(self.direction, *direction, self.steps_in_direction)
+            } else {
+                can_go(self.direction, *direction, self.steps_in_direction)
+            })
+            .filter_map(|direction| grid.next(self.position, direction).map(|position| {
+                let steps_in_direction = if direction == self.direction { self.steps_in_direction + 1 } else { 1 };
+                (Crucible { position, direction, steps_in_direction, ..*self }, grid[position])
+            }))
+            .collect()
     }
 
-    fn can_go_ultra(&self, direction: &Direction, steps_taken: u8) -> bool {
-        if steps_taken < 4 {
-            self == direction
-        } else {
-            self.get_backwards() != *direction && (self != direction || steps_taken < 10)
-        }
+    fn is_goal(&self, grid: &Grid<u32>) -> bool {
+        self.position == (grid.height() - 1, grid.width() - 1)
     }
 }
 
-#[derive(PartialEq, Debug)]
-struct Grid {
-    no_of_rows: usize,
-    no_of_columns: usize,
-    grid: Vec<Vec<u32>>
-}
-
-impl Grid {
-
-    fn from(data: &str) -> Grid {
-        let grid: Vec<Vec<u32>> = data.lines().map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect()).collect();
-        let no_of_rows = grid.len();
-        let no_of_columns = grid.first().unwrap().len();
-        Grid {
-            no_of_rows,
-            no_of_columns,
-            grid
-        }
-    }
+const DIRECTIONS: [Direction; 4] = [North, West, South, East];
 
-    // x, y => col, row
-    // NORTH -1 row -> x, y-1
-    // SOUTH +1 row -> x, y+1
-    // EAST +1 col -> x+1, y
-    // WEST -1 col -> x-1, y
-    fn next(&self, current_col: usize, current_row: usize, direction: &Direction) -> Option<(usize, usize)> {
-        match *direction {
-            North => current_row.checked_sub(1).map(move |y| (current_col, y)),
-            West => current_col.checked_sub(1).map(move |x| (x, current_row)),
-            South => {
-                let y = current_row + 1;
-                if y >= self.no_of_rows {
-                    None
-                } else {
-                    Some((current_col, y))
-                }
-            },
-            East => {
-                let x = current_col + 1;
-                if x >= self.no_of_columns {
-                    None
-                } else {
-                    Some((x, current_row))
-                }
-            }
-        }
-    }
+// Whether a crucible that's gone `steps_taken` steps `last` can go `direction` next
+fn can_go(last: Direction, direction: Direction, steps_taken: u8) -> bool {
+    last.reverse() != direction && (last != direction || steps_taken < 3)
+}
 
-    fn visit(&self, current: &Visitor, next_direction: &Direction) -> Option<Visitor> {
-        self.next(current.location.0, current.location.1, next_direction)
-            .map(move |(col, row)| {
-                let steps_in_direction = if current.last_direction == *next_direction {
-                    current.steps_in_direction + 1
-                } else {
-                    1
-                };
-                Visitor {
-                    location: (col, row),
-                    current_heat: current.current_heat + self.grid[row][col],
-                    last_direction: (*next_direction).clone(),
-                    steps_in_direction
-                }
-            })
+fn can_go_ultra(last: Direction, direction: Direction, steps_taken: u8) -> bool {
+    if steps_taken < 4 {
+        last == direction
+    } else {
+        last.reverse() != direction && (last != direction || steps_taken < 10)
     }
+}
 
+/// Reads the heat lost on entering each block of the city, one digit per block.
+fn parse_grid(data: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::parse(data, |c| c.to_digit(10))
 }
 
-#[derive(Debug)]
-struct Visitor {
-    location: (usize, usize),
-    current_heat: u32,
-    last_direction: Direction,
-    steps_in_direction: u8
+/// Generates a `size` by `size` city, losing between one and nine heat in each block.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(5)).map(|_| (0..size.max(5)).map(|_| rng.gen_range('1'..='9')).collect::<String>()).join("\n")
 }
 
 #[cfg(test)]
@@ -191,28 +114,16 @@ mod test {
     use std::ops::Deref;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day17::{day17a, day17b, Direction, Grid};
-    use crate::day17::Direction::*;
+    use crate::common::grid::Grid;
+    use crate::common::point::Direction;
+    use crate::common::point::Direction::*;
+    use crate::day17::{can_go, day17a, day17b, parse_grid};
+    use crate::examples::{DAY17_1 as TEST_DATA, DAY17_2 as TEST_DATA_2};
 
-    const TEST_DATA: &str = "2413432311323\n\
-                             3215453535623\n\
-                             3255245654254\n\
-                             3446585845452\n\
-                             4546657867536\n\
-                             1438598798454\n\
-                             4457876987766\n\
-                             3637877979653\n\
-                             4654967986887\n\
-                             4564679986453\n\
-                             1224686865563\n\
-                             2546548887735\n\
-                             4322674655533";
-
-    const TEST_DATA_2: &str = "111111111111\n\
-                               999999999991\n\
-                               999999999991\n\
-                               999999999991\n\
-                               999999999991";
+    // x, y => col, row
+    fn next(current_col: usize, current_row: usize, direction: Direction) -> Option<(usize, usize)> {
+        PARSED_DATA.next((current_row, current_col), direction).map(|(row, col)| (col, row))
+    }
 
     lazy_static! {
         static ref PARSED_DATA_VEC: Vec<Vec<u32>> = vec![
@@ -233,16 +144,12 @@ mod test {
     }
 
     lazy_static! {
-        static ref PARSED_DATA: Grid = Grid {
-            no_of_columns: 13,
-            no_of_rows: 13,
-            grid: PARSED_DATA_VEC.clone()
-        };
+        static ref PARSED_DATA: Grid<u32> = Grid::from_rows(PARSED_DATA_VEC.clone());
     }
 
     #[test]
     fn test_grid_from() {
-        assert_eq!(Grid::from(TEST_DATA), *PARSED_DATA.deref());
+        assert_eq!(parse_grid(TEST_DATA), Ok(PARSED_DATA.clone()));
     }
 
     #[rstest]
@@ -262,7 +169,7 @@ mod test {
     #[case(East, East, 4, false)] // shouldn't happen, but check anyway
     #[case(East, South, 4, true)] // shouldn't happen, but check anyway
     fn test_direction_can_go(#[case] direction: Direction, #[case] proposed: Direction, #[case] steps_taken: u8, #[case] expected: bool) {
-        assert_eq!(direction.can_go(&proposed, steps_taken), expected);
+        assert_eq!(can_go(direction, proposed, steps_taken), expected);
     }
 
     // x, y => col, row
@@ -282,21 +189,21 @@ mod test {
     #[case(East, 12, 12, None)]
     #[case(South, 12, 12, None)]
     fn test_grid_next(#[case] direction: Direction, #[case] current_column: usize, #[case] current_row: usize, #[case] expected: Option<(usize, usize)>) {
-        assert_eq!(PARSED_DATA.next(current_column, current_row, &direction), expected)
+        assert_eq!(next(current_column, current_row, direction), expected)
     }
 
     // 0 0 0
     // x 0 0
     #[test]
     fn test_grid_next_1() {
-        assert_eq!(PARSED_DATA.next(0, 1, &East), Some((1, 1)));
+        assert_eq!(next(0, 1, East), Some((1, 1)));
     }
 
     // 0 x 0
     // 0 0 0
     #[test]
     fn test_grid_next_2() {
-        assert_eq!(PARSED_DATA.next(1, 0, &East), Some((2, 0)));
+        assert_eq!(next(1, 0, East), Some((2, 0)));
     }
 
     #[test]
@@ -306,7 +213,7 @@ mod test {
 
     #[test]
     fn test_day17b_2() {
-        assert_eq!(day17b(&Grid::from(TEST_DATA_2)), 47); // the example in AoC is wrong, probably deliberately.
+        assert_eq!(day17b(&parse_grid(TEST_DATA_2).unwrap()), 47); // the example in AoC is wrong, probably deliberately.
     }
 
 }
\ No newline at end of file
@@ -1,156 +1,224 @@
-use regex::Regex;
-use crate::common::load_from;
-
-pub fn run_day() {
-    let data = load_from("day18.txt");
-    let edges = parse_instructions(data.as_str());
-    println!("Part 1: {}", day_18a(&edges));
-    println!("Part 2: {}", day_18b(&edges));
+//! Day 18: Lavaduct Lagoon
+//!
+//! Works out the size of the lagoon from the dig plan, using the shoelace formula and Pick's theorem.
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::point::Direction::{East, North, South, West};
+use crate::common::geometry::{Orientation, Polygon};
+use crate::common::point::Point;
+use crate::common::ParseError;
+use crate::common::parse::lines;
+use crate::solution::Solution;
+use crate::generate::random_loop;
+
+/// The number of cubic metres the lagoon holds, using the plain instructions and then the ones hidden in the colours.
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+
+    type Parsed = Vec<Edge>;
+    type PartOne = i64;
+    type PartTwo = i64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Edge>, ParseError> {
+        parse_instructions(input)
+    }
+
+    fn part_one(&self, edges: &Vec<Edge>) -> i64 {
+        day_18a(edges)
+    }
+
+    fn part_two(&self, edges: &Vec<Edge>) -> i64 {
+        day_18b(edges)
+    }
 }
 
 
 fn day_18a(edges: &Vec<Edge>) -> i64 {
-    // shoelace to get the area (which will underestimate), pick's to get the internal points,
-    // then add it all together to get the total number of points, which is the area/volume.
-    // use absolute due to the fact that the sign indicated direction of verticies.
-    let shoelace_area: i64 = edges.iter().map(|x| x.det().clone()).sum::<i64>().abs() / 2i64;
-    let perimeter: i64 = edges.iter().map(|x| (x.first.row - x.last.row + x.first.column - x.last.column).abs()).sum();
-    let picks_internal_points: i64 = shoelace_area + 1 - (perimeter / 2);
-    picks_internal_points + perimeter
+    // Pick's theorem gives the points inside the trench, and the trench itself is dug out as well
+    lagoon(edges, false).enclosed_points().expect("the lagoon was measured when it was parsed")
 }
 
 fn day_18b(edges: &Vec<Edge>) -> i64 {
-    // shoelace to get the area (which will underestimate), pick's to get the internal points,
-    // then add it all together to get the total number of points, which is the area/volume.
-    // use absolute due to the fact that the sign indicated direction of verticies.
-    let shoelace_area: i64 = edges.iter().map(|x| x.det_hex().clone()).sum::<i64>().abs() / 2i64;
-    let perimeter: i64 = edges.iter().map(|x| (x.hex_first.row - x.hex_last.row + x.hex_first.column - x.hex_last.column).abs()).sum();
-    let picks_internal_points: i64 = shoelace_area + 1 - (perimeter / 2);
-    picks_internal_points + perimeter
+    lagoon(edges, true).enclosed_points().expect("the lagoon was measured when it was parsed")
 }
 
-fn parse_instructions(data: &str) -> Vec<Edge> {
-    let mut row = 0i64;
-    let mut column = 0i64;
+// The trench as a polygon, following either the plain instructions or the ones hidden in the colours
+fn lagoon(edges: &[Edge], hex: bool) -> Polygon {
+    Polygon::new(edges.iter().map(|edge| if hex { edge.hex_first } else { edge.first }).collect())
+}
 
-    let mut row_hex = 0i64;
-    let mut column_hex = 0i64;
+fn parse_instructions(data: &str) -> Result<Vec<Edge>, ParseError> {
+    let mut first = Point::default();
+    let mut hex_first = Point::default();
 
     let mut edges: Vec<Edge> = Vec::new();
 
-    let pattern = Regex::new(r"^([UDLR]) (\d+) \(#([0-9a-f]{5})([0-3])\)$").unwrap();
-    for line in data.lines() {
-        let caps = pattern.captures(line).unwrap();
-
-        // simple
-        let direction = caps.get(1).unwrap();
-        let steps = str::parse::<i64>(caps.get(2).unwrap().as_str()).unwrap();
-        let last = match direction.as_str() {
-            "U" => Coord { row: row - steps, column },
-            "D" => Coord { row: row + steps, column },
-            "R" => Coord { row, column: column + steps },
-            "L" => Coord { row, column: column - steps  },
-            a => panic!("Unknown direction: {}", a)
+    for line in lines(data) {
+        const EXPECTED: &str = "expected a direction, a number of steps and a colour, like R 6 (#70c710)";
+        let [direction, steps, colour] = line.words(line.text, Ok)?[..] else {
+            return Err(line.error(line.text, EXPECTED));
         };
 
-        // hex
-        let hex_steps = i64::from_str_radix(caps.get(3).unwrap().as_str(), 16).unwrap();
-        let hex_direction = caps.get(4).unwrap();
-        let hex_last = match hex_direction.as_str() {
-            "0" => Coord { row: row_hex, column: column_hex + hex_steps },
-            "1" => Coord { row: row_hex + hex_steps, column: column_hex },
-            "2" => Coord { row: row_hex, column: column_hex - hex_steps  },
-            "3" => Coord { row: row_hex - hex_steps, column: column_hex },
-            a => panic!("Unknown direction: {}", a)
+        // simple -- the steps could still be too big
+        let direction = match direction {
+            "U" => North,
+            "D" => South,
+            "R" => East,
+            "L" => West,
+            _ => return Err(line.error(line.text, EXPECTED))
         };
+        let last = first.step_by(direction, line.number(steps)?)
+            .ok_or_else(|| line.error(steps, "the trench goes too far to keep track of"))?;
+
+        // hex -- five digits of steps and then one for the direction
+        let hex = line.delimited(colour, "(#", ")", EXPECTED)?;
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)) {
+            return Err(line.error(colour, EXPECTED));
+        }
+        let hex_steps = isize::from_str_radix(&hex[..5], 16).unwrap();
+        let hex_direction = match &hex[5..] {
+            "0" => East,
+            "1" => South,
+            "2" => West,
+            "3" => North,
+            _ => return Err(line.error(&hex[5..], "expected the last digit of the colour to be a direction from 0 to 3"))
+        };
+        let hex_last = hex_first.step_by(hex_direction, hex_steps)
+            .ok_or_else(|| line.error(hex, "the trench goes too far to keep track of"))?;
 
-        let first = Coord { row, column };
-        let hex_first = Coord { row: row_hex, column: column_hex };
-        row = last.row;
-        column = last.column;
-        row_hex = hex_last.row;
-        column_hex = hex_last.column;
         edges.push(Edge {
             first,
             last,
             hex_first,
             hex_last
         });
+        first = last;
+        hex_first = hex_last;
     }
 
-    edges
+    // Catch a lagoon that's too big to measure, or flat, here, rather than when measuring it
+    for (hex, instructions) in [(false, "plain instructions"), (true, "colours")] {
+        let lagoon = lagoon(&edges, hex);
+        if lagoon.enclosed_points().is_none() {
+            let problem = if lagoon.orientation() == Some(Orientation::Degenerate) { "flat, with no inside" } else { "too big to measure" };
+            return Err(ParseError::new(1, 1, "", format!("the lagoon dug from the {} is {}", instructions, problem)));
+        }
+    }
+    Ok(edges)
 }
 
+/// One trench from the dig plan, for both readings of the instruction.
 #[derive(PartialEq, Debug)]
-struct Edge {
-    first: Coord,
-    last: Coord,
-    hex_first: Coord,
-    hex_last: Coord
+pub struct Edge {
+    first: Point,
+    last: Point,
+    hex_first: Point,
+    hex_last: Point
 }
 
-impl Edge {
-    fn det(&self) -> i64 {
-        (self.first.row * self.last.column) - (self.first.column * self.last.row)
-    }
+/// Generates a dig plan from a random loop on a `size` by `size` grid. The instructions hidden in the colours follow
+/// the same loop turned or flipped over, and stretched out a long way, so neither lagoon's edge crosses itself.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let size = size.clamp(3, 10000);
+    let path = random_loop(rng, size, size);
+    // Only the corners matter, the cells in between become the lengths of each edge
+    let corners: Vec<(usize, usize)> = path.iter().enumerate().filter(|(i, _)| {
+        let before = path[(i + path.len() - 1) % path.len()];
+        let after = path[(i + 1) % path.len()];
+        before.0 != after.0 && before.1 != after.1
+    }).map(|(_, corner)| *corner).collect();
+
+    let (swap, flip_x, flip_y) = (rng.gen_bool(0.5), rng.gen_bool(0.5), rng.gen_bool(0.5));
+    let turned: Vec<(usize, usize)> = corners.iter().map(|&(x, y)| {
+        let (x, y) = if swap { (y, x) } else { (x, y) };
+        (if flip_x { size - 1 - x } else { x }, if flip_y { size - 1 - y } else { y })
+    }).collect();
+
+    let plain = edges(&stretch(rng, &corners, size, 4));
+    let hidden = edges(&stretch(rng, &turned, size, 0xfffff / size));
+    plain.iter().zip(hidden).map(|((direction, length), (hidden_direction, hidden_length))| {
+        let hidden_direction = match hidden_direction { 'R' => 0, 'D' => 1, 'L' => 2, _ => 3 };
+        format!("{} {} (#{:05x}{})", direction, length, hidden_length, hidden_direction)
+    }).join("\n")
+}
 
-    fn det_hex(&self) -> i64 {
-        (self.hex_first.row * self.hex_last.column) - (self.hex_first.column * self.hex_last.row)
-    }
+// Moves the corners apart by between one and `most` for each row and column between them. This keeps the shape of the
+// loop, so it still doesn't cross itself.
+fn stretch(rng: &mut StdRng, corners: &[(usize, usize)], size: usize, most: usize) -> Vec<(usize, usize)> {
+    let positions = |rng: &mut StdRng| -> Vec<usize> {
+        (0..size).scan(0, |position, _| {
+            *position += rng.gen_range(1..=most);
+            Some(*position)
+        }).collect()
+    };
+    let (columns, rows) = (positions(rng), positions(rng));
+    corners.iter().map(|&(x, y)| (columns[x], rows[y])).collect()
 }
 
-#[derive(PartialEq, Hash, Debug)]
-struct Coord {
-    row: i64,
-    column: i64
+fn edges(corners: &[(usize, usize)]) -> Vec<(char, usize)> {
+    corners.iter().zip(corners.iter().cycle().skip(1)).map(|(&(x, y), &(next_x, next_y))| {
+        if next_x > x {
+            ('R', next_x - x)
+        } else if next_x < x {
+            ('L', x - next_x)
+        } else if next_y > y {
+            ('D', next_y - y)
+        } else {
+            ('U', y - next_y)
+        }
+    }).collect()
 }
 
 #[cfg(test)]
 mod test {
     use std::ops::Deref;
-    use indoc::indoc;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day18::{Coord, day_18a, day_18b, Edge, parse_instructions};
-
-    const TEST_DATA: &str = indoc! {
-                            "R 6 (#70c710)
-                             D 5 (#0dc571)
-                             L 2 (#5713f0)
-                             D 2 (#d2c081)
-                             R 2 (#59c680)
-                             D 2 (#411b91)
-                             L 5 (#8ceee2)
-                             U 2 (#caa173)
-                             L 1 (#1b58a2)
-                             U 2 (#caa171)
-                             R 2 (#7807d2)
-                             U 3 (#a77fa3)
-                             L 2 (#015232)
-                             U 2 (#7a21e3)" };
+    use crate::common::ParseError;
+    use crate::common::geometry::Orientation;
+    use crate::common::point::Point;
+    use crate::day18::{day_18a, day_18b, Edge, lagoon, parse_instructions};
+    use crate::examples::DAY18 as TEST_DATA;
 
     lazy_static! {
         static ref PARSED_DATA: Vec<Edge> = vec![
-            Edge { first: Coord { row: 0, column: 0 }, last: Coord { row: 0, column: 6 }, hex_first: Coord { row: 0, column: 0}, hex_last: Coord { row: 0, column: 461937} }, // R 6 -- R 461937
-            Edge { first: Coord { row: 0, column: 6 }, last: Coord { row: 5, column: 6 }, hex_first: Coord { row: 0, column: 461937}, hex_last: Coord { row: 56407, column: 461937} }, // D 5 -- D 56407
-            Edge { first: Coord { row: 5, column: 6 }, last: Coord { row: 5, column: 4 }, hex_first: Coord { row: 56407, column: 461937}, hex_last: Coord { row: 56407, column: 818608} }, // L 2 -- R 356671
-            Edge { first: Coord { row: 5, column: 4 }, last: Coord { row: 7, column: 4 }, hex_first: Coord { row: 56407, column: 818608 }, hex_last: Coord { row: 919647, column: 818608} }, // D 2 -- D 863240
-            Edge { first: Coord { row: 7, column: 4 }, last: Coord { row: 7, column: 6 }, hex_first: Coord { row: 919647, column: 818608}, hex_last: Coord { row: 919647, column: 1186328} }, // R 2 -- R 367720
-            Edge { first: Coord { row: 7, column: 6 }, last: Coord { row: 9, column: 6 }, hex_first: Coord { row: 919647, column: 1186328}, hex_last: Coord { row: 1186328, column: 1186328} }, // D 2 -- D 266681
-            Edge { first: Coord { row: 9, column: 6 }, last: Coord { row: 9, column: 1 }, hex_first: Coord { row: 1186328, column: 1186328}, hex_last: Coord { row: 1186328, column: 609066} }, // L 5 -- L 577262
-            Edge { first: Coord { row: 9, column: 1 }, last: Coord { row: 7, column: 1 }, hex_first: Coord { row: 1186328, column: 609066}, hex_last: Coord { row: 356353, column: 609066} }, // U 2 -- U 829975
-            Edge { first: Coord { row: 7, column: 1 }, last: Coord { row: 7, column: 0 }, hex_first: Coord { row: 356353, column: 609066}, hex_last: Coord { row: 356353, column: 497056} }, // L 1 -- L 112010
-            Edge { first: Coord { row: 7, column: 0 }, last: Coord { row: 5, column: 0 }, hex_first: Coord { row: 356353, column: 497056}, hex_last: Coord { row: 1186328, column: 497056} }, // U 2 -- D 829975
-            Edge { first: Coord { row: 5, column: 0 }, last: Coord { row: 5, column: 2 }, hex_first: Coord { row: 1186328, column: 497056}, hex_last: Coord { row: 1186328, column: 5411} }, // R 2 -- L 491645
-            Edge { first: Coord { row: 5, column: 2 }, last: Coord { row: 2, column: 2 }, hex_first: Coord { row: 1186328, column: 5411}, hex_last: Coord { row: 500254, column: 5411} }, // U 3 -- U 686074
-            Edge { first: Coord { row: 2, column: 2 }, last: Coord { row: 2, column: 0 }, hex_first: Coord { row: 500254, column: 5411}, hex_last: Coord { row: 500254, column: 0} }, // L 2 -- L 5411
-            Edge { first: Coord { row: 2, column: 0 }, last: Coord { row: 0, column: 0 }, hex_first: Coord { row: 500254, column: 0}, hex_last: Coord { row: 0, column: 0} } // U 2 -- U 500254
+            Edge { first: Point { row: 0, column: 0 }, last: Point { row: 0, column: 6 }, hex_first: Point { row: 0, column: 0}, hex_last: Point { row: 0, column: 461937} }, // R 6 -- R 461937
+            Edge { first: Point { row: 0, column: 6 }, last: Point { row: 5, column: 6 }, hex_first: Point { row: 0, column: 461937}, hex_last: Point { row: 56407, column: 461937} }, // D 5 -- D 56407
+            Edge { first: Point { row: 5, column: 6 }, last: Point { row: 5, column: 4 }, hex_first: Point { row: 56407, column: 461937}, hex_last: Point { row: 56407, column: 818608} }, // L 2 -- R 356671
+            Edge { first: Point { row: 5, column: 4 }, last: Point { row: 7, column: 4 }, hex_first: Point { row: 56407, column: 818608 }, hex_last: Point { row: 919647, column: 818608} }, // D 2 -- D 863240
+            Edge { first: Point { row: 7, column: 4 }, last: Point { row: 7, column: 6 }, hex_first: Point { row: 919647, column: 818608}, hex_last: Point { row: 919647, column: 1186328} }, // R 2 -- R 367720
+            Edge { first: Point { row: 7, column: 6 }, last: Point { row: 9, column: 6 }, hex_first: Point { row: 919647, column: 1186328}, hex_last: Point { row: 1186328, column: 1186328} }, // D 2 -- D 266681
+            Edge { first: Point { row: 9, column: 6 }, last: Point { row: 9, column: 1 }, hex_first: Point { row: 1186328, column: 1186328}, hex_last: Point { row: 1186328, column: 609066} }, // L 5 -- L 577262
+            Edge { first: Point { row: 9, column: 1 }, last: Point { row: 7, column: 1 }, hex_first: Point { row: 1186328, column: 609066}, hex_last: Point { row: 356353, column: 609066} }, // U 2 -- U 829975
+            Edge { first: Point { row: 7, column: 1 }, last: Point { row: 7, column: 0 }, hex_first: Point { row: 356353, column: 609066}, hex_last: Point { row: 356353, column: 497056} }, // L 1 -- L 112010
+            Edge { first: Point { row: 7, column: 0 }, last: Point { row: 5, column: 0 }, hex_first: Point { row: 356353, column: 497056}, hex_last: Point { row: 1186328, column: 497056} }, // U 2 -- D 829975
+            Edge { first: Point { row: 5, column: 0 }, last: Point { row: 5, column: 2 }, hex_first: Point { row: 1186328, column: 497056}, hex_last: Point { row: 1186328, column: 5411} }, // R 2 -- L 491645
+            Edge { first: Point { row: 5, column: 2 }, last: Point { row: 2, column: 2 }, hex_first: Point { row: 1186328, column: 5411}, hex_last: Point { row: 500254, column: 5411} }, // U 3 -- U 686074
+            Edge { first: Point { row: 2, column: 2 }, last: Point { row: 2, column: 0 }, hex_first: Point { row: 500254, column: 5411}, hex_last: Point { row: 500254, column: 0} }, // L 2 -- L 5411
+            Edge { first: Point { row: 2, column: 0 }, last: Point { row: 0, column: 0 }, hex_first: Point { row: 500254, column: 0}, hex_last: Point { row: 0, column: 0} } // U 2 -- U 500254
         ];
     }
 
     #[test]
     fn test_parse_instruction() {
-        assert_eq!(parse_instructions(TEST_DATA), *PARSED_DATA.deref())
+        assert_eq!(parse_instructions(TEST_DATA).unwrap(), *PARSED_DATA.deref())
+    }
+
+    #[rstest]
+    #[case("R 6 (#70c710)\nX 5 (#0dc571)", ParseError::new(2, 1, "X 5 (#0dc571)", "expected a direction, a number of steps and a colour, like R 6 (#70c710)"))]
+    #[case("R 99999999999999999999 (#70c710)", ParseError::new(1, 3, "R 99999999999999999999 (#70c710)", "expected a number, found \"99999999999999999999\""))]
+    #[case("R 6 (70c710)", ParseError::new(1, 5, "R 6 (70c710)", "expected a direction, a number of steps and a colour, like R 6 (#70c710)"))]
+    #[case("R 6 (#70c714)", ParseError::new(1, 12, "R 6 (#70c714)", "expected the last digit of the colour to be a direction from 0 to 3"))]
+    #[case("R 9223372036854775807 (#70c710)\nR 1 (#70c710)", ParseError::new(2, 3, "R 1 (#70c710)", "the trench goes too far to keep track of"))]
+    #[case("R 4611686018427387903 (#000010)\nD 4611686018427387903 (#000011)\nL 4611686018427387903 (#000012)\nU 4611686018427387903 (#000013)",
+           ParseError::new(1, 1, "", "the lagoon dug from the plain instructions is too big to measure"))]
+    #[case("R 5 (#000020)\nL 5 (#000022)", ParseError::new(1, 1, "", "the lagoon dug from the plain instructions is flat, with no inside"))]
+    fn test_parse_instruction_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_instructions(input), Err(expected));
     }
 
     #[test]
@@ -163,23 +231,13 @@ mod test {
         assert_eq!(day_18b(PARSED_DATA.deref()), 952408144115);
     }
 
-    #[rstest]
-    #[case(0, 0)]
-    #[case(1, -30)]
-    #[case(2, -10)]
-    #[case(3, -8)]
-    #[case(4, 14)]
-    #[case(5, -12)]
-    #[case(6, -45)]
-    #[case(7, 2)]
-    #[case(8, -7)]
-    #[case(9, 0)]
-    #[case(10, 10)]
-    #[case(11, 6)]
-    #[case(12, -4)]
-    #[case(13, 0)]
-    fn test_edge_det(#[case] idx: usize, #[case] expected: i64) {
-        assert_eq!(PARSED_DATA.get(idx).unwrap().det(), expected);
+    #[test]
+    fn test_lagoon() {
+        let lagoon = lagoon(PARSED_DATA.deref(), false);
+        assert_eq!(lagoon.twice_signed_area(), Some(-84));
+        assert_eq!(lagoon.orientation(), Some(Orientation::Clockwise));
+        assert_eq!(lagoon.boundary_points(), Some(38));
+        assert_eq!(lagoon.interior_points(), Some(24));
     }
 
 }
\ No newline at end of file
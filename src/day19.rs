@@ -1,17 +1,42 @@
-use std::cmp::max;
-use std::collections::HashMap;
-use regex::Regex;
+//! Day 19: Aplenty
+//!
+//! Sorts the parts through the workflows, then sends ranges of ratings through them to count every accepted part.
+
+use std::collections::{HashMap, HashSet, VecDeque};
 use tailcall::tailcall;
-use crate::common::load_from;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::common::parse::{blocks, Line, missing};
+use crate::common::interval::{Interval, Region};
+use crate::solution::Solution;
 use crate::day19::Result::*;
 use crate::day19::Check::*;
 use crate::day19::Category::*;
 
-pub fn run_day() {
-    let data = load_from("day19.txt");
-    let (rules, parts) = parse_data(data.as_str());
-    println!("Part 1: {}", day19a(&parts, &rules));
-    println!("Part 2: {}", day19b(&rules));
+/// Sums the ratings of the accepted parts, then counts every combination of ratings that would be accepted.
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u8 = 19;
+
+    type Parsed = System;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> std::result::Result<System, ParseError> {
+        parse_data(input)
+    }
+
+    fn part_one(&self, parsed: &System) -> u64 {
+        day19a(&parsed.1, &parsed.0)
+    }
+
+    fn part_two(&self, parsed: &System) -> u64 {
+        day19b(&parsed.0)
+    }
 }
 
 fn day19a(parts: &Vec<Part>, rules: &HashMap<String, Vec<Check>>) -> u64 {
@@ -24,7 +49,7 @@ fn day19b(rules: &HashMap<String, Vec<Check>>) -> u64 {
 
 #[tailcall]
 fn run_workflow(part: &Part, rules: &HashMap<String, Vec<Check>>, current_rule: &str) -> bool {
-    let rule = rules.get(current_rule).unwrap();
+    let rule = rules.get(current_rule).expect("every workflow was checked when it was parsed");
 
     let result = rule.iter().filter_map(|check| {
         match check {
@@ -44,7 +69,7 @@ fn run_workflow(part: &Part, rules: &HashMap<String, Vec<Check>>, current_rule:
             }
             Always(result) => Some(result)
         }
-    }).next().unwrap();
+    }).next().expect("every workflow ends with a rule that matches everything");
 
     if let Workflow(wf) = result {
         run_workflow(part, rules, wf.as_str())
@@ -61,7 +86,7 @@ fn run_ranges(part_range: Vec<(String, PartRange)>, rules: &HashMap<String, Vec<
     let mut next_ranges: Vec<(String, PartRange)> = Vec::new();
     let mut add: u64 = acc;
     for (next_workflow, range) in part_range {
-        let checks = rules.get(next_workflow.as_str()).unwrap();
+        let checks = rules.get(next_workflow.as_str()).expect("every workflow was checked when it was parsed");
         let mut left = range.clone();
         for check in checks {
             let (new_workflow, cont) = left.split(check);
@@ -92,57 +117,109 @@ fn run_ranges(part_range: Vec<(String, PartRange)>, rules: &HashMap<String, Vec<
     }
 }
 
-fn parse_data(data: &str) -> (HashMap<String, Vec<Check>>, Vec<Part>) {
-    let rule_regex = Regex::new(r"([a-z]+)\{(.+)}").unwrap();
+fn parse_data(data: &str) -> std::result::Result<System, ParseError> {
+    let blocks = blocks(data);
+    let [workflows, parts] = &blocks[..] else {
+        return Err(missing(data, "expected the workflows, a blank line and then the parts"));
+    };
 
-    let mut lines = data.lines();
     let mut rules: HashMap<String, Vec<Check>> = HashMap::new();
-    let mut parts: Vec<Part> = Vec::new();
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            break;
+    // Where each workflow sends parts, so they can be checked once every workflow has been read
+    let mut leads_to: HashMap<&str, Vec<(Line, &str)>> = HashMap::new();
+    for line in workflows {
+        const EXPECTED: &str = "expected a workflow, like px{a<2006:qkq,m>2090:A,rfg}";
+        let (key, rule_string) = line.split_once(line.text, "{", EXPECTED)?;
+        let rule_string = line.delimited(rule_string, "", "}", EXPECTED)?;
+        let mut destinations = Vec::new();
+        let checks = line.list(rule_string, ",", |check| {
+            destinations.push((*line, check.split_once(':').map_or(check, |(_, action)| action)));
+            Check::parse(*line, check)
+        })?;
+        if !matches!(checks.last(), Some(Always(_))) {
+            let last = rule_string.rsplit(',').next().map_or(rule_string, str::trim);
+            return Err(line.error(last, "the last rule has to send every part somewhere, like rfg"));
         }
-
-        let captures = rule_regex.captures(line).unwrap();
-        let key = String::from(captures.get(1).unwrap().as_str());
-        let rule_string = captures.get(2).unwrap().as_str();
-        let checks: Vec<Check> = rule_string.split(",").map(|x| Check::parse(x)).collect();
-        rules.insert(key, checks);
+        if rules.insert(String::from(key), checks).is_some() {
+            return Err(line.error(key, format!("there's already a workflow called {}", key)));
+        }
+        leads_to.insert(key, destinations);
     }
 
-    while let Some(line) = lines.next() {
-        if line.is_empty() {
-            break;
+    if !rules.contains_key("in") {
+        return Err(ParseError::new(1, 1, data.lines().next().unwrap_or_default(), "there's no workflow called in to start from"));
+    }
+    for (line, destination) in leads_to.values().flatten() {
+        if !["A", "R"].contains(destination) && !rules.contains_key(*destination) {
+            return Err(line.error(destination, format!("there's no workflow called {}", destination)));
         }
-
-        parts.push(Part::parse(line));
+    }
+    if let Some((line, destination)) = find_loop("in", &leads_to, &mut HashMap::new()) {
+        return Err(line.error(destination, format!("the workflows go round in a loop back to {}", destination)));
     }
 
-    (rules, parts)
+    let parts = parts.iter().map(|line| Part::parse(*line)).collect::<std::result::Result<Vec<Part>, ParseError>>()?;
+    Ok((rules, parts))
 }
 
+// Where the workflows go round in a loop, as the first step that goes back to a workflow that led to it, so a part
+// sorted with them would never be accepted or rejected. `seen` holds true for the workflows being followed, and false
+// for the ones that have been followed all the way to A or R.
+fn find_loop<'a>(from: &'a str, leads_to: &HashMap<&'a str, Vec<(Line<'a>, &'a str)>>, seen: &mut HashMap<&'a str, bool>) -> Option<(Line<'a>, &'a str)> {
+    seen.insert(from, true);
+    for &(line, to) in leads_to.get(from).into_iter().flatten() {
+        match seen.get(to) {
+            Some(true) => return Some((line, to)),
+            Some(false) => {},
+            None => if let Some(found) = find_loop(to, leads_to, seen) {
+                return Some(found);
+            }
+        }
+    }
+    seen.insert(from, false);
+    None
+}
+
+/// The workflows by name, and the parts to sort with them.
+pub type System = (HashMap<String, Vec<Check>>, Vec<Part>);
+
+/// One of the four ratings a part has.
 #[derive(PartialEq, Debug)]
-enum Category {
+pub enum Category {
+    /// Extremely cool looking.
     X,
+    /// Musical.
     M,
+    /// Aerodynamic.
     A,
+    /// Shiny.
     S
 }
 
 impl Category {
-    fn from(string: &str) -> Category {
+    fn parse(line: Line, string: &str) -> std::result::Result<Category, ParseError> {
         match string {
-            "x" => X,
-            "m" => M,
-            "a" => A,
-            "s" => S,
-            x => panic!("{} is not a category", x)
+            "x" => Ok(X),
+            "m" => Ok(M),
+            "a" => Ok(A),
+            "s" => Ok(S),
+            x => Err(line.error(string, format!("expected x, m, a or s, found \"{}\"", x)))
+        }
+    }
+
+    // Which side of a PartRange's region holds this rating
+    fn axis(&self) -> usize {
+        match self {
+            X => 0,
+            M => 1,
+            A => 2,
+            S => 3
         }
     }
 }
 
+/// A part and its ratings.
 #[derive(PartialEq, Debug)]
-struct Part {
+pub struct Part {
     x: u64,
     m: u64,
     a: u64,
@@ -164,29 +241,31 @@ impl Part {
         }
     }
 
-    fn parse(string: &str) -> Part {
+    fn parse(line: Line) -> std::result::Result<Part, ParseError> {
         let mut result = Part { x: 0, m: 0, a: 0, s: 0 };
-        let parts = string[1..(string.len()-1)].split(",");
-        for part in parts {
-            let (key, string_value) = part.split_once("=").unwrap();
-            let value = string_value.parse::<u64>().unwrap();
-            match key {
-               "x" => result.x = value,
-               "m" => result.m = value,
-               "a" => result.a = value,
-               "s" => result.s = value,
-                l => panic!("{} is not a attribute", l)
+        let ratings = line.delimited(line.text, "{", "}", "expected a part, like {x=787,m=2655,a=1222,s=2876}")?;
+        let ratings = line.pairs(ratings, ",", "=", |key, value| Ok((Category::parse(line, key)?, line.number::<u64>(value)?)))?;
+        for (category, value) in ratings {
+            match category {
+                X => result.x = value,
+                M => result.m = value,
+                A => result.a = value,
+                S => result.s = value
             };
         }
 
-        result
+        Ok(result)
     }
 }
 
+/// One rule in a workflow.
 #[derive(PartialEq, Debug)]
-enum Check {
+pub enum Check {
+    /// Matches parts whose rating in the category is below the number.
     LessThan(Category, u64, Result),
+    /// Matches parts whose rating in the category is above the number.
     GreaterThan(Category, u64, Result),
+    /// Matches every part, which is how a workflow ends.
     Always(Result)
 }
 
@@ -199,26 +278,31 @@ impl Check {
         }.clone()
     }
 
-    fn parse(rule: &str) -> Check {
-        if rule.contains(":") {
-            let (first, action) = rule.split_once(":").unwrap();
-            let category = Category::from(&first[0..1]);
-            let bound = first[2..].parse::<u64>().unwrap();
-            if &first[1..2] == ">" {
-                GreaterThan(category, bound, Result::parse(action))
-            } else {
-                LessThan(category, bound, Result::parse(action))
-            }
-        } else {
-            Always(Result::parse(rule))
+    fn parse<'a>(line: Line<'a>, rule: &'a str) -> std::result::Result<Check, ParseError> {
+        let Some((first, action)) = rule.split_once(":") else {
+            return Ok(Always(Result::parse(rule)));
+        };
+        let (Some(category), Some(comparison), Some(bound)) = (first.get(0..1), first.get(1..2), first.get(2..)) else {
+            return Err(line.error(first, "expected a rating, < or > and a number, like a<2006"));
+        };
+        let category = Category::parse(line, category)?;
+        let bound = line.number::<u64>(bound)?;
+        match comparison {
+            ">" => Ok(GreaterThan(category, bound, Result::parse(action))),
+            "<" => Ok(LessThan(category, bound, Result::parse(action))),
+            _ => Err(line.error(comparison, format!("expected < or >, found \"{}\"", comparison)))
         }
     }
 }
 
+/// Where a part goes after a rule matches it.
 #[derive(PartialEq, Debug, Clone)]
-enum Result {
+pub enum Result {
+    /// On to the workflow with this name.
     Workflow(String),
+    /// The part is accepted.
     Accept,
+    /// The part is rejected.
     Reject
 }
 
@@ -232,162 +316,102 @@ impl Result {
     }
 }
 
+/// Every part whose ratings are each within a range, so a workflow can be run on many parts at once.
 #[derive(PartialEq, Clone, Copy, Debug)]
-struct SimpleRange {
-    min: u64,
-    max: u64
+pub struct PartRange {
+    ratings: Region<u64, 4>
 }
 
-impl SimpleRange {
-    fn init() -> SimpleRange {
-        SimpleRange { min: 1, max: 4000 }
-    }
+impl PartRange {
 
-    fn range(&self) -> u64 {
-        max(0, self.max + 1 - self.min)
+    /// Every possible part, with each rating from 1 to 4000.
+    pub fn init() -> PartRange {
+        PartRange { ratings: Region::new([Interval::inclusive(1, 4000); 4]) }
     }
 
-    fn split_less_than(&self, amt: u64) -> (Option<SimpleRange>, Option<SimpleRange>) {
-        if self.min >= amt {
-            (None, Some(self.clone()))
-        } else if self.max < amt {
-            (Some(self.clone()), None)
-        } else {
-            (Some(SimpleRange { min: self.min, max: amt - 1}), Some(SimpleRange { min: amt, max: self.max }))
-        }
+    /// The number of distinct parts in the range.
+    pub fn total(&self) -> u64 {
+        self.ratings.volume()
     }
 
-    fn split_greater_than(&self, amt: u64) -> (Option<SimpleRange>, Option<SimpleRange>) {
-        if self.min > amt {
-            (Some(self.clone()), None)
-        } else if self.max <= amt {
-            (None, Some(self.clone()))
-        } else {
-            (Some(SimpleRange { min: amt + 1, max: self.max}), Some(SimpleRange { min: self.min, max: amt }))
+    /// Splits the range into the parts that match the check and the parts that don't. Either can be empty.
+    pub fn split(&self, check: &Check) -> (Option<PartRange>, Option<PartRange>) {
+        let wrap = |ratings: Option<Region<u64, 4>>| ratings.map(|ratings| PartRange { ratings });
+        match check {
+            LessThan(category, amt, _) => {
+                let (below, above) = self.ratings.split_at(category.axis(), *amt);
+                (wrap(below), wrap(above))
+            },
+            GreaterThan(category, amt, _) => {
+                let (below, above) = self.ratings.split_at(category.axis(), amt + 1);
+                (wrap(above), wrap(below))
+            },
+            Always(_) => (Some(*self), None)
         }
     }
-}
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-struct PartRange {
-    x: SimpleRange,
-    m: SimpleRange,
-    a: SimpleRange,
-    s: SimpleRange
 }
 
-impl PartRange {
-
-    fn init() -> PartRange {
-        PartRange {
-            x: SimpleRange::init(),
-            m: SimpleRange::init(),
-            a: SimpleRange::init(),
-            s: SimpleRange::init()
-        }
-    }
 
-    fn total(&self) -> u64 {
-        self.x.range() * self.m.range() * self.s.range() * self.a.range()
-    }
-
-    fn split(&self, check: &Check) -> (Option<PartRange>, Option<PartRange>) {
-        match check {
-            LessThan(X, amt, _) => {
-                let (first, second) = self.x.split_less_than(*amt);
-                (
-                    first.map(|r| PartRange { x: r, m: self.m.clone(), a: self.a.clone(), s: self.s.clone() }),
-                    second.map(|r| PartRange { x: r, m: self.m.clone(), a: self.a.clone(), s: self.s.clone() })
-                )
-            },
-            LessThan(M, amt, _) => {
-                let (first, second) = self.m.split_less_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: r, a: self.a.clone(), s: self.s.clone() }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: r, a: self.a.clone(), s: self.s.clone() })
-                )
-            },
-            LessThan(A, amt, _) => {
-                let (first, second) = self.a.split_less_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: r, s: self.s.clone() }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: r, s: self.s.clone() })
-                )
-            },
-            LessThan(S, amt, _) => {
-                let (first, second) = self.s.split_less_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: self.a.clone(), s: r }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: self.a.clone(), s: r })
-                )
-            },
-            GreaterThan(X, amt, _) => {
-                let (first, second) = self.x.split_greater_than(*amt);
-                (
-                    first.map(|r| PartRange { x: r, m: self.m.clone(), a: self.a.clone(), s: self.s.clone() }),
-                    second.map(|r| PartRange { x: r, m: self.m.clone(), a: self.a.clone(), s: self.s.clone() })
-                )
-            },
-            GreaterThan(M, amt, _) => {
-                let (first, second) = self.m.split_greater_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: r, a: self.a.clone(), s: self.s.clone() }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: r, a: self.a.clone(), s: self.s.clone() })
-                )
-            },
-            GreaterThan(A, amt, _) => {
-                let (first, second) = self.a.split_greater_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: r, s: self.s.clone() }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: r, s: self.s.clone() })
-                )
-            },
-            GreaterThan(S, amt, _) => {
-                let (first, second) = self.s.split_greater_than(*amt);
-                (
-                    first.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: self.a.clone(), s: r }),
-                    second.map(|r| PartRange { x: self.x.clone(), m: self.m.clone(), a: self.a.clone(), s: r })
-                )
-            },
-            Always(_) => (Some(self.clone()), None)
+/// Generates up to `size` workflows and `size` parts. The workflows branch out from `in` like a tree, so every part
+/// is accepted or rejected in the end.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+    let size = size.clamp(1, 10000);
+    let mut used: HashSet<String> = HashSet::from([String::from("in")]);
+    let mut waiting: VecDeque<String> = VecDeque::from([String::from("in")]);
+    let mut workflows: Vec<String> = Vec::new();
+
+    while let Some(name) = waiting.pop_front() {
+        let mut targets: Vec<String> = Vec::new();
+        for _ in 0..=rng.gen_range(1..=3) {
+            let target = if used.len() < size && rng.gen_bool(0.6) {
+                let workflow = loop {
+                    let workflow: String = (0..rng.gen_range(2..=3)).map(|_| rng.gen_range('a'..='z')).collect();
+                    if used.insert(workflow.clone()) {
+                        break workflow;
+                    }
+                };
+                waiting.push_back(workflow.clone());
+                workflow
+            } else if rng.gen_bool(0.5) {
+                String::from("A")
+            } else {
+                String::from("R")
+            };
+            targets.push(target);
         }
+        let otherwise = targets.pop().unwrap();
+        let rules = targets.iter().map(|target| {
+            let category = CATEGORIES[rng.gen_range(0..CATEGORIES.len())];
+            let comparison = if rng.gen_bool(0.5) { '<' } else { '>' };
+            format!("{}{}{}:{}", category, comparison, rng.gen_range(1..=4000), target)
+        }).join(",");
+        workflows.push(format!("{}{{{},{}}}", name, rules, otherwise));
     }
+    workflows.shuffle(rng);
 
+    let parts = (0..size).map(|_| {
+        let [x, m, a, s] = [(); 4].map(|_| rng.gen_range(1..=4000));
+        format!("{{x={},m={},a={},s={}}}", x, m, a, s)
+    }).join("\n");
+    format!("{}\n\n{}", workflows.join("\n"), parts)
 }
 
-
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
     use std::ops::Deref;
-    use indoc::indoc;
     use proptest::proptest;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
-    use crate::day19::{Part, Check, parse_data, day19a, day19b};
+    use crate::common::ParseError;
+    use crate::common::parse::Line;
+    use crate::day19::{Part, PartRange, Check, parse_data, day19a, day19b};
     use crate::day19::Check::*;
     use crate::day19::Category::*;
     use crate::day19::Result::*;
-
-    const TEST_DATA: &str = indoc! {
-        "px{a<2006:qkq,m>2090:A,rfg}
-         pv{a>1716:R,A}
-         lnx{m>1548:A,A}
-         rfg{s<537:gd,x>2440:R,A}
-         qs{s>3448:A,lnx}
-         qkq{x<1416:A,crn}
-         crn{x>2662:A,R}
-         in{s<1351:px,qqz}
-         qqz{s>2770:qs,m<1801:hdj,R}
-         gd{a>3333:R,R}
-         hdj{m>838:A,pv}
-
-         {x=787,m=2655,a=1222,s=2876}
-         {x=1679,m=44,a=2067,s=496}
-         {x=2036,m=264,a=79,s=2244}
-         {x=2461,m=1339,a=466,s=291}
-         {x=2127,m=1623,a=2188,s=1013}"
-    };
+    use crate::examples::DAY19 as TEST_DATA;
 
     lazy_static! {
         static ref PARSED_CHECK_SETS: HashMap<String, Vec<Check>> = HashMap::from([
@@ -427,7 +451,7 @@ mod test {
 
     #[test]
     fn test_parsing() {
-        let (checks, parts) = parse_data(TEST_DATA);
+        let (checks, parts) = parse_data(TEST_DATA).unwrap();
         assert_eq!(checks, *PARSED_CHECK_SETS.deref());
         assert_eq!(parts, *PARSED_PARTS.deref());
     }
@@ -445,7 +469,35 @@ mod test {
     #[case("s>2770:qs", GreaterThan(S, 2770, Workflow(String::from("qs"))))]
     #[case("a>3333:R", GreaterThan(A, 3333, Reject))]
     fn test_check_parse(#[case] input: &str, #[case] expected: Check) {
-        assert_eq!(Check::parse(input), expected);
+        assert_eq!(Check::parse(Line::new(0, input), input), Ok(expected));
+    }
+
+    #[rstest]
+    #[case("in{s<1351:px,qqz}\n", ParseError::new(1, 1, "", "expected the workflows, a blank line and then the parts"))]
+    #[case("in{s<1351:px,qqz\n\n{x=1}", ParseError::new(1, 4, "in{s<1351:px,qqz", "expected a workflow, like px{a<2006:qkq,m>2090:A,rfg}"))]
+    #[case("in{s=1351:px,qqz}\n\n{x=1}", ParseError::new(1, 5, "in{s=1351:px,qqz}", "expected < or >, found \"=\""))]
+    #[case("in{q<1351:px,qqz}\n\n{x=1}", ParseError::new(1, 4, "in{q<1351:px,qqz}", "expected x, m, a or s, found \"q\""))]
+    #[case("in{s:px,qqz}\n\n{x=1}", ParseError::new(1, 4, "in{s:px,qqz}", "expected a rating, < or > and a number, like a<2006"))]
+    #[case("in{A}\n\n{x=1,m:2}", ParseError::new(3, 6, "{x=1,m:2}", "expected a key and a value with \"=\" between them"))]
+    #[case("in{A}\n\n{x=1,m=2a}", ParseError::new(3, 8, "{x=1,m=2a}", "expected a number, found \"2a\""))]
+    #[case("in{s<1351:px,A}\n\n{x=1}", ParseError::new(1, 11, "in{s<1351:px,A}", "there's no workflow called px"))]
+    #[case("px{A}\n\n{x=1}", ParseError::new(1, 1, "px{A}", "there's no workflow called in to start from"))]
+    #[case("in{s<1351:A}\n\n{x=1}", ParseError::new(1, 4, "in{s<1351:A}", "the last rule has to send every part somewhere, like rfg"))]
+    #[case("in{A}\nin{R}\n\n{x=1}", ParseError::new(2, 1, "in{R}", "there's already a workflow called in"))]
+    #[case("in{px}\npx{a<5:A,qk}\nqk{in}\n\n{x=1}", ParseError::new(3, 4, "qk{in}", "the workflows go round in a loop back to in"))]
+    fn test_parse_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_data(input), Err(expected));
+    }
+
+    #[rstest]
+    #[case(LessThan(X, 1001, Accept), 1000, 3000)]
+    #[case(GreaterThan(S, 3000, Reject), 1000, 3000)]
+    #[case(LessThan(A, 1, Accept), 0, 4000)]
+    fn test_part_range_split(#[case] check: Check, #[case] matched: u64, #[case] unmatched: u64) {
+        let (first, second) = PartRange::init().split(&check);
+        let others = 4000 * 4000 * 4000;
+        assert_eq!(first.map(|r| r.total()).unwrap_or(0), matched * others);
+        assert_eq!(second.map(|r| r.total()).unwrap_or(0), unmatched * others);
     }
 
     proptest! {
@@ -453,7 +505,7 @@ mod test {
         fn test_parts_parse(x in 0..=9999u64, m in 0..=9999u64, a in 0..=9999u64, s in 0..=9999u64) {
             // create string
             let input = format!("{{x={x},m={m},a={a},s={s}}}");
-            assert_eq!(Part::parse(input.as_str()), Part { x, m, a, s })
+            assert_eq!(Part::parse(Line::new(0, input.as_str())), Ok(Part { x, m, a, s }))
         }
     }
 
@@ -1,22 +1,58 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+//! Day 20: Pulse Propagation
+//!
+//! Simulates the pulses sent between the modules. Part two finds the loop on each input to the module that feeds rx.
+
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
+use std::iter::{from_fn, once, repeat_with};
 use std::ops::Deref;
 use dyn_eq::DynEq;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use num::integer::lcm;
-use regex::Regex;
 use tailcall::tailcall;
-use crate::common::{EMPTY_STRING_VEC, load_from};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::common::cycle;
+use crate::common::{EMPTY_STRING_VEC, ParseError};
+use crate::common::parse::{lines, missing};
+use crate::differential::Comparison;
+use crate::solution::{Part, Solution};
+use crate::{debug, info, trace};
 
 dyn_eq::eq_trait_object!(Module);
 
-pub fn run_day() {
-    let data = load_from("day20.txt");
-    let mut part_1_modules = parse_modules(data.as_str());
-    let mut part_2_modules = parse_modules(data.as_str());
-    println!("Part 1: {}", day20a(&mut part_1_modules));
-    println!("Part 2: {}", day20b(&mut part_2_modules));
+/// The product of the low and high pulses sent after a thousand button presses, then the presses needed to send a low
+/// pulse to rx. Each part works on its own copy of the modules, as they hold state.
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u8 = 20;
+
+    type Parsed = IndexMap<String, Box<dyn Module>>;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<IndexMap<String, Box<dyn Module>>, ParseError> {
+        parse_modules(input)
+    }
+
+    // Neither example has an rx, so it's only part two that needs it
+    fn check(&self, input: &str, modules: &IndexMap<String, Box<dyn Module>>, part: Part) -> Result<(), ParseError> {
+        match part {
+            Part::One => Ok(()),
+            Part::Two => check_rx(input, modules)
+        }
+    }
+
+    fn part_one(&self, modules: &IndexMap<String, Box<dyn Module>>) -> u64 {
+        day20a(&mut modules.clone())
+    }
+
+    fn part_two(&self, modules: &IndexMap<String, Box<dyn Module>>) -> u64 {
+        day20b(&mut modules.clone())
+    }
 }
 
 fn day20a(modules: &mut IndexMap<String, Box<dyn Module>>) -> u64 {
@@ -28,16 +64,35 @@ fn day20b(modules: &mut IndexMap<String, Box<dyn Module>>) -> u64 {
     find_rx(modules)
 }
 
-fn parse_modules(data: &str) -> IndexMap<String, Box<dyn Module>> {
+fn parse_modules(data: &str) -> Result<IndexMap<String, Box<dyn Module>>, ParseError> {
     // first, parse each line to be type, (b, %, &), and outputs (...)
-    let reg = Regex::new("([%&])?([a-z]+) -> (.+)").unwrap();
-    let r: Vec<(&str, &str, Vec<&str>)> = data.lines().map(|x| {
-        let captures = reg.captures(x).unwrap();
-        let module_type = captures.get(1).map(|x| x.as_str()).unwrap_or("b");
-        let module_name = captures.get(2).unwrap().as_str();
-        let output_modules: Vec<&str> = captures.get(3).unwrap().as_str().split(", ").collect();
-        (module_type, module_name, output_modules)
-    }).collect();
+    let mut names: HashSet<&str> = HashSet::new();
+    let r: Vec<(&str, &str, Vec<&str>)> = lines(data).map(|line| {
+        const EXPECTED: &str = "expected a module, like %a -> b, c";
+        let is_name = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase());
+        let (module, outputs) = line.split_once(line.text, " -> ", EXPECTED)?;
+        let (module_type, module_name) = match module.strip_prefix(['%', '&']) {
+            Some(name) => (&module[..1], name),
+            None => ("b", module)
+        };
+        if !is_name(module_name) {
+            return Err(line.error(line.text, EXPECTED));
+        }
+        if module_type == "b" && module_name != "broadcaster" {
+            return Err(line.error(module_name, "only the broadcaster can be without a % or & prefix"));
+        }
+        if !names.insert(module_name) {
+            return Err(line.error(module_name, format!("there's already a module called {}", module_name)));
+        }
+        let output_modules = line.list(outputs, ",", |output| {
+            if is_name(output) { Ok(output) } else { Err(line.error(output, format!("expected the name of a module, found \"{}\"", output))) }
+        })?;
+        Ok((module_type, module_name, output_modules))
+    }).collect::<Result<Vec<(&str, &str, Vec<&str>)>, ParseError>>()?;
+
+    if !r.iter().any(|(_, name, _)| *name == "broadcaster") {
+        return Err(missing(data, "there is no broadcaster module"));
+    }
 
     let mut modules: IndexMap<String, Box<dyn Module>> = IndexMap::new();
     let mut final_outputs: HashSet<String> = HashSet::new();
@@ -58,10 +113,10 @@ fn parse_modules(data: &str) -> IndexMap<String, Box<dyn Module>> {
                 }).collect();
                 Box::new(Conjunction::new(inputs.iter().map(|x| String::from(*x)).collect_vec(), outputs.clone()))
             }
-            "b" => {
+            // the only module with no prefix is the broadcaster, we checked that above
+            _ => {
                 Box::new(Broadcast::new(outputs.clone()))
             }
-            _ => panic!("Nope")
         };
         modules.insert(String::from(*input), module);
     }
@@ -72,10 +127,33 @@ fn parse_modules(data: &str) -> IndexMap<String, Box<dyn Module>> {
         }
     }
 
-    modules
+    Ok(modules)
 }
 
-#[derive(Eq, PartialEq, Hash)]
+// Part two watches the inputs to the one conjunction that feeds rx
+fn check_rx(input: &str, modules: &IndexMap<String, Box<dyn Module>>) -> Result<(), ParseError> {
+    let line_of = |name: &str| lines(input)
+        .find(|line| line.text.split_once(" -> ").is_some_and(|(module, _)| module.trim_start_matches(['%', '&']) == name))
+        .expect("every module with outputs has a line");
+    let feeds_rx: Vec<&String> = modules.iter()
+        .filter(|(_, module)| module.output().iter().any(|output| output == "rx"))
+        .map(|(name, _)| name)
+        .collect();
+    match feeds_rx[..] {
+        [] => Err(missing(input, "nothing sends pulses to rx, which part two needs")),
+        [name] => {
+            let line = line_of(name);
+            if line.text.starts_with('&') { Ok(()) } else { Err(line.error(line.text, "part two needs rx to be fed by a conjunction, like &zh -> rx")) }
+        }
+        [_, second, ..] => {
+            let line = line_of(second);
+            Err(line.error(line.text, "part two needs rx to be fed by only one module"))
+        }
+    }
+}
+
+// What every module remembers, to spot the modules getting back to how they were before
+#[derive(Eq, PartialEq, Hash, Clone)]
 struct Cache {
     state: Vec<bool>
 }
@@ -98,84 +176,71 @@ fn find_rx(modules: &mut IndexMap<String, Box<dyn Module>>) -> u64 {
     let input = modules.iter()
         .filter(|(_, module)| module.output().contains(&String::from("rx")))
         .map(|(name, _)| name.clone())
-        .next().unwrap();
+        .next().expect("part two was checked for rx");
 
-    let mut feed_in: HashSet<String> = modules.get(&input).unwrap().keys_to_watch().iter().map(|x| x.clone()).collect();
+    let mut feed_in: HashSet<String> = modules.get(&input).expect("part two was checked for rx").keys_to_watch().into_iter().collect();
+    info!("rx is fed by {}, which needs a high pulse from each of {}", input, feed_in.iter().sorted().join(", "));
 
     // figure out what happens from the broadcaster
     let mut loops: Vec<u64> = Vec::new();
     let output_from_bcast = modules.get("broadcaster").unwrap().output().clone();
 
     for bcast in output_from_bcast {
-        let mut cache: HashMap<Cache, u64> = HashMap::new();
-        cache.insert(Cache::from(modules), 0);
+        debug!("pressing the button with only {} connected to the broadcaster", bcast);
         modules.insert(input.clone(), Box::new(Output::new()));
         let mut counter = 0;
-        'outer: loop {
+        let mut found: Option<String> = None;
+        let states = once(Cache::from(modules)).chain(from_fn(|| {
             counter += 1;
             let mut pulses: Vec<(String, Pulse)> = Vec::new();
             send_pulse(VecDeque::from([(String::from("broadcaster"), bcast.clone(), Pulse::Low)]), modules, &mut pulses);
-            if let Some(x) = cache.insert(Cache::from(modules), counter) {
-                panic!("We got a loop {} -> {}", x, counter);
-            }
-           // println!("{:?}", modules.get("fd").unwrap());
             let exit = modules.get(&input).unwrap();
-            for i in &feed_in.clone() {
-                if exit.has_high(i) { // they're all inverters, so all need to get Low to send High.
-                    loops.push(counter);
-                    feed_in.remove(i);
-                    break 'outer;
-                }
-            }
+            trace!("press {}: {:?}", counter, exit);
+            // they're all inverters, so all need to get Low to send High.
+            found = feed_in.iter().find(|i| exit.has_high(i)).cloned();
+            found.is_none().then(|| Cache::from(modules))
+        }));
+        if let Some(repeat) = cycle::find_in(states) {
+            panic!("We got a loop {} -> {}", repeat.prefix, repeat.prefix + repeat.period);
         }
+        let i = found.unwrap();
+        info!("{} sends a high pulse to {} every {} presses", i, input, counter);
+        loops.push(counter);
+        feed_in.remove(&i);
     }
 
-    loops.iter().map(|x| x.clone()).reduce(|x, y| lcm(x, y)).unwrap()
+    let presses = loops.iter().copied().reduce(lcm).unwrap();
+    info!("the loops line up, sending a low pulse to rx, after {} presses", presses);
+    presses
 }
 
 
 fn cycle(modules: &mut IndexMap<String, Box<dyn Module>>, count: u64) -> (u64, u64) {
-    let mut pulses_list: Vec<Vec<Pulse>> = Vec::new();
-    let mut counter: u64 = 0;
-    let mut low: u64 = 0;
-    let mut high: u64 = 0;
-    let mut cache: HashMap<Cache, u64> = HashMap::new();
-
-    cache.insert(Cache::from(modules), 0);
-    while counter < count {
+    // The low and high pulses sent by each press, which only depend on the state the modules were in before it
+    let mut counts: Vec<(u64, u64)> = Vec::new();
+    let states = once(Cache::from(modules)).chain(repeat_with(|| {
         let mut pulses: Vec<(String, Pulse)> = Vec::new();
         send_pulse(VecDeque::from([(String::from("button"), String::from("broadcaster"), Pulse::Low)]), modules, &mut pulses);
-        let actual_pulses = pulses.iter().map(|(_, p)| p.clone()).collect_vec();
-        let low_cycle = actual_pulses.iter().filter(|x| **x == Pulse::Low).count() as u64;
-        low += low_cycle;
-        high += pulses.len() as u64 - low_cycle;
-        pulses_list.push(actual_pulses);
-        if let Some(previous) = cache.insert(Cache::from(modules), counter + 1) {
-            // we have a repeat, so we find that range and repeat it.
-            let range = counter + 1 - previous;
-            let left = count - counter - 1;
-            let full_cycles = left / range;
-            let remainder = left % range;
-            // at 0, we need all pulses, at 1, we need all but the first, so we just use previous here.
-            let pulses_in_cycle: Vec<&Vec<Pulse>> = pulses_list.iter().skip(previous as usize).collect();
-
-            if remainder != 0 {
-                let r: Vec<(u64, Pulse)> = pulses_in_cycle.iter().take(remainder as usize).flat_map(|x| x.iter()).sorted().dedup_with_count().map(|(s, p)| (s as u64, p.clone())).collect();
-                low += r.iter().find_or_first(|x| x.1 == Pulse::Low).map(|(x, _)| x.clone()).unwrap_or(0u64);
-                high += r.iter().find_or_first(|x| x.1 == Pulse::High).map(|(x, _)| x.clone()).unwrap_or(0u64);
-            }
-            let full_cycle: Vec<(u64, Pulse)> = pulses_in_cycle.iter().flat_map(|x| x.iter()).sorted().dedup_with_count().map(|(s, p)| (s as u64, p.clone())).collect();
-            let l = full_cycle.iter().find_or_first(|x| x.1 == Pulse::Low).map(|(x, _)| x.clone()).unwrap_or(0u64) * full_cycles;
-            let h = full_cycle.iter().find_or_first(|x| x.1 == Pulse::High).map(|(x, _)| x.clone()).unwrap_or(0u64) * full_cycles;
-            low += l;
-            high += h;
-            break;
-        } else {
-            counter += 1;
+        let low = pulses.iter().filter(|(_, p)| *p == Pulse::Low).count() as u64;
+        counts.push((low, pulses.len() as u64 - low));
+        Cache::from(modules)
+    })).take(count as usize + 1);
+    let repeat = cycle::find_in(states);
+
+    let total = |presses: &[(u64, u64)], times: u64| presses.iter().fold((0, 0), |(low, high), (l, h)| (low + l * times, high + h * times));
+    match repeat {
+        None => total(&counts, 1),
+        Some(repeat) => {
+            debug!("the modules are back as they were after {} presses after {}, so the rest repeats", repeat.prefix, repeat.prefix + repeat.period);
+            // from press prefix + 1 on, the presses go round the ones that started from the states in the cycle
+            let (before, cycle) = counts.split_at(repeat.prefix);
+            let left = count - repeat.prefix as u64;
+            let full_cycles = left / repeat.period as u64;
+            let remainder = (left % repeat.period as u64) as usize;
+            [total(before, 1), total(cycle, full_cycles), total(&cycle[..remainder], 1)].iter()
+                .fold((0, 0), |(low, high), (l, h)| (low + l, high + h))
         }
     }
-
-    (low, high)
 }
 
 #[tailcall]
@@ -200,32 +265,52 @@ fn send_pulse(modules_to_run: VecDeque<(String, String, Pulse)>, modules: &mut I
     }
 }
 
+/// A high or low pulse.
 #[repr(u8)]
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
-enum Pulse {
+pub enum Pulse {
+    /// A low pulse, which flips a flip-flop.
     Low,
+    /// A high pulse, which flip-flops ignore.
     High
 }
 
 // Modules
-trait Module: DynEq + Debug {
+/// A module in the network, which reacts to pulses and may send more.
+pub trait Module: DynEq + Debug {
+    /// Takes a pulse from the module called `input`, and gives the pulse it sends on to all its outputs, if any.
     fn receive(&mut self, pulse: &Pulse, input: &str) -> Option<Pulse>;
+
+    /// The names of the modules it sends pulses to.
     fn output(&self) -> &Vec<String>;
 
+    /// Whatever it remembers, as on or off, which is nothing by default.
     fn state(&self) -> Vec<bool> {
         Vec::new()
     }
 
+    /// The names of the modules that send it pulses, if it keeps track of them.
     fn keys_to_watch(&self) -> Vec<String> {
         Vec::new()
     }
 
+    /// Whether it has been sent a high pulse by the module called `from`, if it keeps track of that.
     fn has_high(&self, _: &String) -> bool {
         false
     }
+
+    /// A boxed copy, as the modules are kept as trait objects and so can't just derive Clone.
+    fn clone_module(&self) -> Box<dyn Module>;
+}
+
+// The modules hold state, so each part needs its own copy of the parsed modules to work on.
+impl Clone for Box<dyn Module> {
+    fn clone(&self) -> Self {
+        self.clone_module()
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct Broadcast {
     outputs: Vec<String>
 }
@@ -246,9 +331,13 @@ impl Module for Broadcast {
     fn output(&self) -> &Vec<String> {
         &self.outputs
     }
+
+    fn clone_module(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct FlipFlop {
     outputs: Vec<String>,
     state: bool
@@ -284,9 +373,13 @@ impl Module for FlipFlop {
     fn state(&self) -> Vec<bool> {
         Vec::from([self.state])
     }
+
+    fn clone_module(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct Conjunction {
     outputs: Vec<String>,
     high_from: IndexMap<String, Pulse>
@@ -326,9 +419,13 @@ impl Module for Conjunction {
     fn keys_to_watch(&self) -> Vec<String> {
         self.high_from.keys().map(|x| x.clone()).collect_vec()
     }
+
+    fn clone_module(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
 }
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct Output {
     last_received: HashSet<String>
 }
@@ -355,22 +452,109 @@ impl Module for Output {
     fn has_high(&self, from: &String) -> bool {
         self.last_received.contains(from)
     }
+
+    fn clone_module(&self) -> Box<dyn Module> {
+        Box::new(self.clone())
+    }
+}
+
+// The simple way to do part two: keep pressing the button until rx gets a low pulse. None if that takes more than
+// `limit` presses.
+fn press_until_rx(modules: &mut IndexMap<String, Box<dyn Module>>, limit: u64) -> Option<u64> {
+    let feeds_rx: Vec<String> = modules.iter()
+        .filter(|(_, module)| module.output().iter().any(|output| output == "rx"))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for press in 1..=limit {
+        let mut pulses: Vec<(String, Pulse)> = Vec::new();
+        send_pulse(VecDeque::from([(String::from("button"), String::from("broadcaster"), Pulse::Low)]), modules, &mut pulses);
+        if pulses.iter().any(|(from, pulse)| *pulse == Pulse::Low && feeds_rx.contains(from)) {
+            return Some(press);
+        }
+    }
+    None
+}
+
+/// Part two with the LCM of the loops that feed rx, and by pressing the button until rx gets a low pulse, for the
+/// differential tests. None if that takes too many presses.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let modules = parse_modules(input)?;
+    let Some(reference) = press_until_rx(&mut modules.clone(), 1_000_000) else {
+        return Ok(None);
+    };
+    Ok(Some((day20b(&mut modules.clone()).to_string(), reference.to_string())))
+}
+
+/// Generates a network built like the real one, from `size` counters of twelve flip-flops. Each counter resets when it
+/// reaches its own number, and sends a high pulse through an inverter to the conjunction that feeds rx when it does,
+/// so part two is the LCM of the counters' numbers. There are at most five counters, so that the LCM fits in a u64.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    generate_counters(rng, size.clamp(1, 5), 12)
+}
+
+/// Generates a network like [`generate`] does, but with up to three counters of five flip-flops, so that rx gets its
+/// low pulse soon enough to press the button until it does.
+pub(crate) fn generate_short_counters(rng: &mut StdRng, size: usize) -> String {
+    generate_counters(rng, size.clamp(1, 3), 5)
+}
+
+fn generate_counters(rng: &mut StdRng, counters: usize, bits: usize) -> String {
+    let mut used: HashSet<String> = HashSet::from([String::from("rx")]);
+    let mut name = |rng: &mut StdRng| loop {
+        let name: String = (0..2).map(|_| rng.gen_range('a'..='z')).collect();
+        if used.insert(name.clone()) {
+            return name;
+        }
+    };
+
+    let feeds_rx = name(rng);
+    let mut lines: Vec<String> = vec![format!("&{} -> rx", feeds_rx)];
+    let mut firsts: Vec<String> = Vec::new();
+    let mut numbers: HashSet<usize> = HashSet::new();
+    for _ in 0..counters {
+        // The highest and lowest bits are always set, which the counter needs to reset itself
+        let number = loop {
+            let number = rng.gen_range(1 << (bits - 1)..1 << bits) | 1;
+            if numbers.insert(number) {
+                break number;
+            }
+        };
+        let flip_flops: Vec<String> = (0..bits).map(|_| name(rng)).collect();
+        let (hub, inverter) = (name(rng), name(rng));
+
+        // Each flip-flop feeds the next one, and the hub if its bit is set in the number
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut targets: Vec<&String> = flip_flops.get(bit + 1).into_iter().collect();
+            if number & (1 << bit) != 0 {
+                targets.push(&hub);
+            }
+            targets.shuffle(rng);
+            lines.push(format!("%{} -> {}", flip_flop, targets.iter().join(", ")));
+        }
+        // Once every flip-flop it hears from is on, the hub turns the rest on, and the first one off, to carry back to 0
+        let mut targets: Vec<&String> = flip_flops.iter().enumerate()
+            .filter(|(bit, _)| *bit == 0 || number & (1 << bit) == 0)
+            .map(|(_, flip_flop)| flip_flop)
+            .chain(once(&inverter))
+            .collect();
+        targets.shuffle(rng);
+        lines.push(format!("&{} -> {}", hub, targets.iter().join(", ")));
+        lines.push(format!("&{} -> {}", inverter, feeds_rx));
+        firsts.push(flip_flops[0].clone());
+    }
+    lines.push(format!("broadcaster -> {}", firsts.join(", ")));
+    lines.shuffle(rng);
+    lines.join("\n")
 }
 
 #[cfg(test)]
 mod test {
     use indexmap::IndexMap;
-    use indoc::indoc;
     use rstest::rstest;
-    use crate::day20::{Broadcast, Conjunction, day20a, FlipFlop, Module, Output, parse_modules, Pulse};
-
-    const TEST_DATA_1: &str = indoc! {
-        "broadcaster -> a, b, c
-         %a -> b
-         %b -> c
-         %c -> inv
-         &inv -> a"
-    };
+    use crate::common::ParseError;
+    use crate::day20::{Broadcast, Conjunction, Day20, day20a, FlipFlop, Module, Output, parse_modules, press_until_rx, Pulse};
+    use crate::solution::{Part, Solution};
+    use crate::examples::{DAY20_1 as TEST_DATA_1, DAY20_2 as TEST_DATA_2};
 
     fn parsed_data_1() -> IndexMap<String, Box<dyn Module>> {
         IndexMap::from([
@@ -382,12 +566,6 @@ mod test {
         ])
     }
 
-    const TEST_DATA_2: &str = indoc! { "broadcaster -> a
-                                        %a -> inv, con
-                                        &inv -> b
-                                        %b -> con
-                                        &con -> output" };
-
     fn parsed_data_2() -> IndexMap<String, Box<dyn Module>> {
         IndexMap::from([
             (String::from("broadcaster"), Box::new(Broadcast::new(vec![String::from("a")])) as Box<dyn Module>),
@@ -401,26 +579,55 @@ mod test {
 
     #[test]
     fn test_parse_modules() {
-        assert_eq!(parse_modules(TEST_DATA_1), parsed_data_1());
+        assert_eq!(parse_modules(TEST_DATA_1).unwrap(), parsed_data_1());
     }
 
     #[test]
     fn test_parse_modules_2() {
-        assert_eq!(parse_modules(TEST_DATA_2), parsed_data_2());
+        assert_eq!(parse_modules(TEST_DATA_2).unwrap(), parsed_data_2());
+    }
+
+    #[rstest]
+    #[case("broadcaster -> a\n%a -> b\n#b -> a", ParseError::new(3, 1, "#b -> a", "expected a module, like %a -> b, c"))]
+    #[case("broadcaster -> a\na -> b", ParseError::new(2, 1, "a -> b", "only the broadcaster can be without a % or & prefix"))]
+    #[case("%a -> b\n&b -> a", ParseError::new(2, 1, "", "there is no broadcaster module"))]
+    #[case("broadcaster -> a, B", ParseError::new(1, 19, "broadcaster -> a, B", "expected the name of a module, found \"B\""))]
+    #[case("broadcaster -> a\n%a -> b\n&a -> b", ParseError::new(3, 2, "&a -> b", "there's already a module called a"))]
+    fn test_parse_modules_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_modules(input).err(), Some(expected));
+    }
+
+    #[rstest]
+    #[case("broadcaster -> a\n%a -> zz", Err(ParseError::new(2, 1, "", "nothing sends pulses to rx, which part two needs")))]
+    #[case("broadcaster -> a\n%a -> rx", Err(ParseError::new(2, 1, "%a -> rx", "part two needs rx to be fed by a conjunction, like &zh -> rx")))]
+    #[case("broadcaster -> a, b\n&a -> rx\n&b -> rx", Err(ParseError::new(3, 1, "&b -> rx", "part two needs rx to be fed by only one module")))]
+    #[case("broadcaster -> a\n&a -> rx", Ok(()))]
+    fn test_check(#[case] input: &str, #[case] expected: Result<(), ParseError>) {
+        let modules = parse_modules(input).unwrap();
+        assert_eq!(Day20.check(input, &modules, Part::One), Ok(()));
+        assert_eq!(Day20.check(input, &modules, Part::Two), expected);
     }
 
     #[test]
     fn test_day20a_1() {
-        let mut data = parse_modules(TEST_DATA_1);
+        let mut data = parse_modules(TEST_DATA_1).unwrap();
         assert_eq!(day20a(&mut data), 32000000);
     }
 
     #[test]
     fn test_day20a_2() {
-        let mut data = parse_modules(TEST_DATA_2);
+        let mut data = parse_modules(TEST_DATA_2).unwrap();
         assert_eq!(day20a(&mut data), 11687500);
     }
 
+    #[test]
+    fn test_press_until_rx() {
+        // b only turns on, and sends the high pulse that sets off the conjunction, every other press
+        let data = parse_modules("broadcaster -> a\n%a -> b\n%b -> con\n&con -> rx").unwrap();
+        assert_eq!(press_until_rx(&mut data.clone(), 10), Some(2));
+        assert_eq!(press_until_rx(&mut data.clone(), 1), None);
+    }
+
 
     #[rstest]
     #[case(false, Pulse::High, None, false)]
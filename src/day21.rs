@@ -1,99 +1,65 @@
-use std::cmp::max;
+//! Day 21: Step Counter
+//!
+//! Counts the garden plots the elf can reach, fitting a quadratic to the repeating map for the large step count in
+//! part two.
+
 use std::collections::HashSet;
 use tailcall::tailcall;
-use crate::common::load_from;
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::cancel;
+use crate::common::grid::Grid;
+use crate::common::point::{Direction, Point, Position};
+use crate::common::ParseError;
+use crate::differential::Comparison;
+use crate::solution::Solution;
+use crate::{debug, info};
+
+/// The plots reachable in exactly 64 steps, then in 26501365 steps on a map that repeats forever.
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u8 = 21;
+
+    type Parsed = Garden;
+    type PartOne = usize;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Garden, ParseError> {
+        parse_data(input)
+    }
+
+    fn part_one(&self, garden: &Garden) -> usize {
+        day21a(garden)
+    }
 
-pub fn run_day() {
-    let data = load_from("day21.txt");
-    let grid = parse_data(data.as_str());
-    println!("Part 1: {}", day21a(&grid));
-    println!("Part 2: {}", day21b(&grid));
+    fn part_two(&self, garden: &Garden) -> u64 {
+        day21b(garden)
+    }
 }
 
-fn day21a(grid: &Grid) -> usize {
-    walk_from_start(&grid, 64).len()
+fn day21a(garden: &Garden) -> usize {
+    walk_from_start(garden, 64).len()
 }
 
-fn day21b(grid: &Grid) -> u64 {
-    walk_on_infinite(&grid, 26501365)
+fn day21b(garden: &Garden) -> u64 {
+    walk_on_infinite(garden, 26501365)
 }
 
-// fn walk_on_infinite(grid: &Grid, steps_to_take: u64) -> u64 {
-//     let length = grid.rows as u64;
-//     let enlarged_grid = grid.expand_significantly();
-//
-//     // find stable pattern (flip flop) in original square
-//     // once found, count, then find length and 2 length later, that's our three points
-//     // determine quadratic from there.
-//
-//     #[tailcall]
-//     fn take_step_until_flip_flop(grid: &Grid, previous: HashSet<Coord>, from: HashSet<Coord>, counter: u64, max: u64, remainder: u64) -> (HashSet<Coord>, u64) {
-//
-//         let next_steps: HashSet<Coord> = from.iter().flat_map(|x| grid.next(x)).collect::<HashSet<Coord>>();
-//         let next_in_grid: HashSet<Coord> = next_steps
-//             .iter()
-//             .filter(|x| x.row < (grid.rows as isize) && x.row >= 0 && x.column < (grid.columns as isize) && x.column >= 0)
-//             .map(|x| x.clone())
-//             .collect();
-//         println!("{counter}");
-//         println!("{:?}", next_in_grid.difference(&previous).collect::<HashSet<&Coord>>());
-//         if (next_in_grid == previous && (counter % grid.rows as u64) == remainder) || counter == max {
-//             // this step is our result, return state and count
-//             (next_steps, counter)
-//         } else {
-//             let current: HashSet<Coord> = from.iter()
-//                 .filter(|x| x.row < (grid.rows as isize) && x.row >= 0 && x.column < (grid.columns as isize) && x.column >= 0)
-//                 .map(|x| x.clone())
-//                 .collect();
-//             take_step_until_flip_flop(grid, current, next_steps, counter + 1, max, remainder)
-//         }
-//     }
-//
-//     let initial: HashSet<Coord> = HashSet::from([grid.start.clone()]);
-//     // This is our min value. What's our target -- basically remainder + n*length
-//     let remainder = steps_to_take % length;
-//     println!("{remainder}");
-//     let (state, first_index) = take_step_until_flip_flop(&enlarged_grid, HashSet::new(), initial, 1, steps_to_take, remainder);
-//     let first_value = state.len() as u64;
-//     println!("{first_value}");
-//     if first_index == steps_to_take  {
-//         first_value
-//     } else {
-//         let (state_2, second_index) = take_step_until_flip_flop(&enlarged_grid, HashSet::new(), state, first_index + 1, steps_to_take, remainder);
-//         let second_value = state_2.len() as u64;
-//         if second_index == steps_to_take {
-//             second_value
-//         } else {
-//             let (state_3, third_index) = take_step_until_flip_flop(&enlarged_grid, HashSet::new(), state_2, second_index + 1, steps_to_take, remainder);
-//             let third_value = state_3.len() as u64;
-//             if third_index == steps_to_take {
-//                 third_value
-//             } else {
-//                 println!("{first_value} {second_value} {third_value}");
-//                 println!("{first_index} {second_index} {third_index}");
-//                 let a = (third_value as i64 - 2 * second_value as i64 - first_value as i64) / 2;
-//                 let b = second_value as i64 - first_value as i64 - a;
-//
-//                 let target = (steps_to_take as i64 - first_index as i64) / length as i64;
-//                 (a * target * target + b * target + first_value as i64).try_into().unwrap()
-//             }
-//         }
-//     }
-// }
-
-fn walk_on_infinite(grid: &Grid, steps_to_take: u64) -> u64 {
-    let length = grid.rows as u64;
-    let enlarged_grid = grid.expand_significantly();
+fn walk_on_infinite(garden: &Garden, steps_to_take: u64) -> u64 {
+    let length = garden.rocks.height() as u64;
 
     let first = steps_to_take % length;
     let second = first + length;
     let third  = second + length;
 
-    let first_state = take_step(&enlarged_grid, HashSet::from([grid.start.clone()]), 1, first);
+    let first_state = take_step(garden, true, HashSet::from([garden.start]), 1, first);
     let first_value = first_state.len() as u64;
-    let second_state = take_step(&enlarged_grid, first_state, first + 1, second);
+    let second_state = take_step(garden, true, first_state, first + 1, second);
     let second_value = second_state.len() as u64;
-    let third_value = take_step(&enlarged_grid, second_state, second + 1, third).len() as u64;
+    let third_value = take_step(garden, true, second_state, second + 1, third).len() as u64;
+    info!("sampled {} plots after {} steps, {} after {} and {} after {}", first_value, first, second_value, second, third_value, third);
 
     // equations are
     // a x^2 + b x + c = y(x)
@@ -109,170 +75,206 @@ fn walk_on_infinite(grid: &Grid, steps_to_take: u64) -> u64 {
     let a = (third_value as i64 - 2 * second_value as i64 + first_value as i64) / 2;
     let b = second_value as i64 - first_value as i64 - a;
 
-    let target: i64 = ((steps_to_take - first) / grid.rows as u64).try_into().unwrap();
+    let target: i64 = ((steps_to_take - first) / length).try_into().unwrap();
+    debug!("fitted {}x^2 + {}x + {}, and {} steps is x = {}", a, b, first_value, steps_to_take, target);
     (a * target * target + b * target + first_value as i64) as u64
 }
 
 #[tailcall]
-fn take_step(grid: &Grid, from: HashSet<Coord>, counter: u64, max: u64) -> HashSet<Coord> {
-    let next_steps: HashSet<Coord> = from.iter().flat_map(|x| grid.next(x)).collect::<HashSet<Coord>>();
+fn take_step(garden: &Garden, repeats: bool, from: HashSet<Point>, counter: u64, max: u64) -> HashSet<Point> {
+    cancel::check();
+    let next_steps: HashSet<Point> = from.iter().flat_map(|x| garden.next(x, repeats)).collect::<HashSet<Point>>();
     if counter < max {
-        take_step(grid, next_steps, counter + 1, max)
+        take_step(garden, repeats, next_steps, counter + 1, max)
     } else {
         next_steps
     }
 }
 
-fn walk_from_start(grid: &Grid, steps_to_take: u64) -> HashSet<Coord> {
-    take_step(grid, HashSet::from([grid.start.clone()]), 1, steps_to_take)
+fn walk_from_start(garden: &Garden, steps_to_take: u64) -> HashSet<Point> {
+    take_step(garden, false, HashSet::from([garden.start]), 1, steps_to_take)
 }
 
-fn parse_data(data: &str) -> Grid {
-    let mut start = Coord { row: 0, column: 0 };
-    let mut rocks = HashSet::<Coord>::new();
-
-    let mut rows: usize = 0;
-    let mut columns: usize = 0;
-    for (row, line) in data.lines().enumerate() {
-        for (column, character) in line.chars().enumerate() {
-            match character {
-                '.' => { /* do nothing */ }
-                '#' => { rocks.insert(Coord { row: row as isize, column: column as isize} ); }
-                'S' => { start = Coord { row: row as isize, column: column as isize }; }
-                a => { panic!("{} is not a valid character", a) }
-            }
-            columns = max(columns, column);
-        }
-        rows = max(rows, row);
-    }
-
-    Grid { start, rocks, columns: columns + 1, rows: rows + 1 }
+fn parse_data(data: &str) -> Result<Garden, ParseError> {
+    let tiles = Grid::parse(data, |c| matches!(c, '.' | '#' | 'S').then_some(c))?;
+    let (row, column) = tiles.find(|c| *c == 'S')
+        .ok_or_else(|| ParseError::new(1, 1, data.lines().next().unwrap_or_default(), "there's no S for the elf to start on"))?;
+    Ok(Garden { start: Point::from(Position::new(row, column)), rocks: tiles.map(|c| *c == '#') })
 }
 
+/// The garden, with where the rocks are and where the elf starts. In part two it repeats forever in every direction.
 #[derive(Debug, PartialEq)]
-struct Grid {
-    start: Coord,
-    rocks: HashSet<Coord>,
-    rows: usize,
-    columns: usize
+pub struct Garden {
+    start: Point,
+    rocks: Grid<bool>
 }
 
-impl Grid {
-    fn expand_significantly(&self) -> Grid {
-        let mut rock_on = HashSet::new();
-        for r in -5..=5isize {
-            for c in -5..=5isize {
-                rock_on.extend(self.rocks.iter().map(|x| Coord { row: x.row + r * self.rows as isize, column: x.column + c * self.columns as isize }));
-            }
+impl Garden {
+    // Whether there's a rock at the coordinate. If the garden repeats, that's in whichever copy of it the coordinate is
+    // in, otherwise there are no rocks outside it.
+    fn is_rock(&self, coord: &Point, repeats: bool) -> bool {
+        if !repeats {
+            return Position::try_from(*coord).ok().and_then(|position| self.rocks.get(position.into())) == Some(&true);
         }
+        self.rocks[(coord.row.rem_euclid(self.rocks.height() as isize) as usize, coord.column.rem_euclid(self.rocks.width() as isize) as usize)]
+    }
 
-        Grid {
-            start: self.start,
-            rocks: rock_on,
-            rows: self.rows,
-            columns: self.columns
-        }
+    fn next(&self, coord: &Point, repeats: bool) -> HashSet<Point> {
+        Direction::ALL.into_iter().map(|direction| coord.step(direction)).filter(|x| !self.is_rock(x, repeats)).collect()
     }
+}
 
-    fn next(&self, coord: &Coord) -> HashSet<Coord> {
-        HashSet::from([
-            Coord { row: coord.row + 1, column: coord.column },
-            Coord { row: coord.row - 1, column: coord.column },
-            Coord { row: coord.row, column: coord.column + 1 },
-            Coord { row: coord.row, column: coord.column - 1 },
-        ]).difference(&self.rocks).map(|x| x.clone()).collect()
+// The simple way to count the plots that can be reached in exactly `steps` steps on the garden that repeats forever:
+// find how far away every plot in range is, then count the ones the right distance away to be stood on at the end.
+fn count_reachable(garden: &Garden, steps: u64) -> u64 {
+    let mut seen: HashSet<Point> = HashSet::from([garden.start]);
+    let mut frontier: Vec<Point> = vec![garden.start];
+    let mut count = u64::from(steps.is_multiple_of(2));
+    for step in 1..=steps {
+        cancel::check();
+        let mut next: Vec<Point> = Vec::new();
+        for coord in &frontier {
+            for direction in Direction::ALL {
+                let neighbour = coord.step(direction);
+                if !garden.is_rock(&neighbour, true) && seen.insert(neighbour) {
+                    next.push(neighbour);
+                }
+            }
+        }
+        // Plots can be stepped off and back on again, so they can be reached at every step of the same parity
+        if step % 2 == steps % 2 {
+            count += next.len() as u64;
+        }
+        frontier = next;
     }
+    count
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
-struct Coord {
-    row: isize,
-    column: isize
+/// Part two at a few numbers of steps that end at the edge of a garden, like 26501365 does for the real one, with the
+/// quadratic and by counting every plot, for the differential tests.
+pub(crate) fn check_shortcut(input: &str) -> Comparison {
+    let garden = parse_data(input)?;
+    let length = garden.rocks.height() as u64;
+    let steps = [4, 5, 6].map(|gardens| gardens * length + length / 2);
+    let quadratic = steps.map(|steps| walk_on_infinite(&garden, steps));
+    let counted = steps.map(|steps| count_reachable(&garden, steps));
+    Ok(Some((quadratic.iter().join(", "), counted.iter().join(", "))))
+}
+
+/// Generates a `size` by `size` garden, made one bigger if `size` is even, with S in the middle. As in the real garden,
+/// the middle row and column and the edges have no rocks. Part two is only exact for gardens that are 131 wide, like
+/// the real one, as that is when 26501365 steps end right at the edge of a garden.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    random_garden(rng, size, true)
+}
+
+/// Generates a garden like [`generate`] does, but with rocks anywhere but on S, even in the middle row and column and
+/// along the edges. The plots reached no longer grow quadratically, so this is for checking that the differential
+/// tests notice.
+pub fn generate_with_rocks_anywhere(rng: &mut StdRng, size: usize) -> String {
+    random_garden(rng, size, false)
+}
+
+fn random_garden(rng: &mut StdRng, size: usize, clear_lines: bool) -> String {
+    let size = size.max(5) | 1;
+    let middle = size / 2;
+    (0..size).map(|row| (0..size).map(|column| {
+        if row == middle && column == middle {
+            'S'
+        } else if clear_lines && (row == middle || column == middle || row == 0 || column == 0 || row == size - 1 || column == size - 1) {
+            '.'
+        } else if rng.gen_bool(0.1) {
+            '#'
+        } else {
+            '.'
+        }
+    }).collect::<String>()).join("\n")
 }
 
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
     use std::ops::Deref;
-    use indoc::indoc;
     use structopt::lazy_static::lazy_static;
-    use crate::day21::{Coord, Grid, parse_data, walk_from_start};
-
-    const TEST_DATA: &str = indoc! {
-        "...........
-         .....###.#.
-         .###.##..#.
-         ..#.#...#..
-         ....#.#....
-         .##..S####.
-         .##..#...#.
-         .......##..
-         .##.#.####.
-         .##..##.##.
-         ..........."
-    };
+    use rstest::rstest;
+    use crate::common::grid::Grid;
+    use crate::common::point::Point;
+    use crate::day21::{count_reachable, Garden, parse_data, take_step, walk_from_start};
+    use crate::examples::DAY21 as TEST_DATA;
+
+    fn garden(height: usize, width: usize, start: Point, rocks: HashSet<Point>) -> Garden {
+        let mut grid = Grid::filled(height, width, false);
+        for rock in rocks {
+            grid[(rock.row as usize, rock.column as usize)] = true;
+        }
+        Garden { start, rocks: grid }
+    }
+
+    fn walk_repeating(steps: u64) -> HashSet<Point> {
+        take_step(PARSED_DATA.deref(), true, HashSet::from([PARSED_DATA.start]), 1, steps)
+    }
 
     lazy_static! {
-        static ref PARSED_DATA: Grid = Grid {
-            rows: 11,
-            columns: 11,
-            start: Coord { row: 5, column: 5 },
-            rocks: HashSet::from([
-                Coord { row: 1, column: 5 },
-                Coord { row: 1, column: 6 },
-                Coord { row: 1, column: 7 },
-                Coord { row: 1, column: 9 },
-                Coord { row: 2, column: 1 },
-                Coord { row: 2, column: 2 },
-                Coord { row: 2, column: 3 },
-                Coord { row: 2, column: 5 },
-                Coord { row: 2, column: 6 },
-                Coord { row: 2, column: 9 },
-                Coord { row: 3, column: 2 },
-                Coord { row: 3, column: 4 },
-                Coord { row: 3, column: 8 },
-                Coord { row: 4, column: 4 },
-                Coord { row: 4, column: 6 },
-                Coord { row: 5, column: 1 },
-                Coord { row: 5, column: 2 },
-                Coord { row: 5, column: 6 },
-                Coord { row: 5, column: 7 },
-                Coord { row: 5, column: 8 },
-                Coord { row: 5, column: 9 },
-                Coord { row: 6, column: 1 },
-                Coord { row: 6, column: 2 },
-                Coord { row: 6, column: 5 },
-                Coord { row: 6, column: 9 },
-                Coord { row: 7, column: 7 },
-                Coord { row: 7, column: 8 },
-                Coord { row: 8, column: 1 },
-                Coord { row: 8, column: 2 },
-                Coord { row: 8, column: 4 },
-                Coord { row: 8, column: 6 },
-                Coord { row: 8, column: 7 },
-                Coord { row: 8, column: 8 },
-                Coord { row: 8, column: 9 },
-                Coord { row: 9, column: 1 },
-                Coord { row: 9, column: 2 },
-                Coord { row: 9, column: 5 },
-                Coord { row: 9, column: 6 },
-                Coord { row: 9, column: 8 },
-                Coord { row: 9, column: 9 }
-            ])
-        };
+        static ref PARSED_DATA: Garden = garden(11, 11, Point { row: 5, column: 5 }, HashSet::from([
+                Point { row: 1, column: 5 },
+                Point { row: 1, column: 6 },
+                Point { row: 1, column: 7 },
+                Point { row: 1, column: 9 },
+                Point { row: 2, column: 1 },
+                Point { row: 2, column: 2 },
+                Point { row: 2, column: 3 },
+                Point { row: 2, column: 5 },
+                Point { row: 2, column: 6 },
+                Point { row: 2, column: 9 },
+                Point { row: 3, column: 2 },
+                Point { row: 3, column: 4 },
+                Point { row: 3, column: 8 },
+                Point { row: 4, column: 4 },
+                Point { row: 4, column: 6 },
+                Point { row: 5, column: 1 },
+                Point { row: 5, column: 2 },
+                Point { row: 5, column: 6 },
+                Point { row: 5, column: 7 },
+                Point { row: 5, column: 8 },
+                Point { row: 5, column: 9 },
+                Point { row: 6, column: 1 },
+                Point { row: 6, column: 2 },
+                Point { row: 6, column: 5 },
+                Point { row: 6, column: 9 },
+                Point { row: 7, column: 7 },
+                Point { row: 7, column: 8 },
+                Point { row: 8, column: 1 },
+                Point { row: 8, column: 2 },
+                Point { row: 8, column: 4 },
+                Point { row: 8, column: 6 },
+                Point { row: 8, column: 7 },
+                Point { row: 8, column: 8 },
+                Point { row: 8, column: 9 },
+                Point { row: 9, column: 1 },
+                Point { row: 9, column: 2 },
+                Point { row: 9, column: 5 },
+                Point { row: 9, column: 6 },
+                Point { row: 9, column: 8 },
+                Point { row: 9, column: 9 }
+            ]));
     }
 
     #[test]
     fn test_parse_data() {
-        assert_eq!(parse_data(TEST_DATA), *PARSED_DATA.deref())
+        assert_eq!(parse_data(TEST_DATA).as_ref(), Ok(PARSED_DATA.deref()))
+    }
+
+    #[test]
+    fn test_parse_data_without_start() {
+        assert_eq!(parse_data("...\n.#.").unwrap_err().message, "there's no S for the elf to start on");
     }
 
     #[test]
     fn test_walk_from_start_1() {
         assert_eq!(walk_from_start(PARSED_DATA.deref(), 1), HashSet::from(
             [
-                Coord { row: 5, column: 4 },
-                Coord { row: 4, column: 5 },
+                Point { row: 5, column: 4 },
+                Point { row: 4, column: 5 },
             ]
         ))
     }
@@ -281,10 +283,10 @@ mod test {
     fn test_walk_from_start_2() {
         assert_eq!(walk_from_start(PARSED_DATA.deref(), 2), HashSet::from(
             [
-                Coord { row: 3, column: 5 },
-                Coord { row: 5, column: 3 },
-                Coord { row: 5, column: 5 },
-                Coord { row: 6, column: 4 },
+                Point { row: 3, column: 5 },
+                Point { row: 5, column: 3 },
+                Point { row: 5, column: 5 },
+                Point { row: 6, column: 4 },
             ]
         ))
     }
@@ -293,12 +295,12 @@ mod test {
     fn test_walk_from_start_3() {
         assert_eq!(walk_from_start(PARSED_DATA.deref(), 3), HashSet::from(
             [
-                Coord { row: 3, column: 6 },
-                Coord { row: 4, column: 3 },
-                Coord { row: 4, column: 5 },
-                Coord { row: 5, column: 4 },
-                Coord { row: 6, column: 3 },
-                Coord { row: 7, column: 4 },
+                Point { row: 3, column: 6 },
+                Point { row: 4, column: 3 },
+                Point { row: 4, column: 5 },
+                Point { row: 5, column: 4 },
+                Point { row: 6, column: 3 },
+                Point { row: 7, column: 4 },
             ]
         ))
     }
@@ -307,22 +309,22 @@ mod test {
     fn test_walk_from_start_6() {
         assert_eq!(walk_from_start(PARSED_DATA.deref(), 6), HashSet::from(
             [
-                Coord { row: 2, column: 8 },
-                Coord { row: 3, column: 1 },
-                Coord { row: 3, column: 3 },
-                Coord { row: 3, column: 5 },
-                Coord { row: 3, column: 7 },
-                Coord { row: 4, column: 0 },
-                Coord { row: 4, column: 2 },
-                Coord { row: 4, column: 8 },
-                Coord { row: 5, column: 3 },
-                Coord { row: 5, column: 5 },
-                Coord { row: 6, column: 4 },
-                Coord { row: 6, column: 6 },
-                Coord { row: 7, column: 1 },
-                Coord { row: 7, column: 3 },
-                Coord { row: 7, column: 5 },
-                Coord { row: 9, column: 3 },
+                Point { row: 2, column: 8 },
+                Point { row: 3, column: 1 },
+                Point { row: 3, column: 3 },
+                Point { row: 3, column: 5 },
+                Point { row: 3, column: 7 },
+                Point { row: 4, column: 0 },
+                Point { row: 4, column: 2 },
+                Point { row: 4, column: 8 },
+                Point { row: 5, column: 3 },
+                Point { row: 5, column: 5 },
+                Point { row: 6, column: 4 },
+                Point { row: 6, column: 6 },
+                Point { row: 7, column: 1 },
+                Point { row: 7, column: 3 },
+                Point { row: 7, column: 5 },
+                Point { row: 9, column: 3 },
             ]
         ))
     }
@@ -333,19 +335,28 @@ mod test {
     }
 
     #[test]
-    fn test_check_50_steps_expanded() {
-        assert_eq!(walk_from_start(&PARSED_DATA.deref().expand_significantly().expand_significantly(), 50).len(), 1594)
+    fn test_check_50_steps_repeating() {
+        assert_eq!(walk_repeating(50).len(), 1594)
     }
 
     #[test]
-    fn test_check_17_steps_expanded() {
-        assert_eq!(walk_from_start(&PARSED_DATA.deref().expand_significantly().expand_significantly(), 17).len(), 145);
-        assert_eq!(walk_from_start(&PARSED_DATA.deref().expand_significantly().expand_significantly(), 28).len(), 460);
-        assert_eq!(walk_from_start(&PARSED_DATA.deref().expand_significantly().expand_significantly(), 39).len(), 944);
-        assert_eq!(walk_from_start(&PARSED_DATA.deref().expand_significantly().expand_significantly(), 50).len(), 1594);
+    fn test_check_17_steps_repeating() {
+        assert_eq!(walk_repeating(17).len(), 145);
+        assert_eq!(walk_repeating(28).len(), 460);
+        assert_eq!(walk_repeating(39).len(), 944);
+        assert_eq!(walk_repeating(50).len(), 1594);
     }
 
 
+    #[rstest]
+    #[case(6, 16)]
+    #[case(10, 50)]
+    #[case(50, 1594)]
+    #[case(100, 6536)]
+    fn test_count_reachable(#[case] steps: u64, #[case] expected: u64) {
+        assert_eq!(count_reachable(PARSED_DATA.deref(), steps), expected);
+    }
+
     // #[test]
     // fn test_day21b() {
     //     assert_eq!(walk_on_infinite(PARSED_DATA.deref(), 6), 16);
@@ -1,20 +1,49 @@
+//! Day 22: Sand Slabs
+//!
+//! Lets the bricks fall into place, then works out which could be safely disintegrated and how many would fall if
+//! each one went.
+
 use std::collections::{BTreeMap, HashSet};
 use itertools::Itertools;
-use crate::common::load_from;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use crate::cancel;
+use crate::common::ParseError;
+use crate::common::parse::{Line, lines};
+use crate::common::interval::{Interval, Region};
+use crate::solution::Solution;
 
 type Coord = (u32, u32, u32);
 
-pub fn run_day() {
-    let data = load_from("day22.txt");
-    let gravity_bricks = prepare_bricks(data.as_str());
-    println!("Part 1: {}", run_day22a(&gravity_bricks));
-    println!("Part 2: {}", run_day22b(&gravity_bricks));
+/// Counts the bricks that could be safely disintegrated, then sums the bricks that would fall for each one. Parsing
+/// drops the bricks into place.
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u8 = 22;
+
+    type Parsed = Vec<Brick>;
+    type PartOne = u32;
+    type PartTwo = u64;
+
+    fn parse(&self, input: &str) -> Result<Vec<Brick>, ParseError> {
+        prepare_bricks(input)
+    }
+
+    fn part_one(&self, bricks: &Vec<Brick>) -> u32 {
+        run_day22a(bricks)
+    }
+
+    fn part_two(&self, bricks: &Vec<Brick>) -> u64 {
+        run_day22b(bricks)
+    }
 }
 
-fn prepare_bricks(data: &str) -> Vec<Brick> {
-    let mut bricks = parse_bricks(&data);
+fn prepare_bricks(data: &str) -> Result<Vec<Brick>, ParseError> {
+    let mut bricks = parse_bricks(data)?;
     sort_bricks_in_z(&mut bricks);
-    apply_gravity(bricks).0
+    Ok(apply_gravity(bricks).0)
 }
 
 fn run_day22a(bricks: &Vec<Brick>) -> u32 {
@@ -52,6 +81,7 @@ fn run_day22b(bricks: &Vec<Brick>) -> u64 {
     let mut changed = 0u64;
     // let (min_z_slice, max_z_slice) = gather_slices(bricks);
     for brick in bricks {
+        cancel::check();
         let vec = bricks.iter().filter_map(|x| {
             if x == brick {
                 None
@@ -74,26 +104,24 @@ fn gather_slices<'a>(bricks: &'a Vec<Brick>) -> (BTreeMap<u32, Vec<&'a Brick>>,
     (min_z_slice, max_z_slice)
 }
 
-fn parse_coord(coord: &str) -> Coord {
-    let mut c = coord.split(",");
-    (
-        c.next().unwrap().parse().unwrap(),
-        c.next().unwrap().parse().unwrap(),
-        c.next().unwrap().parse().unwrap()
-    )
+fn parse_coord<'a>(line: Line<'a>, coord: &'a str) -> Result<Coord, ParseError> {
+    let numbers = line.list(coord, ",", |number| line.number::<u32>(number))?;
+    let [x, y, z] = numbers[..] else {
+        return Err(line.error(coord, "expected three numbers, like 1,0,1"));
+    };
+    Ok((x, y, z))
 }
 
-fn parse_bricks(data: &str) -> Vec<Brick> {
-    data.lines()
+fn parse_bricks(data: &str) -> Result<Vec<Brick>, ParseError> {
+    lines(data)
         .map(|line| {
-            line.split_once("~").map(|(first, second)| {
-                let a = parse_coord(first);
-                let b = parse_coord(second);
-                Brick {
-                    min: (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
-                    max: (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2))
-                }
-            }).unwrap()
+            let (first, second) = line.split_once(line.text, "~", "expected the two ends of a brick, like 1,0,1~1,2,1")?;
+            let a = parse_coord(line, first)?;
+            let b = parse_coord(line, second)?;
+            Ok(Brick {
+                min: (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+                max: (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2))
+            })
         })
         .collect()
 }
@@ -123,14 +151,16 @@ fn apply_gravity(bricks: Vec<Brick>) -> (Vec<Brick>, u64) {
     (result, changed)
 }
 
+/// A brick and the cubes at either end of it.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Hash)]
-struct Brick {
+pub struct Brick {
     min: Coord,
     max: Coord
 }
 
 impl Brick {
-    pub fn drop_to(&self, new_z: u32) -> Brick {
+    // The same brick moved straight down or up, so that its bottom is at new_z
+    fn drop_to(&self, new_z: u32) -> Brick {
         Brick {
             min: (self.min.0, self.min.1, new_z),
             max: (self.max.0, self.max.1, self.max.2 - self.min.2 + new_z)
@@ -153,9 +183,12 @@ impl Brick {
     }
 
     fn xy_overlap(&self, other: &Brick) -> bool {
-        // x then y
-        self.max.0 >= other.min.0 && other.max.0 >= self.min.0 &&
-            self.max.1 >= other.min.1 && other.max.1 >= self.min.1
+        self.footprint().overlaps(&other.footprint())
+    }
+
+    // The x then y that the brick covers, seen from above
+    fn footprint(&self) -> Region<u32, 2> {
+        Region::new([Interval::inclusive(self.min.0, self.max.0), Interval::inclusive(self.min.1, self.max.1)])
     }
 
     #[allow(dead_code)]
@@ -172,27 +205,42 @@ impl Brick {
     }
 }
 
+/// Generates `size` bricks of up to four cubes in a 10 by 10 area. Each brick is put somewhere above the bricks that are
+/// already there, so none of them overlap.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    let mut heights = [[0usize; 10]; 10];
+    let mut bricks: Vec<String> = (0..size.max(1)).map(|_| {
+        let length = rng.gen_range(1..=4);
+        let (dx, dy, dz) = match rng.gen_range(0..3) {
+            0 => (length - 1, 0, 0),
+            1 => (0, length - 1, 0),
+            _ => (0, 0, length - 1)
+        };
+        let (x, y) = (rng.gen_range(0..10 - dx), rng.gen_range(0..10 - dy));
+        let cells: Vec<(usize, usize)> = (x..=x + dx).flat_map(|i| (y..=y + dy).map(move |j| (i, j))).collect();
+        let z = cells.iter().map(|&(i, j)| heights[i][j]).max().unwrap() + 1 + rng.gen_range(0..=3);
+        for &(i, j) in &cells {
+            heights[i][j] = z + dz;
+        }
+        format!("{},{},{}~{},{},{}", x, y, z, x + dx, y + dy, z + dz)
+    }).collect();
+    bricks.shuffle(rng);
+    bricks.join("\n")
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::BTreeMap;
     use std::ops::Deref;
-    use indoc::indoc;
     use itertools::Itertools;
     use proptest::bool::{ANY as BOOL_ANY};
     use proptest::proptest;
     use rstest::rstest;
     use structopt::lazy_static::lazy_static;
+    use crate::common::ParseError;
+    use crate::common::parse::Line;
     use crate::day22::{apply_gravity, Brick, Coord, gather_slices, parse_bricks, parse_coord, prepare_bricks, run_day22a, run_day22b};
-
-    const TEST_DATA: &str = indoc! {
-        "1,0,1~1,2,1
-         0,0,2~2,0,2
-         0,2,3~2,2,3
-         0,0,4~0,2,4
-         2,0,5~2,2,5
-         0,1,6~2,1,6
-         1,1,8~1,1,9"
-    };
+    use crate::examples::DAY22 as TEST_DATA;
 
     lazy_static! {
         static ref PARSED_DATA: Vec<Brick> = vec![
@@ -220,14 +268,23 @@ mod test {
 
     #[test]
     fn test_parsing() {
-        assert_eq!(parse_bricks(TEST_DATA), *PARSED_DATA.deref())
+        assert_eq!(parse_bricks(TEST_DATA).unwrap(), *PARSED_DATA.deref())
+    }
+
+    #[rstest]
+    #[case("1,0,1~1,2,1\n0,0,2 2,0,2", ParseError::new(2, 1, "0,0,2 2,0,2", "expected the two ends of a brick, like 1,0,1~1,2,1"))]
+    #[case("1,0,1~1,2", ParseError::new(1, 7, "1,0,1~1,2", "expected three numbers, like 1,0,1"))]
+    #[case("1,0,1~1,x,1", ParseError::new(1, 9, "1,0,1~1,x,1", "expected a number, found \"x\""))]
+    #[case("1,-1,1~1,2,1", ParseError::new(1, 3, "1,-1,1~1,2,1", "expected a number, found \"-1\""))]
+    fn test_parse_bricks_errors(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(parse_bricks(input), Err(expected));
     }
 
     proptest! {
         #[test]
         fn test_parse_coords(x in 0..100u32, y in 0..100u32, z in 0..100u32) {
             let string_to_parse = format!("{x},{y},{z}");
-            assert_eq!(parse_coord(string_to_parse.as_str()), (x, y, z))
+            assert_eq!(parse_coord(Line::new(0, string_to_parse.as_str()), string_to_parse.as_str()), Ok((x, y, z)))
         }
     }
 
@@ -263,7 +320,7 @@ mod test {
 
     #[test]
     fn integration_test_day_22a() {
-        let g_bricks = prepare_bricks(TEST_DATA);
+        let g_bricks = prepare_bricks(TEST_DATA).unwrap();
         assert_eq!(run_day22a(&g_bricks), 5);
     }
 
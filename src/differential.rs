@@ -0,0 +1,208 @@
+//! Checks the shortcuts some days take against simple, slow ways of working out the same thing, on lots of small
+//! generated inputs.
+//!
+//! The shortcuts rely on things that are true of the real inputs, such as the garden in day 21 having no rocks in its
+//! middle row and column. The generators make inputs with the same properties, so a disagreement means that either a
+//! shortcut relies on something we didn't know about, or a generator is missing something the real inputs have.
+//!
+//! Some shortcuts also have a generator that breaks those properties on purpose, which shows whether a shortcut really
+//! needs them, and that the check would notice if it did.
+
+use std::ops::Range;
+use std::panic;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::{day05, day08, day09, day10, day14, day20, day21};
+use crate::common::ParseError;
+use crate::generate::Generator;
+use crate::runner::panic_message;
+
+/// The answer with the shortcut and then the simple way, None if the simple way would take too long, or why the input
+/// couldn't be parsed.
+pub type Comparison = Result<Option<(String, String)>, ParseError>;
+
+/// A shortcut one of the days takes, and how to check it.
+pub struct Shortcut {
+    /// The day that takes it
+    pub day: u8,
+    /// What the shortcut is
+    pub name: &'static str,
+    /// Makes the inputs to check it on, which need to be small enough for the simple way to finish
+    pub generate: Generator,
+    /// Makes inputs without the properties the real inputs have, if there's a way to make them that the simple way can
+    /// cope with
+    pub break_assumptions: Option<Generator>,
+    /// The size to ask the generators for
+    pub size: usize,
+    /// Works out the answer with the shortcut and then the simple way
+    pub compare: fn(&str) -> Comparison
+}
+
+impl Shortcut {
+    /// The generator to use, or None if the assumptions are to be broken and there's no way to break them.
+    pub fn generator(&self, break_assumptions: bool) -> Option<Generator> {
+        if break_assumptions { self.break_assumptions } else { Some(self.generate) }
+    }
+}
+
+/// Every shortcut that can be checked, in day order.
+// Add new shortcuts here
+pub fn shortcuts() -> Vec<Shortcut> {
+    vec![
+        Shortcut { day: 5, name: "following ranges through the maps", generate: day05::generate, break_assumptions: None, size: 20, compare: day05::check_shortcut },
+        Shortcut { day: 8, name: "lining up the ghosts' loops", generate: day08::generate, break_assumptions: Some(day08::generate_loose), size: 300, compare: day08::check_shortcut },
+        Shortcut { day: 9, name: "Function extrapolation", generate: day09::generate, break_assumptions: None, size: 20, compare: day09::check_shortcut },
+        Shortcut { day: 10, name: "shoelace formula and Pick's theorem", generate: day10::generate, break_assumptions: None, size: 12, compare: day10::check_shortcut },
+        Shortcut { day: 14, name: "skipping repeated spin cycles", generate: day14::generate, break_assumptions: None, size: 8, compare: day14::check_shortcut },
+        Shortcut { day: 20, name: "LCM of the loops that feed rx", generate: day20::generate_short_counters, break_assumptions: None, size: 3, compare: day20::check_shortcut },
+        Shortcut { day: 21, name: "quadratic extrapolation", generate: day21::generate, break_assumptions: Some(day21::generate_with_rocks_anywhere), size: 11, compare: day21::check_shortcut },
+    ]
+}
+
+/// How the shortcut did on one input.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Outcome {
+    /// Both ways got the same answer
+    Agreed,
+    /// The simple way would have taken too long
+    Skipped,
+    /// The two ways got different answers
+    Disagreed {
+        /// The answer with the shortcut
+        shortcut: String,
+        /// The answer the simple way
+        reference: String
+    },
+    /// One of the ways panicked, with this message
+    Panicked(String),
+    /// The generator made an input that doesn't parse
+    Invalid(ParseError)
+}
+
+/// An input the shortcut didn't agree on.
+#[derive(PartialEq, Debug)]
+pub struct Failure {
+    /// The seed the input was made from
+    pub seed: u64,
+    /// The input itself
+    pub input: String,
+    /// What went wrong
+    pub outcome: Outcome
+}
+
+/// How the shortcut did across every seed.
+#[derive(PartialEq, Debug, Default)]
+pub struct Summary {
+    /// How many inputs both ways agreed on
+    pub agreed: usize,
+    /// How many inputs the simple way would have taken too long on
+    pub skipped: usize,
+    /// Every input that went wrong, in seed order
+    pub failures: Vec<Failure>
+}
+
+/// Makes the input for `seed` with `generate` and compares the two ways of working out its answer. Panics are caught,
+/// as a shortcut that panics on an input is as wrong as one that gets a different answer.
+pub fn check(shortcut: &Shortcut, generate: Generator, seed: u64) -> (String, Outcome) {
+    let input = generate(&mut StdRng::seed_from_u64(seed), shortcut.size);
+    let outcome = match panic::catch_unwind(|| (shortcut.compare)(input.as_str())) {
+        Ok(Ok(Some((answer, reference)))) if answer == reference => Outcome::Agreed,
+        Ok(Ok(Some((answer, reference)))) => Outcome::Disagreed { shortcut: answer, reference },
+        Ok(Ok(None)) => Outcome::Skipped,
+        Ok(Err(e)) => Outcome::Invalid(e),
+        Err(payload) => Outcome::Panicked(panic_message(payload.as_ref()))
+    };
+    (input, outcome)
+}
+
+/// Checks the shortcut on the input `generate` makes for each seed.
+pub fn check_seeds(shortcut: &Shortcut, generate: Generator, seeds: Range<u64>) -> Summary {
+    let mut summary = Summary::default();
+    for seed in seeds {
+        match check(shortcut, generate, seed) {
+            (_, Outcome::Agreed) => summary.agreed += 1,
+            (_, Outcome::Skipped) => summary.skipped += 1,
+            (input, outcome) => summary.failures.push(Failure { seed, input, outcome })
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rstest::rstest;
+    use crate::common::ParseError;
+    use crate::differential::{check_seeds, Comparison, Failure, Outcome, Shortcut, shortcuts};
+
+    fn constant(_: &mut StdRng, size: usize) -> String {
+        size.to_string()
+    }
+
+    fn agrees(_: &str) -> Comparison {
+        Ok(Some((String::from("1"), String::from("1"))))
+    }
+
+    fn too_slow(_: &str) -> Comparison {
+        Ok(None)
+    }
+
+    fn disagrees(input: &str) -> Comparison {
+        Ok(Some((String::from(input), String::from("7"))))
+    }
+
+    fn panics(_: &str) -> Comparison {
+        panic!("no loop")
+    }
+
+    fn invalid(input: &str) -> Comparison {
+        Err(ParseError::new(1, 1, input, "expected a loop"))
+    }
+
+    #[test]
+    fn test_every_shortcut_agrees() {
+        for shortcut in shortcuts() {
+            let summary = check_seeds(&shortcut, shortcut.generate, 0..3);
+            assert_eq!(summary.failures, Vec::new(), "day {}, {}", shortcut.day, shortcut.name);
+            assert_eq!(summary.agreed, 3, "day {}, {}", shortcut.day, shortcut.name);
+        }
+    }
+
+    #[test]
+    fn test_breaking_assumptions() {
+        // Day 21's quadratic needs the clear middle row and column, so it's caught out without them
+        let shortcuts = shortcuts();
+        let find = |day: u8| shortcuts.iter().find(|shortcut| shortcut.day == day).unwrap();
+        let summary = check_seeds(find(21), find(21).generator(true).unwrap(), 0..5);
+        assert!(summary.failures.iter().all(|failure| matches!(failure.outcome, Outcome::Disagreed { .. })));
+        assert!(!summary.failures.is_empty());
+        assert!(find(9).generator(true).is_none());
+    }
+
+    #[rstest]
+    #[case(agrees, 2, 0, vec![])]
+    #[case(too_slow, 0, 2, vec![])]
+    #[case(disagrees, 0, 0, vec![
+        Outcome::Disagreed { shortcut: String::from("5"), reference: String::from("7") },
+        Outcome::Disagreed { shortcut: String::from("5"), reference: String::from("7") }
+    ])]
+    #[case(panics, 0, 0, vec![Outcome::Panicked(String::from("no loop")), Outcome::Panicked(String::from("no loop"))])]
+    #[case(invalid, 0, 0, vec![
+        Outcome::Invalid(ParseError::new(1, 1, "5", "expected a loop")),
+        Outcome::Invalid(ParseError::new(1, 1, "5", "expected a loop"))
+    ])]
+    fn test_check_seeds(
+        #[case] compare: fn(&str) -> Comparison,
+        #[case] agreed: usize,
+        #[case] skipped: usize,
+        #[case] failures: Vec<Outcome>
+    ) {
+        let shortcut = Shortcut { day: 1, name: "test", generate: constant, break_assumptions: None, size: 5, compare };
+        let summary = check_seeds(&shortcut, constant, 3..5);
+        assert_eq!((summary.agreed, summary.skipped), (agreed, skipped));
+        let expected: Vec<Failure> = failures.into_iter().zip(3..)
+            .map(|(outcome, seed)| Failure { seed, input: String::from("5"), outcome })
+            .collect();
+        assert_eq!(summary.failures, expected);
+    }
+}
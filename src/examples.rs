@@ -0,0 +1,554 @@
+//! The sample inputs from the puzzles, which are used by --example and by each day's tests.
+
+use std::collections::BTreeMap;
+use std::io;
+use indoc::indoc;
+use crate::check::Answers;
+use crate::common::InputSource;
+use crate::report;
+use crate::report::{Format, PartResult, Status};
+use crate::runner::{DayError, error_results, run_day, Settings};
+use crate::solution::{Part, Solver};
+
+/// The sample inputs from each day's puzzle, along with the answers our solutions give for them. These are mostly the
+/// answers in the puzzle text, but a few days (11 and 21, say) use different settings in the puzzle's examples, and
+/// some parts aren't given an answer at all. Where a sample can't be used for a part, like day 8's part two sample
+/// in part one, the answer is None and that part isn't run.
+#[derive(PartialEq, Debug)]
+pub struct Example {
+    /// What --example calls it: the day, and which of the day's samples it is if there's more than one.
+    pub name: &'static str,
+    /// The day the sample is from.
+    pub day: u8,
+    /// The sample input.
+    pub input: &'static str,
+    /// The answer to part one, or None if part one isn't run on it.
+    pub part_one: Option<&'static str>,
+    /// The answer to part two, or None if part two isn't run on it.
+    pub part_two: Option<&'static str>
+}
+
+/// Every example, in day order.
+// Add new examples here
+pub const EXAMPLES: &[Example] = &[
+    Example { name: "day01-1", day: 1, input: DAY01_1, part_one: Some("142"), part_two: Some("142") },
+    Example { name: "day01-2", day: 1, input: DAY01_2, part_one: None, part_two: Some("281") },
+    Example { name: "day02", day: 2, input: DAY02, part_one: Some("8"), part_two: Some("2286") },
+    Example { name: "day03", day: 3, input: DAY03, part_one: Some("4361"), part_two: Some("467835") },
+    Example { name: "day04", day: 4, input: DAY04, part_one: Some("13"), part_two: Some("30") },
+    Example { name: "day05", day: 5, input: DAY05, part_one: Some("35"), part_two: Some("46") },
+    Example { name: "day06", day: 6, input: DAY06, part_one: Some("288"), part_two: Some("71503") },
+    Example { name: "day07", day: 7, input: DAY07, part_one: Some("6440"), part_two: Some("5905") },
+    Example { name: "day08-1", day: 8, input: DAY08_1, part_one: Some("2"), part_two: Some("2") },
+    Example { name: "day08-2", day: 8, input: DAY08_2, part_one: Some("6"), part_two: Some("6") },
+    Example { name: "day08-3", day: 8, input: DAY08_3, part_one: None, part_two: Some("6") },
+    Example { name: "day09", day: 9, input: DAY09, part_one: Some("114"), part_two: Some("2") },
+    Example { name: "day10-1", day: 10, input: DAY10_1, part_one: Some("4"), part_two: Some("1") },
+    Example { name: "day10-2", day: 10, input: DAY10_2, part_one: Some("8"), part_two: Some("1") },
+    Example { name: "day10-3", day: 10, input: DAY10_3, part_one: Some("23"), part_two: Some("4") },
+    Example { name: "day10-4", day: 10, input: DAY10_4, part_one: Some("70"), part_two: Some("8") },
+    Example { name: "day10-5", day: 10, input: DAY10_5, part_one: Some("80"), part_two: Some("10") },
+    Example { name: "day11", day: 11, input: DAY11, part_one: Some("374"), part_two: Some("82000210") },
+    Example { name: "day12", day: 12, input: DAY12, part_one: Some("21"), part_two: Some("525152") },
+    Example { name: "day13", day: 13, input: DAY13, part_one: Some("405"), part_two: Some("400") },
+    Example { name: "day14", day: 14, input: DAY14, part_one: Some("136"), part_two: Some("64") },
+    Example { name: "day15", day: 15, input: DAY15, part_one: Some("1320"), part_two: Some("145") },
+    Example { name: "day16", day: 16, input: DAY16, part_one: Some("46"), part_two: Some("51") },
+    Example { name: "day17-1", day: 17, input: DAY17_1, part_one: Some("102"), part_two: Some("94") },
+    Example { name: "day17-2", day: 17, input: DAY17_2, part_one: Some("59"), part_two: Some("47") },
+    Example { name: "day18", day: 18, input: DAY18, part_one: Some("62"), part_two: Some("952408144115") },
+    Example { name: "day19", day: 19, input: DAY19, part_one: Some("19114"), part_two: Some("167409079868000") },
+    Example { name: "day20-1", day: 20, input: DAY20_1, part_one: Some("32000000"), part_two: None },
+    Example { name: "day20-2", day: 20, input: DAY20_2, part_one: Some("11687500"), part_two: None },
+    Example { name: "day21", day: 21, input: DAY21, part_one: Some("4056"), part_two: None },
+    Example { name: "day22", day: 22, input: DAY22, part_one: Some("5"), part_two: Some("7") },
+];
+
+impl Example {
+    fn answer(&self, part: Part) -> Option<&'static str> {
+        match part {
+            Part::One => self.part_one,
+            Part::Two => self.part_two
+        }
+    }
+}
+
+/// The examples for the given days, or just the one that's asked for by name.
+pub fn select(days: &[u8], name: Option<&str>) -> Result<Vec<&'static Example>, String> {
+    match name {
+        Some(name) => EXAMPLES.iter()
+            .find(|example| example.name == name)
+            .map(|example| vec![example])
+            .ok_or_else(|| format!("There is no example called {}", name)),
+        None => Ok(EXAMPLES.iter().filter(|example| days.contains(&example.day)).collect())
+    }
+}
+
+/// Runs each example and checks it against its answers. Returns false if any of them failed.
+pub fn run(examples: &[&Example], days: &BTreeMap<u8, &'static dyn Solver>, parts: &[Part], output: Format) -> bool {
+    let mut passed = true;
+    let mut results: Vec<PartResult> = Vec::new();
+    for example in examples {
+        let example_parts: Vec<Part> = parts.iter().copied().filter(|part| example.answer(*part).is_some()).collect();
+        if example_parts.is_empty() {
+            continue;
+        }
+        let mut answers = Answers::default();
+        for part in &example_parts {
+            answers.add(example.day, *part, example.answer(*part).unwrap());
+        }
+        let source = InputSource::Text(example.input.to_string());
+        let settings = Settings { source: &source, parts: &example_parts, answers: Some(&answers), memory: false, timeout: None };
+
+        let mut example_results = match run_day(*days.get(&example.day).unwrap(), &settings) {
+            Ok(example_results) => example_results,
+            Err(error) => {
+                match &error {
+                    DayError::Parse(e) => eprintln!("Example {}: {}", example.name, e),
+                    e => eprintln!("Example {}: {}", example.name, e)
+                }
+                error_results(example.day, &example_parts, &error)
+            }
+        };
+        passed &= example_results.iter().all(|r| r.status == Status::Pass);
+        if output.is_streamed() {
+            println!("Example {}", example.name);
+            report::write(output, &example_results, &mut io::stdout()).unwrap();
+        } else {
+            results.append(&mut example_results);
+        }
+    }
+
+    if !output.is_streamed() {
+        report::write(output, &results, &mut io::stdout()).unwrap();
+    }
+    passed
+}
+
+/// The first sample input from day 1's puzzle.
+pub const DAY01_1: &str = indoc! {
+    "1abc2
+     pqr3stu8vwx
+     a1b2c3d4e5f
+     treb7uchet"
+};
+
+/// The second sample input from day 1's puzzle.
+pub const DAY01_2: &str = indoc! {
+    "two1nine
+     eightwothree
+     abcone2threexyz
+     xtwone3four
+     4nineeightseven2
+     zoneight234
+     7pqrstsixteen"
+};
+
+/// The sample input from day 2's puzzle.
+pub const DAY02: &str = indoc! {
+    "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+     Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+     Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+     Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+     Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"
+};
+
+/// The sample input from day 3's puzzle.
+pub const DAY03: &str = indoc! {
+    "467..114..
+     ...*......
+     ..35..633.
+     ......#...
+     617*......
+     .....+.58.
+     ..592.....
+     ......755.
+     ...$.*....
+     .664.598.."
+};
+
+/// The sample input from day 4's puzzle.
+pub const DAY04: &str = indoc! {
+    "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+     Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+     Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+     Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+     Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+     Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
+};
+
+/// The sample input from day 5's puzzle.
+pub const DAY05: &str = indoc! {
+    "seeds: 79 14 55 13
+
+     seed-to-soil map:
+     50 98 2
+     52 50 48
+
+     soil-to-fertilizer map:
+     0 15 37
+     37 52 2
+     39 0 15
+
+     fertilizer-to-water map:
+     49 53 8
+     0 11 42
+     42 0 7
+     57 7 4
+
+     water-to-light map:
+     88 18 7
+     18 25 70
+
+     light-to-temperature map:
+     45 77 23
+     81 45 19
+     68 64 13
+
+     temperature-to-humidity map:
+     0 69 1
+     1 0 69
+
+     humidity-to-location map:
+     60 56 37
+     56 93 4
+"
+};
+
+/// The sample input from day 6's puzzle.
+pub const DAY06: &str = indoc! {
+    "Time:      7  15   30
+     Distance:  9  40  200"
+};
+
+/// The sample input from day 7's puzzle.
+pub const DAY07: &str = indoc! {
+    "32T3K 765
+     T55J5 684
+     KK677 28
+     KTJJT 220
+     QQQJA 483"
+};
+
+/// The first sample input from day 8's puzzle.
+pub const DAY08_1: &str = indoc! {
+    "RL
+
+     AAA = (BBB, CCC)
+     BBB = (DDD, EEE)
+     CCC = (ZZZ, GGG)
+     DDD = (DDD, DDD)
+     EEE = (EEE, EEE)
+     GGG = (GGG, GGG)
+     ZZZ = (ZZZ, ZZZ)"
+};
+
+/// The second sample input from day 8's puzzle.
+pub const DAY08_2: &str = indoc! {
+    "LLR
+
+     AAA = (BBB, BBB)
+     BBB = (AAA, ZZZ)
+     ZZZ = (ZZZ, ZZZ)"
+};
+
+/// The third sample input from day 8's puzzle.
+pub const DAY08_3: &str = indoc! {
+    "LR
+
+     11A = (11B, XXX)
+     11B = (XXX, 11Z)
+     11Z = (11B, XXX)
+     22A = (22B, XXX)
+     22B = (22C, 22C)
+     22C = (22Z, 22Z)
+     22Z = (22B, 22B)
+     XXX = (XXX, XXX)"
+};
+
+/// The sample input from day 9's puzzle.
+pub const DAY09: &str = indoc! {
+    "0 3 6 9 12 15
+     1 3 6 10 15 21
+     10 13 16 21 30 45"
+};
+
+/// The first sample input from day 10's puzzle.
+pub const DAY10_1: &str = indoc! {
+    ".....
+     .S-7.
+     .|.|.
+     .L-J.
+     ....."
+};
+
+/// The second sample input from day 10's puzzle.
+pub const DAY10_2: &str = indoc! {
+    "7-F7-
+     .FJ|7
+     SJLL7
+     |F--J
+     LJ.LJ"
+};
+
+/// The third sample input from day 10's puzzle.
+pub const DAY10_3: &str = indoc! {
+    "...........
+     .S-------7.
+     .|F-----7|.
+     .||.....||.
+     .||.....||.
+     .|L-7.F-J|.
+     .|..|.|..|.
+     .L--J.L--J.
+     ..........."
+};
+
+/// The fourth sample input from day 10's puzzle.
+pub const DAY10_4: &str = indoc! {
+    ".F----7F7F7F7F-7....
+     .|F--7||||||||FJ....
+     .||.FJ||||||||L7....
+     FJL7L7LJLJ||LJ.L-7..
+     L--J.L7...LJS7F-7L7.
+     ....F-J..F7FJ|L7L7L7
+     ....L7.F7||L7|.L7L7|
+     .....|FJLJ|FJ|F7|.LJ
+     ....FJL-7.||.||||...
+     ....L---J.LJ.LJLJ..."
+};
+
+/// The fifth sample input from day 10's puzzle.
+pub const DAY10_5: &str = indoc! {
+    "FF7FSF7F7F7F7F7F---7
+     L|LJ||||||||||||F--J
+     FL-7LJLJ||||||LJL-77
+     F--JF--7||LJLJ7F7FJ-
+     L---JF-JLJ.||-FJLJJ7
+     |F|F-JF---7F7-L7L|7|
+     |FFJF7L7F-JF7|JL---7
+     7-L-JL7||F7|L7F-7F7|
+     L.L7LFJ|||||FJL7||LJ
+     L7JLJL-JLJLJL--JLJ.L"
+};
+
+/// The sample input from day 11's puzzle.
+pub const DAY11: &str = indoc! {
+    "...#......
+     .......#..
+     #.........
+     ..........
+     ......#...
+     .#........
+     .........#
+     ..........
+     .......#..
+     #...#....."
+};
+
+/// The sample input from day 12's puzzle.
+pub const DAY12: &str = indoc! {
+    "???.### 1,1,3
+     .??..??...?##. 1,1,3
+     ?#?#?#?#?#?#?#? 1,3,1,6
+     ????.#...#... 4,1,1
+     ????.######..#####. 1,6,5
+     ?###???????? 3,2,1"
+};
+
+/// The sample input from day 13's puzzle.
+pub const DAY13: &str = indoc! {
+    "#.##..##.
+     ..#.##.#.
+     ##......#
+     ##......#
+     ..#.##.#.
+     ..##..##.
+     #.#.##.#.
+
+     #...##..#
+     #....#..#
+     ..##..###
+     #####.##.
+     #####.##.
+     ..##..###
+     #....#..#"
+};
+
+/// The sample input from day 14's puzzle.
+pub const DAY14: &str = indoc! {
+    "O....#....
+     O.OO#....#
+     .....##...
+     OO.#O....O
+     .O.....O#.
+     O.#..O.#.#
+     ..O..#O..O
+     .......O..
+     #....###..
+     #OO..#...."
+};
+
+/// The sample input from day 15's puzzle.
+pub const DAY15: &str = indoc! {
+    "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7"
+};
+
+/// The sample input from day 16's puzzle.
+pub const DAY16: &str = indoc! {
+    r#".|...\....
+       |.-.\.....
+       .....|-...
+       ........|.
+       ..........
+       .........\
+       ..../.\\..
+       .-.-/..|..
+       .|....-|.\
+       ..//.|...."#
+};
+
+/// The first sample input from day 17's puzzle.
+pub const DAY17_1: &str = indoc! {
+    "2413432311323
+     3215453535623
+     3255245654254
+     3446585845452
+     4546657867536
+     1438598798454
+     4457876987766
+     3637877979653
+     4654967986887
+     4564679986453
+     1224686865563
+     2546548887735
+     4322674655533"
+};
+
+/// The second sample input from day 17's puzzle.
+pub const DAY17_2: &str = indoc! {
+    "111111111111
+     999999999991
+     999999999991
+     999999999991
+     999999999991"
+};
+
+/// The sample input from day 18's puzzle.
+pub const DAY18: &str = indoc! {
+    "R 6 (#70c710)
+     D 5 (#0dc571)
+     L 2 (#5713f0)
+     D 2 (#d2c081)
+     R 2 (#59c680)
+     D 2 (#411b91)
+     L 5 (#8ceee2)
+     U 2 (#caa173)
+     L 1 (#1b58a2)
+     U 2 (#caa171)
+     R 2 (#7807d2)
+     U 3 (#a77fa3)
+     L 2 (#015232)
+     U 2 (#7a21e3)"
+};
+
+/// The sample input from day 19's puzzle.
+pub const DAY19: &str = indoc! {
+    "px{a<2006:qkq,m>2090:A,rfg}
+     pv{a>1716:R,A}
+     lnx{m>1548:A,A}
+     rfg{s<537:gd,x>2440:R,A}
+     qs{s>3448:A,lnx}
+     qkq{x<1416:A,crn}
+     crn{x>2662:A,R}
+     in{s<1351:px,qqz}
+     qqz{s>2770:qs,m<1801:hdj,R}
+     gd{a>3333:R,R}
+     hdj{m>838:A,pv}
+
+     {x=787,m=2655,a=1222,s=2876}
+     {x=1679,m=44,a=2067,s=496}
+     {x=2036,m=264,a=79,s=2244}
+     {x=2461,m=1339,a=466,s=291}
+     {x=2127,m=1623,a=2188,s=1013}"
+};
+
+/// The first sample input from day 20's puzzle.
+pub const DAY20_1: &str = indoc! {
+    "broadcaster -> a, b, c
+     %a -> b
+     %b -> c
+     %c -> inv
+     &inv -> a"
+};
+
+/// The second sample input from day 20's puzzle.
+pub const DAY20_2: &str = indoc! {
+    "broadcaster -> a
+     %a -> inv, con
+     &inv -> b
+     %b -> con
+     &con -> output"
+};
+
+/// The sample input from day 21's puzzle.
+pub const DAY21: &str = indoc! {
+    "...........
+     .....###.#.
+     .###.##..#.
+     ..#.#...#..
+     ....#.#....
+     .##..S####.
+     .##..#...#.
+     .......##..
+     .##.#.####.
+     .##..##.##.
+     ..........."
+};
+
+/// The sample input from day 22's puzzle.
+pub const DAY22: &str = indoc! {
+    "1,0,1~1,2,1
+     0,0,2~2,0,2
+     0,2,3~2,2,3
+     0,0,4~0,2,4
+     2,0,5~2,2,5
+     0,1,6~2,1,6
+     1,1,8~1,1,9"
+};
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::examples::{EXAMPLES, select};
+    use crate::solution::{Part, registry};
+
+    #[test]
+    fn test_every_example_gives_its_answers() {
+        let registry = registry();
+        for example in EXAMPLES {
+            let solver = registry.get(&example.day).unwrap();
+            let parsed = solver.parse_input(example.input).unwrap();
+            for part in [Part::One, Part::Two] {
+                if let Some(expected) = example.answer(part) {
+                    assert_eq!(solver.check_part(example.input, parsed.as_ref(), part), Ok(()), "example {} part {}", example.name, part);
+                    assert_eq!(solver.solve(parsed.as_ref(), part), expected, "example {} part {}", example.name, part);
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(&[8], None, vec!["day08-1", "day08-2", "day08-3"])]
+    #[case(&[1, 2], None, vec!["day01-1", "day01-2", "day02"])]
+    #[case(&[1, 2], Some("day17-2"), vec!["day17-2"])]
+    fn test_select(#[case] days: &[u8], #[case] name: Option<&str>, #[case] expected: Vec<&str>) {
+        let names: Vec<&str> = select(days, name).unwrap().iter().map(|example| example.name).collect();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_select_unknown() {
+        assert_eq!(select(&[1], Some("day99")), Err(String::from("There is no example called day99")));
+    }
+}
@@ -0,0 +1,166 @@
+//! Downloads the puzzle inputs from the Advent of Code site and keeps them in the data directory.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::common::data_file_name;
+
+/// Where the inputs are downloaded from, unless told otherwise.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2023";
+/// Holds the value of the `session` cookie from a logged in browser, as each user has their own inputs.
+pub const SESSION_VARIABLE: &str = "AOC_SESSION";
+/// Overrides the site the inputs are downloaded from, to test against a local server say.
+pub const BASE_URL_VARIABLE: &str = "AOC_BASE_URL";
+
+/// What happened to a day's input.
+#[derive(PartialEq, Debug)]
+pub enum Fetched {
+    /// The input was downloaded to this file
+    Downloaded(PathBuf),
+    /// The file was already there, so nothing was downloaded
+    Cached(PathBuf)
+}
+
+/// Downloads the puzzle inputs from the site, as the user the session belongs to.
+#[derive(PartialEq, Debug)]
+pub struct Fetcher {
+    base_url: String,
+    session: Option<String>
+}
+
+impl Fetcher {
+    /// Downloads from `base_url`, such as <https://adventofcode.com/2023>, with the session cookie if there is one.
+    pub fn new(base_url: &str, session: Option<&str>) -> Fetcher {
+        Fetcher {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+        }
+    }
+
+    /// Uses the given base URL, or the one from AOC_BASE_URL, or the real site, and the session from AOC_SESSION.
+    pub fn from_env(base_url: Option<&str>) -> Fetcher {
+        let base_url = base_url.map(String::from)
+            .or_else(|| env::var(BASE_URL_VARIABLE).ok())
+            .unwrap_or_else(|| String::from(DEFAULT_BASE_URL));
+        Fetcher::new(base_url.as_str(), env::var(SESSION_VARIABLE).ok().as_deref())
+    }
+
+    /// Where a day's input is downloaded from.
+    pub fn url(&self, day: u8) -> String {
+        format!("{}/day/{}/input", self.base_url, day)
+    }
+
+    /// Downloads a day's input into `dir`, unless it's already there. The session is only needed if there is
+    /// something to download.
+    pub fn fetch(&self, day: u8, dir: &Path) -> Result<Fetched, String> {
+        let path = dir.join(data_file_name(day));
+        if path.exists() {
+            return Ok(Fetched::Cached(path));
+        }
+        let Some(session) = &self.session else {
+            return Err(format!("Set {} to your session cookie to download the input for day {}", SESSION_VARIABLE, day));
+        };
+
+        let url = self.url(day);
+        let input = ureq::get(url.as_str())
+            .set("Cookie", format!("session={}", session).as_str())
+            .set("User-Agent", concat!("aoc-2023/", env!("CARGO_PKG_VERSION"), " input fetcher"))
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, response) =>
+                    format!("Could not download {}: {} {}", url, code, response.status_text()),
+                e => format!("Could not download {}: {}", url, e)
+            })?
+            .into_string()
+            .map_err(|e| format!("Could not read the input from {}: {}", url, e))?;
+
+        // Written to a temporary file first, so a failed write doesn't leave half an input that looks cached
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+        let partial = path.with_extension("part");
+        fs::write(&partial, input)
+            .and_then(|_| fs::rename(&partial, &path))
+            .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        Ok(Fetched::Downloaded(path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::thread::JoinHandle;
+    use crate::fetch::{Fetched, Fetcher};
+    use crate::testing::TempDir;
+
+    // Answers one request with the given status and body, and hands back the request it got
+    fn mock_server(status: &'static str, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2023", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (base_url, handle)
+    }
+
+    #[test]
+    fn test_fetch_downloads_input() {
+        let (base_url, server) = mock_server("200 OK", "1abc2\npqr3stu8vwx\n");
+        let dir = TempDir::new("fetch-download");
+        let fetched = Fetcher::new(base_url.as_str(), Some("secret")).fetch(1, &dir);
+        let request = server.join().unwrap();
+        let contents = fs::read_to_string(dir.join("day01.txt"));
+
+        assert_eq!(fetched, Ok(Fetched::Downloaded(dir.join("day01.txt"))));
+        assert_eq!(contents.unwrap(), "1abc2\npqr3stu8vwx\n");
+        assert!(request.starts_with("GET /2023/day/1/input HTTP/1.1"), "{}", request);
+        assert!(request.to_lowercase().contains("cookie: session=secret"), "{}", request);
+    }
+
+    #[test]
+    fn test_fetch_skips_existing_file() {
+        let dir = TempDir::new("fetch-cached");
+        fs::write(dir.join("day07.txt"), "32T3K 765").unwrap();
+        // Nothing is listening here, so this would fail if it tried to download anything
+        let fetched = Fetcher::new("http://127.0.0.1:1", None).fetch(7, &dir);
+        let contents = fs::read_to_string(dir.join("day07.txt")).unwrap();
+
+        assert_eq!(fetched, Ok(Fetched::Cached(dir.join("day07.txt"))));
+        assert_eq!(contents, "32T3K 765");
+    }
+
+    #[test]
+    fn test_fetch_error_status() {
+        let (base_url, server) = mock_server("404 Not Found", "Please don't repeatedly request this endpoint before it unlocks!");
+        let dir = TempDir::new("fetch-missing");
+        let fetched = Fetcher::new(base_url.as_str(), Some("secret")).fetch(25, &dir);
+        server.join().unwrap();
+
+        assert_eq!(fetched, Err(format!("Could not download {}/day/25/input: 404 Not Found", base_url)));
+        assert!(!dir.join("day25.txt").exists());
+    }
+
+    #[test]
+    fn test_fetch_needs_session() {
+        let dir = TempDir::new("fetch-session");
+        let fetched = Fetcher::new("http://127.0.0.1:1", Some("  ")).fetch(3, &dir);
+        assert_eq!(fetched, Err(String::from("Set AOC_SESSION to your session cookie to download the input for day 3")));
+    }
+
+    #[test]
+    fn test_url() {
+        assert_eq!(Fetcher::new("http://localhost:8080/2023/", None).url(12), "http://localhost:8080/2023/day/12/input");
+    }
+}
@@ -0,0 +1,158 @@
+//! Random puzzle inputs for every day, for stress testing and for sharing without giving away the real inputs.
+//!
+//! Each day's generator lives next to its parser, and makes inputs that keep to everything the real inputs promise,
+//! so that the solutions can solve them. The same day, size and seed always give the same input.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day20, day21, day22};
+
+/// Makes an input of roughly the given size, which means something different for each day: the number of lines,
+/// say, or the width of a grid.
+pub type Generator = fn(&mut StdRng, usize) -> String;
+
+/// The generator for a day, if there is one.
+// Add new days here
+pub fn generator(day: u8) -> Option<Generator> {
+    let generator: Generator = match day {
+        1 => day01::generate,
+        2 => day02::generate,
+        3 => day03::generate,
+        4 => day04::generate,
+        5 => day05::generate,
+        6 => day06::generate,
+        7 => day07::generate,
+        8 => day08::generate,
+        9 => day09::generate,
+        10 => day10::generate,
+        11 => day11::generate,
+        12 => day12::generate,
+        13 => day13::generate,
+        14 => day14::generate,
+        15 => day15::generate,
+        16 => day16::generate,
+        17 => day17::generate,
+        18 => day18::generate,
+        19 => day19::generate,
+        20 => day20::generate,
+        21 => day21::generate,
+        22 => day22::generate,
+        _ => return None
+    };
+    Some(generator)
+}
+
+/// Generates an input for a day, or None if the day doesn't have a generator.
+pub fn generate(day: u8, size: usize, seed: u64) -> Option<String> {
+    generator(day).map(|generator| generator(&mut StdRng::seed_from_u64(seed), size))
+}
+
+/// A loop on a `width` by `height` grid that never touches itself, as the cells it passes through in order. It is
+/// made of a top and a bottom edge, joined by a left and a right side that wander about without meeting.
+pub(crate) fn random_loop(rng: &mut StdRng, width: usize, height: usize) -> Vec<(usize, usize)> {
+    assert!(width >= 3 && height >= 2, "a loop needs at least a 3 by 2 grid");
+    let top = rng.gen_range(0..=(height - 2) / 4);
+    let bottom = rng.gen_range((top + 1).max(height - 1 - (height - 1) / 4)..height);
+
+    // The columns of the left and right sides on each row. Each row's sides overlap the ones above, and the left
+    // side's cells on a row always finish at least one cell before the right side's start.
+    let left = rng.gen_range(0..width - 2);
+    let mut sides: Vec<(usize, usize)> = vec![(left, rng.gen_range(left + 2..width))];
+    for _ in top + 1..bottom {
+        let (left, right) = *sides.last().unwrap();
+        let mut wander = |column: usize| column.saturating_add_signed(rng.gen_range(-2..=2)).min(width - 1);
+        let next = loop {
+            let (next_left, next_right) = (wander(left), wander(right));
+            if next_left.max(left) + 2 <= next_right.min(right) {
+                break (next_left, next_right);
+            }
+        };
+        sides.push(next);
+    }
+    // The bottom row keeps the sides of the row above, so the bottom edge is all that is on it
+    sides.push(*sides.last().unwrap());
+
+    fn walk_to(cells: &mut Vec<(usize, usize)>, to: (usize, usize)) {
+        let (mut x, mut y) = *cells.last().unwrap();
+        while (x, y) != to {
+            if x != to.0 {
+                x = if x < to.0 { x + 1 } else { x - 1 };
+            } else {
+                y = if y < to.1 { y + 1 } else { y - 1 };
+            }
+            cells.push((x, y));
+        }
+    }
+
+    let mut cells = vec![(sides[0].0, top)];
+    walk_to(&mut cells, (sides[0].1, top));
+    for (row, pair) in sides.windows(2).enumerate() {
+        let y = top + row + 1;
+        walk_to(&mut cells, (pair[0].1, y));
+        walk_to(&mut cells, (pair[1].1, y));
+    }
+    walk_to(&mut cells, (sides.last().unwrap().0, bottom));
+    for (row, pair) in sides.windows(2).enumerate().rev() {
+        let y = top + row + 1;
+        walk_to(&mut cells, (pair[0].0, y));
+        walk_to(&mut cells, (pair[0].0, y - 1));
+    }
+    // We end back where we started
+    cells.pop();
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use proptest::proptest;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use rstest::rstest;
+    use crate::generate::{generate, random_loop};
+    use crate::solution::{Part, registry};
+
+    #[test]
+    fn test_every_day_can_be_solved() {
+        for (day, solver) in registry() {
+            for seed in 0..3 {
+                let input = generate(day, 12, seed).unwrap();
+                let parsed = solver.parse_input(input.as_str())
+                    .unwrap_or_else(|e| panic!("day {} seed {} didn't parse: {}\n{}", day, seed, e, input));
+                for part in [Part::One, Part::Two] {
+                    solver.check_part(input.as_str(), parsed.as_ref(), part)
+                        .unwrap_or_else(|e| panic!("day {} seed {} can't do part {}: {}\n{}", day, seed, part, e, input));
+                    solver.solve(parsed.as_ref(), part);
+                }
+            }
+        }
+    }
+
+    #[rstest]
+    #[case(5)]
+    #[case(13)]
+    #[case(20)]
+    fn test_same_seed_same_input(#[case] day: u8) {
+        assert_eq!(generate(day, 20, 42), generate(day, 20, 42));
+        assert_ne!(generate(day, 20, 42), generate(day, 20, 43));
+    }
+
+    #[test]
+    fn test_no_generator() {
+        assert_eq!(generate(26, 10, 0), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_random_loop(seed in 0..u64::MAX, width in 3..40usize, height in 2..40usize) {
+            let cells = random_loop(&mut StdRng::seed_from_u64(seed), width, height);
+            assert!(cells.len() >= 4);
+            assert_eq!(cells.iter().collect::<HashSet<_>>().len(), cells.len(), "the loop crosses itself: {:?}", cells);
+            for (i, (x, y)) in cells.iter().enumerate() {
+                let (next_x, next_y) = cells[(i + 1) % cells.len()];
+                assert!(*x < width && *y < height);
+                assert_eq!(x.abs_diff(next_x) + y.abs_diff(next_y), 1, "{:?} isn't next to {:?}", (x, y), (next_x, next_y));
+            }
+        }
+    }
+}
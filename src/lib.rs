@@ -0,0 +1,54 @@
+//! Solutions to [Advent of Code 2023](https://adventofcode.com/2023).
+//!
+//! Each day has its own module with a `DayNN` type that implements [`solution::Solution`], which splits the puzzle into
+//! parsing the input and solving each part. The parsed types are public, so they can be built once and reused.
+//!
+//! ```
+//! use aoc_2023::day02::Day02;
+//! use aoc_2023::solution::Solution;
+//!
+//! let games = Day02.parse("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap();
+//! assert_eq!(Day02.part_one(&games), 1);
+//! assert_eq!(Day02.part_two(&games), 48);
+//! ```
+//!
+//! To work with every day at once, [`solution::registry`] gives a type-erased [`solution::Solver`] for each of them,
+//! and [`runner`] loads the inputs, runs the days and checks the answers, as the `aoc-2023` binary does.
+
+#![warn(missing_docs)]
+
+pub mod cancel;
+pub mod check;
+pub mod common;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod differential;
+pub mod examples;
+pub mod fetch;
+pub mod generate;
+pub mod memory;
+pub mod report;
+pub mod runner;
+pub mod solution;
+#[doc(hidden)]
+pub mod testing;
@@ -1,88 +1,369 @@
+use std::fs;
+use std::io;
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Duration;
+use structopt::clap::{Error, ErrorKind};
+use itertools::Itertools;
 use structopt::StructOpt;
+use aoc_2023::check::Answers;
+use aoc_2023::common::{InputSource, set_verbosity};
+use aoc_2023::fetch::{Fetched, Fetcher};
+use aoc_2023::memory::CountingAllocator;
+use aoc_2023::{differential, examples, generate, memory, report, runner};
+use aoc_2023::differential::Outcome;
+use aoc_2023::report::{Format, PartResult, Status};
+use aoc_2023::runner::{DayError, Settings};
+use aoc_2023::solution::{registry, Solver};
+use crate::selection::{DayList, PartSelection};
+use crate::watch::Watcher;
 
-mod day01;
-mod day02;
-mod common;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
+mod bench;
+mod scaffold;
+mod selection;
+mod watch;
+
+// Only counts anything once --memory turns it on
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[derive(StructOpt, Debug)]
 #[structopt(name = "aoc")]
 struct Opts {
 
+    /// The days to run, as a single day, a range or a list, like 1-5,9,20-22. Runs every day if not given
     #[structopt(short, long("day"))]
-    days: Vec<u8>
+    days: Vec<DayList>,
+
+    /// Which part to run: a, b or both
+    #[structopt(long, default_value("both"))]
+    part: PartSelection,
+
+    /// Runs each day this many times and prints timings for each phase instead of the answers
+    #[structopt(long)]
+    bench: Option<u32>,
+
+    /// A single day's input file, or - to read it from stdin
+    #[structopt(long, parse(from_os_str), conflicts_with("data-dir"))]
+    input: Option<PathBuf>,
+
+    /// Where to find the dayNN.txt files, defaults to data
+    #[structopt(long, parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+
+    /// Compares each answer against the expected answers file and exits with an error on any mismatch
+    #[structopt(long)]
+    check: bool,
+
+    /// The expected answers used by --check
+    #[structopt(long, parse(from_os_str), default_value("answers.toml"))]
+    answers: PathBuf,
+
+    /// How to print the answers: text, json, csv or markdown
+    #[structopt(long, default_value("text"))]
+    output: Format,
+
+    /// Runs the puzzle examples for the selected days, or just the named one, and checks their answers
+    #[structopt(long, conflicts_with_all(&["input", "data-dir", "check", "bench"]))]
+    example: Option<Option<String>>,
+
+    /// How many days to run at the same time
+    #[structopt(long, default_value("1"))]
+    jobs: usize,
+
+    /// Reports the peak heap usage, total bytes allocated and number of allocations for each part
+    #[structopt(long, conflicts_with_all(&["bench", "example"]))]
+    memory: bool,
+
+    /// Logs how the answers are worked out to stderr. Use -vv or -vvv for more detail
+    #[structopt(short, long, parse(from_occurrences))]
+    verbose: u8,
+
+    /// Keeps running, and runs each day again whenever its input file changes
+    #[structopt(long, conflicts_with_all(&["bench", "example"]))]
+    watch: bool,
+
+    /// Stops any day that takes longer than this many seconds, reports it as TIMEOUT and carries on with the rest
+    #[structopt(long, conflicts_with("example"))]
+    timeout: Option<f64>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>
+
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Downloads the puzzle inputs into the data directory, using the session cookie in AOC_SESSION. Inputs that have
+    /// already been downloaded are left alone
+    Fetch {
+        /// The days to download, like 1-5,9. Downloads every day if not given
+        #[structopt(short, long("day"))]
+        days: Vec<DayList>,
+
+        /// Where to save the dayNN.txt files, defaults to data
+        #[structopt(long, parse(from_os_str))]
+        data_dir: Option<PathBuf>,
+
+        /// The site to download from, defaults to AOC_BASE_URL or https://adventofcode.com/2023
+        #[structopt(long)]
+        base_url: Option<String>
+    },
+
+    /// Prints a random input for a day, which can be used in place of the real one
+    Generate {
+        /// The day to make an input for
+        #[structopt(short, long)]
+        day: u8,
+
+        /// How big to make the input. What this means depends on the day, like the number of lines or the width of a
+        /// grid
+        #[structopt(long, default_value("100"))]
+        size: usize,
+
+        /// The same day, size and seed always give the same input
+        #[structopt(long, default_value("0"))]
+        seed: u64
+    },
 
+    /// Checks the shortcuts the days take against simple, slow ways of getting the same answers, on small random inputs
+    Differential {
+        /// The days to check, like 8-10. Checks every day that has a shortcut if not given
+        #[structopt(short, long("day"))]
+        days: Vec<DayList>,
+
+        /// How many random inputs to check each shortcut on
+        #[structopt(long, default_value("100"))]
+        seeds: u64,
+
+        /// Where to save the inputs that the shortcuts get wrong
+        #[structopt(long, parse(from_os_str))]
+        save_inputs: Option<PathBuf>,
+
+        /// Checks on inputs that don't have the properties the real inputs have, for the shortcuts that can be
+        #[structopt(long)]
+        break_assumptions: bool
+    },
+
+    /// Starts a new day: writes its module from a template, adds it to the registry and makes an empty input file
+    NewDay {
+        /// The day to add
+        day: u8,
+
+        /// Where to make the dayNN.txt file, defaults to data
+        #[structopt(long, parse(from_os_str))]
+        data_dir: Option<PathBuf>
+    }
+}
+
+// The days from the --day arguments, or every day if there weren't any
+fn select_days(lists: &[DayList], days: &BTreeMap<u8, &'static dyn Solver>) -> Vec<u8> {
+    let selected: Vec<u8> = if lists.is_empty() {
+        days.keys().copied().collect()
+    } else {
+        lists.iter().flat_map(|list| list.0.iter().copied()).unique().collect()
+    };
+    if let Some(missing) = selected.iter().find(|day| !days.contains_key(day)) {
+        let allowed = format!("{}-{}", days.keys().next().unwrap(), days.keys().last().unwrap());
+        Error::with_description(format!("Day {} does not exist, the days are {}", missing, allowed).as_str(), ErrorKind::InvalidValue).exit();
+    }
+    selected
+}
+
+// Downloads each day's input in turn. Returns false if any of them couldn't be downloaded.
+fn fetch(days_to_fetch: &[u8], fetcher: &Fetcher, dir: &Path) -> bool {
+    let mut ok = true;
+    for day in days_to_fetch {
+        match fetcher.fetch(*day, dir) {
+            Ok(Fetched::Downloaded(path)) => println!("Day {}: downloaded to {}", day, path.display()),
+            Ok(Fetched::Cached(path)) => println!("Day {}: {} is already there", day, path.display()),
+            Err(e) => {
+                eprintln!("Day {}: {}", day, e);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+// Runs the days and prints their results. Returns true if any of them couldn't be run or got the wrong answer.
+fn run(days_to_run: &[u8], days: &BTreeMap<u8, &'static dyn Solver>, settings: &Settings, jobs: usize, output: Format) -> bool {
+    let mut failed = false;
+    let mut results: Vec<PartResult> = Vec::new();
+    runner::run_all(days_to_run, days, settings, jobs, |day, result| {
+        let mut day_results = match result {
+            Ok(day_results) => day_results,
+            Err(error) => {
+                // The parse error shows where in the input it is, which is too much for the results
+                match &error {
+                    DayError::Parse(e) => eprintln!("{}", e),
+                    e => eprintln!("Day {}: {}", day, e)
+                }
+                failed = true;
+                runner::error_results(day, settings.parts, &error)
+            }
+        };
+        failed |= day_results.iter().any(|r| matches!(r.status, Status::Fail(_) | Status::Timeout));
+        if output.is_streamed() {
+            report::write(output, &day_results, &mut io::stdout()).unwrap();
+        } else {
+            results.append(&mut day_results);
+        }
+    });
+
+    if !output.is_streamed() {
+        report::write(output, &results, &mut io::stdout()).unwrap();
+    }
+    failed
+}
+
+// Checks the shortcuts for the given days on each seed, and prints how they did. Returns false if any of them got
+// something wrong.
+fn check_shortcuts(days_to_check: &[u8], seeds: u64, save_inputs: Option<&Path>, break_assumptions: bool) -> bool {
+    let mut ok = true;
+    for shortcut in differential::shortcuts().iter().filter(|s| days_to_check.contains(&s.day)) {
+        let Some(generate) = shortcut.generator(break_assumptions) else {
+            continue;
+        };
+        let summary = differential::check_seeds(shortcut, generate, 0..seeds);
+        print!("Day {}, {}: {} agreed", shortcut.day, shortcut.name, summary.agreed);
+        if summary.skipped > 0 {
+            print!(", {} too slow to check", summary.skipped);
+        }
+        if !summary.failures.is_empty() {
+            print!(", {} failed", summary.failures.len());
+            ok = false;
+        }
+        println!();
+
+        for failure in &summary.failures {
+            match &failure.outcome {
+                Outcome::Disagreed { shortcut, reference } =>
+                    println!("  seed {}: the shortcut gave {}, the simple way gave {}", failure.seed, shortcut, reference),
+                Outcome::Panicked(message) => println!("  seed {}: the shortcut panicked: {}", failure.seed, message),
+                Outcome::Invalid(e) => println!("  seed {}: the generated input doesn't parse: {}", failure.seed, e),
+                Outcome::Agreed | Outcome::Skipped => {}
+            }
+            if let Some(dir) = save_inputs {
+                let path = dir.join(format!("day{:02}-seed{}.txt", shortcut.day, failure.seed));
+                match fs::create_dir_all(dir).and_then(|_| fs::write(&path, &failure.input)) {
+                    Ok(_) => println!("    input saved to {}", path.display()),
+                    Err(e) => eprintln!("    could not save the input to {}: {}", path.display(), e)
+                }
+            }
+        }
+    }
+    ok
 }
 
 fn main() {
     let args: Opts = Opts::from_args();
 
-    // Add day numbers to functions here
-    let days: BTreeMap<u8, Box<dyn Fn() -> ()>> = BTreeMap::from([
-        (1,  Box::new(day01::run_day) as Box<_>),
-        (2,  Box::new(day02::run_day) as Box<_>),
-        (3,  Box::new(day03::run_day) as Box<_>),
-        (4,  Box::new(day04::run_day) as Box<_>),
-        (5,  Box::new(day05::run_day) as Box<_>),
-        (6,  Box::new(day06::run_day) as Box<_>),
-        (7,  Box::new(day07::run_day) as Box<_>),
-        (8,  Box::new(day08::run_day) as Box<_>),
-        (9,  Box::new(day09::run_day) as Box<_>),
-        (10, Box::new(day10::run_day) as Box<_>),
-        (11, Box::new(day11::run_day) as Box<_>),
-        (12, Box::new(day12::run_day) as Box<_>),
-        (13, Box::new(day13::run_day) as Box<_>),
-        (14, Box::new(day14::run_day) as Box<_>),
-        (15, Box::new(day15::run_day) as Box<_>),
-        (16, Box::new(day16::run_day) as Box<_>),
-        (17, Box::new(day17::run_day) as Box<_>),
-        (18, Box::new(day18::run_day) as Box<_>),
-        (19, Box::new(day19::run_day) as Box<_>),
-        (20, Box::new(day20::run_day) as Box<_>),
-        (21, Box::new(day21::run_day) as Box<_>),
-        (22, Box::new(day22::run_day) as Box<_>),
-    ]);
-    let no_of_days: u8 = days.len().try_into().unwrap();
-
-    // Chooses the days to run
-    let days_to_run: Vec<u8> = if args.days.is_empty() {
-        // No entries = run all days
-        days.keys().map(|x| x.clone()).collect()
-    } else if args.days.iter().all(|entry| *entry <= no_of_days && *entry > 0) {
-        // The days to run
-        args.days
+    let days = registry();
+    set_verbosity(args.verbose);
+
+    match &args.command {
+        Some(Command::Fetch { days: lists, data_dir, base_url }) => {
+            let fetcher = Fetcher::from_env(base_url.as_deref());
+            let dir = data_dir.clone().unwrap_or_else(|| PathBuf::from("data"));
+            if !fetch(&select_days(lists, &days), &fetcher, &dir) {
+                exit(1);
+            }
+            return;
+        }
+        Some(Command::Generate { day, size, seed }) => {
+            match generate::generate(*day, *size, *seed) {
+                Some(input) => print!("{}", input),
+                None => Error::with_description(format!("Day {} does not exist", day).as_str(), ErrorKind::InvalidValue).exit()
+            }
+            return;
+        }
+        Some(Command::Differential { days: lists, seeds, save_inputs, break_assumptions }) => {
+            if !check_shortcuts(&select_days(lists, &days), *seeds, save_inputs.as_deref(), *break_assumptions) {
+                exit(1);
+            }
+            return;
+        }
+        Some(Command::NewDay { day, data_dir }) => {
+            if !(1..=25).contains(day) {
+                Error::with_description(format!("Day {} is not a day of Advent of Code, the days are 1-25", day).as_str(), ErrorKind::InvalidValue).exit();
+            }
+            let dir = data_dir.clone().unwrap_or_else(|| PathBuf::from("data"));
+            match scaffold::new_day(*day, Path::new(env!("CARGO_MANIFEST_DIR")), &dir) {
+                Ok(files) => files.iter().for_each(|file| println!("Wrote {}", file)),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    exit(1);
+                }
+            }
+            return;
+        }
+        None => {}
+    }
+
+    let days_to_run = select_days(&args.days, &days);
+    let parts = args.part.parts();
+
+    if let Some(name) = &args.example {
+        let examples = examples::select(&days_to_run, name.as_deref())
+            .unwrap_or_else(|e| Error::with_description(e.as_str(), ErrorKind::InvalidValue).exit());
+        if !examples::run(&examples, &days, &parts, args.output) {
+            exit(1);
+        }
+        return;
+    }
+
+    let source = match (&args.input, &args.data_dir) {
+        (Some(input), _) => InputSource::from_input(input),
+        (None, Some(dir)) => InputSource::DataDir(dir.clone()),
+        (None, None) => InputSource::default()
+    };
+    if !source.supports_many_days() && days_to_run.len() != 1 {
+        Error::with_description("--input can only be used when running a single day", ErrorKind::ArgumentConflict).exit();
+    }
+
+    let answers = if args.check {
+        match Answers::load(&args.answers) {
+            Ok(answers) => Some(answers),
+            Err(e) => {
+                eprintln!("{}", e);
+                exit(1);
+            }
+        }
     } else {
-        let days: Vec<String> = days.keys().map(|s| format!("{}", *s)).collect();
-        let asked: Vec<String> = args.days.iter().map(|s| format!("{}", *s)).collect();
-        panic!("A day was specified that does not exist (specified {}, allowed days are {})!", asked.join(", "), days.join(", "))
+        None
     };
 
-    for day in days_to_run {
-        println!("Day {}", day);
-        println!("---");
-        days.get(&day).unwrap()();
-        println!("---");
+    // Set up before the first run, so that changes made while it's running aren't missed
+    let watcher = args.watch.then(|| Watcher::new(&days_to_run, &source).unwrap_or_else(|| {
+        Error::with_description("--watch needs the input to come from files, not stdin", ErrorKind::ArgumentConflict).exit()
+    }));
+
+    if args.memory {
+        memory::enable();
+    }
+    let timeout = args.timeout.map(|seconds| Duration::try_from_secs_f64(seconds).unwrap_or_else(|_| {
+        Error::with_description("--timeout needs to be a number of seconds that isn't negative", ErrorKind::InvalidValue).exit()
+    }));
+    let settings = Settings { source: &source, parts: &parts, answers: answers.as_ref(), memory: args.memory, timeout };
+
+    if let Some(runs) = args.bench {
+        if !bench::run(&days_to_run, &days, &settings, runs.max(1), args.output) {
+            exit(1);
+        }
+        return;
     }
-}
 
+    let failed = run(&days_to_run, &days, &settings, args.jobs, args.output);
 
+    if let Some(watcher) = watcher {
+        watcher.watch(|changed| {
+            run(changed, &days, &settings, args.jobs, args.output);
+        });
+    }
+
+    if failed {
+        exit(1);
+    }
+}
@@ -0,0 +1,148 @@
+//! A global allocator that counts what is allocated, so we can see how much memory each day needs.
+//!
+//! The binary installs [`CountingAllocator`] as its global allocator, but it doesn't count anything until [`enable`]
+//! is called. The counts are kept for each thread, so days that are run at the same time (with --jobs) don't get
+//! mixed up.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How much memory something used.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub struct Usage {
+    /// The most that was allocated at any one time, on top of what was already allocated beforehand
+    pub peak_bytes: usize,
+    /// Everything that was allocated, including memory that was freed again. Growing an allocation counts as
+    /// allocating its new size.
+    pub allocated_bytes: usize,
+    /// How many times memory was allocated, or an allocation was grown
+    pub allocations: usize
+}
+
+#[derive(Clone, Copy)]
+struct Counts {
+    current: usize,
+    peak: usize,
+    allocated: usize,
+    allocations: usize
+}
+
+thread_local! {
+    // Initialised with a constant and never dropped, so using it doesn't allocate anything itself
+    static COUNTS: Cell<Counts> = const { Cell::new(Counts { current: 0, peak: 0, allocated: 0, allocations: 0 }) };
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Hands everything on to the system allocator, counting as it goes.
+pub struct CountingAllocator;
+
+/// Starts counting allocations.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn record(allocated: usize, freed: usize) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    // Memory can be freed by a different thread to the one that allocated it, or have been allocated before counting
+    // started, so the current total can't be allowed to go below zero. try_with, as this is still called while a
+    // thread's locals are being torn down.
+    let _ = COUNTS.try_with(|counts| {
+        let mut c = counts.get();
+        c.current = (c.current + allocated).saturating_sub(freed);
+        c.peak = c.peak.max(c.current);
+        if allocated > 0 {
+            c.allocated += allocated;
+            c.allocations += 1;
+        }
+        counts.set(c);
+    });
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record(0, layout.size());
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size(), 0);
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record(new_size, layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// Runs `f` and measures what it allocates on this thread. Everything is zero if counting hasn't been enabled.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Usage) {
+    let before = COUNTS.with(|counts| {
+        let mut c = counts.get();
+        c.peak = c.current;
+        counts.set(c);
+        c
+    });
+    let result = f();
+    let after = COUNTS.with(Cell::get);
+    (result, Usage {
+        peak_bytes: after.peak - before.current,
+        allocated_bytes: after.allocated - before.allocated,
+        allocations: after.allocations - before.allocations
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::memory::{CountingAllocator, enable, measure};
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        enable();
+        let (kept, usage) = measure(|| {
+            // Freed before the other one is made, so it adds to the total allocated but not the peak
+            let dropped: Vec<u64> = (0..500).collect();
+            assert_eq!(dropped.len(), 500);
+            drop(dropped);
+            (0..1000).collect::<Vec<u64>>()
+        });
+        assert_eq!(kept.len(), 1000);
+        assert_eq!(usage.allocations, 2);
+        assert_eq!(usage.allocated_bytes, 12000);
+        assert_eq!(usage.peak_bytes, 8000);
+    }
+
+    #[test]
+    fn test_measure_peak() {
+        enable();
+        let (_, usage) = measure(|| {
+            for size in [100, 400, 200] {
+                drop(Vec::<u8>::with_capacity(size));
+            }
+        });
+        assert_eq!(usage.allocations, 3);
+        assert_eq!(usage.allocated_bytes, 700);
+        assert_eq!(usage.peak_bytes, 400);
+    }
+}
@@ -0,0 +1,400 @@
+//! The results of running each part, and the formats they can be written out in.
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+use std::time::Duration;
+use crate::memory::Usage;
+use crate::solution::Part;
+
+/// How an answer compares to the expected one.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Status {
+    /// Nothing to compare against, as --check wasn't used
+    Solved,
+    /// The answer is the expected one
+    Pass,
+    /// The answer isn't the expected one, which is this
+    Fail(String),
+    /// --check was used, but there's no expected answer for this part
+    Missing,
+    /// Stopped by --timeout before it finished, so there's no answer
+    Timeout,
+    /// The day couldn't be run, for this reason, so there's no answer
+    Error(String)
+}
+
+impl Status {
+    /// The short form used in machine readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Solved => "ok",
+            Status::Pass => "pass",
+            Status::Fail(_) => "fail",
+            Status::Missing => "missing",
+            Status::Timeout => "timeout",
+            Status::Error(_) => "error"
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Status::Timeout => write!(f, "TIMEOUT"),
+            Status::Error(message) => write!(f, "ERROR, {}", message),
+            s => write!(f, "{}", s.name())
+        }
+    }
+}
+
+/// The answer to one part of a day, and how long it took to solve.
+#[derive(PartialEq, Debug, Clone)]
+pub struct PartResult {
+    /// The day the part is from
+    pub day: u8,
+    /// Which part it is
+    pub part: Part,
+    /// The answer, or nothing if it timed out
+    pub answer: String,
+    /// How long solving it took, not counting parsing
+    pub duration: Duration,
+    /// How the answer compares to the expected one
+    pub status: Status,
+    /// How much memory solving it took, if --memory was used
+    pub memory: Option<Usage>
+}
+
+/// How the results are written out, chosen with --output.
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+pub enum Format {
+    /// A heading for each day and a line for each part, for reading in a terminal
+    #[default]
+    Text,
+    /// One JSON array of every result
+    Json,
+    /// A header and then a row for each result
+    Csv,
+    /// A table of every result, for pasting into a README
+    Markdown
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "markdown" => Ok(Format::Markdown),
+            x => Err(format!("{} is not an output format, use one of text, json, csv or markdown", x))
+        }
+    }
+}
+
+impl Format {
+    /// Text is printed as each day finishes, everything else is printed in one go at the end.
+    pub fn is_streamed(&self) -> bool {
+        *self == Format::Text
+    }
+}
+
+/// Writes every result to `out` in the given format.
+pub fn write(format: Format, results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Text => write_text(results, out),
+        Format::Json => write_json(results, out),
+        Format::Csv => write_csv(results, out),
+        Format::Markdown => write_markdown(results, out)
+    }
+}
+
+fn write_text(results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+    for (i, result) in results.iter().enumerate() {
+        if i == 0 || results[i - 1].day != result.day {
+            writeln!(out, "Day {}", result.day)?;
+            writeln!(out, "---")?;
+        }
+        match result.status {
+            Status::Solved => write!(out, "Part {}: {}", result.part, result.answer)?,
+            Status::Timeout => write!(out, "Part {}: {} after {}ms", result.part, result.status, millis(&result.duration))?,
+            Status::Error(_) => write!(out, "Part {}: {}", result.part, result.status)?,
+            _ => write!(out, "Part {}: {} ({})", result.part, result.answer, result.status)?
+        }
+        if let Some(usage) = &result.memory {
+            write!(
+                out,
+                " [peak {}, {} in {} allocations]",
+                bytes(usage.peak_bytes),
+                bytes(usage.allocated_bytes),
+                usage.allocations
+            )?;
+        }
+        writeln!(out)?;
+        if i + 1 == results.len() || results[i + 1].day != result.day {
+            writeln!(out, "---")?;
+        }
+    }
+    Ok(())
+}
+
+/// The duration in milliseconds, to three decimal places.
+pub fn millis(duration: &Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+// In the largest unit that keeps the number at least 1
+fn bytes(n: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    let mut size = n as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", size, UNITS[unit])
+}
+
+// The memory columns are only added when there's something to go in them
+fn has_memory(results: &[PartResult]) -> bool {
+    results.iter().any(|r| r.memory.is_some())
+}
+
+/// The text as a JSON string, quoted and escaped.
+pub fn json_string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(format!("\\u{:04x}", c as u32).as_str()),
+            c => escaped.push(c)
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn write_json(results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, result) in results.iter().enumerate() {
+        writeln!(
+            out,
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"duration_ms\": {}, \"status\": {}{}}}{}",
+            result.day,
+            result.part,
+            json_string(result.answer.as_str()),
+            millis(&result.duration),
+            json_string(result.status.name()),
+            result.memory.map_or(String::new(), |usage| format!(
+                ", \"peak_bytes\": {}, \"allocated_bytes\": {}, \"allocations\": {}",
+                usage.peak_bytes,
+                usage.allocated_bytes,
+                usage.allocations
+            )),
+            if i + 1 == results.len() { "" } else { "," }
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+/// The text as a CSV field, quoted if it needs to be.
+pub fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn write_csv(results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+    let memory = has_memory(results);
+    writeln!(out, "day,part,answer,duration_ms,status{}", if memory { ",peak_bytes,allocated_bytes,allocations" } else { "" })?;
+    for result in results {
+        write!(
+            out,
+            "{},{},{},{},{}",
+            result.day,
+            result.part,
+            csv_field(result.answer.as_str()),
+            millis(&result.duration),
+            result.status.name()
+        )?;
+        if memory {
+            let usage = result.memory.unwrap_or_default();
+            write!(out, ",{},{},{}", usage.peak_bytes, usage.allocated_bytes, usage.allocations)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_markdown(results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+    let memory = has_memory(results);
+    if memory {
+        writeln!(out, "| Day | Part | Answer | Duration | Status | Peak heap | Allocated | Allocations |")?;
+        writeln!(out, "|----:|-----:|-------:|---------:|--------|----------:|----------:|------------:|")?;
+    } else {
+        writeln!(out, "| Day | Part | Answer | Duration | Status |")?;
+        writeln!(out, "|----:|-----:|-------:|---------:|--------|")?;
+    }
+    for result in results {
+        write!(
+            out,
+            "| {} | {} | {} | {:.2?} | {} |",
+            result.day,
+            result.part,
+            result.answer.replace('|', "\\|"),
+            result.duration,
+            result.status.name()
+        )?;
+        match &result.memory {
+            Some(usage) => write!(out, " {} | {} | {} |", bytes(usage.peak_bytes), bytes(usage.allocated_bytes), usage.allocations)?,
+            None if memory => write!(out, " | | |")?,
+            None => {}
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use indoc::indoc;
+    use rstest::rstest;
+    use crate::memory::Usage;
+    use crate::report::{bytes, Format, PartResult, Status, write};
+    use crate::solution::Part;
+
+    fn results() -> Vec<PartResult> {
+        vec![
+            PartResult { day: 2, part: Part::One, answer: String::from("8"), duration: Duration::from_micros(1500), status: Status::Pass, memory: None },
+            PartResult { day: 2, part: Part::Two, answer: String::from("2286"), duration: Duration::from_micros(250), status: Status::Fail(String::from("2287")), memory: None },
+            PartResult { day: 15, part: Part::One, answer: String::from("1,\"x\""), duration: Duration::from_millis(2), status: Status::Solved, memory: None },
+            PartResult { day: 15, part: Part::Two, answer: String::new(), duration: Duration::from_secs(10), status: Status::Timeout, memory: None },
+            PartResult { day: 16, part: Part::One, answer: String::new(), duration: Duration::ZERO, status: Status::Error(String::from("panicked: oops")), memory: None },
+        ]
+    }
+
+    #[rstest]
+    #[case(Format::Text, indoc! {
+        "Day 2
+         ---
+         Part 1: 8 (pass)
+         Part 2: 2286 (FAIL, expected 2287)
+         ---
+         Day 15
+         ---
+         Part 1: 1,\"x\"
+         Part 2: TIMEOUT after 10000.000ms
+         ---
+         Day 16
+         ---
+         Part 1: ERROR, panicked: oops
+         ---
+         "
+    })]
+    #[case(Format::Json, indoc! {
+        r#"[
+             {"day": 2, "part": 1, "answer": "8", "duration_ms": 1.500, "status": "pass"},
+             {"day": 2, "part": 2, "answer": "2286", "duration_ms": 0.250, "status": "fail"},
+             {"day": 15, "part": 1, "answer": "1,\"x\"", "duration_ms": 2.000, "status": "ok"},
+             {"day": 15, "part": 2, "answer": "", "duration_ms": 10000.000, "status": "timeout"},
+             {"day": 16, "part": 1, "answer": "", "duration_ms": 0.000, "status": "error"}
+           ]
+           "#
+    })]
+    #[case(Format::Csv, indoc! {
+        r#"day,part,answer,duration_ms,status
+           2,1,8,1.500,pass
+           2,2,2286,0.250,fail
+           15,1,"1,""x""",2.000,ok
+           15,2,,10000.000,timeout
+           16,1,,0.000,error
+           "#
+    })]
+    #[case(Format::Markdown, indoc! {
+        r#"| Day | Part | Answer | Duration | Status |
+           |----:|-----:|-------:|---------:|--------|
+           | 2 | 1 | 8 | 1.50ms | pass |
+           | 2 | 2 | 2286 | 250.00µs | fail |
+           | 15 | 1 | 1,"x" | 2.00ms | ok |
+           | 15 | 2 |  | 10.00s | timeout |
+           | 16 | 1 |  | 0.00ns | error |
+           "#
+    })]
+    fn test_write(#[case] format: Format, #[case] expected: &str) {
+        let mut out: Vec<u8> = Vec::new();
+        write(format, &results(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    fn memory_results() -> Vec<PartResult> {
+        let usage = |peak_bytes, allocated_bytes, allocations| Some(Usage { peak_bytes, allocated_bytes, allocations });
+        vec![
+            PartResult { day: 12, part: Part::One, answer: String::from("21"), duration: Duration::from_millis(3), status: Status::Solved, memory: usage(512, 4096, 7) },
+            PartResult { day: 12, part: Part::Two, answer: String::from("525152"), duration: Duration::from_millis(40), status: Status::Pass, memory: usage(1536, 3 << 20, 1200) },
+        ]
+    }
+
+    #[rstest]
+    #[case(Format::Text, indoc! {
+        "Day 12
+         ---
+         Part 1: 21 [peak 512 B, 4.00 KiB in 7 allocations]
+         Part 2: 525152 (pass) [peak 1.50 KiB, 3.00 MiB in 1200 allocations]
+         ---
+         "
+    })]
+    #[case(Format::Json, indoc! {
+        r#"[
+             {"day": 12, "part": 1, "answer": "21", "duration_ms": 3.000, "status": "ok", "peak_bytes": 512, "allocated_bytes": 4096, "allocations": 7},
+             {"day": 12, "part": 2, "answer": "525152", "duration_ms": 40.000, "status": "pass", "peak_bytes": 1536, "allocated_bytes": 3145728, "allocations": 1200}
+           ]
+           "#
+    })]
+    #[case(Format::Csv, indoc! {
+        r#"day,part,answer,duration_ms,status,peak_bytes,allocated_bytes,allocations
+           12,1,21,3.000,ok,512,4096,7
+           12,2,525152,40.000,pass,1536,3145728,1200
+           "#
+    })]
+    #[case(Format::Markdown, indoc! {
+        r#"| Day | Part | Answer | Duration | Status | Peak heap | Allocated | Allocations |
+           |----:|-----:|-------:|---------:|--------|----------:|----------:|------------:|
+           | 12 | 1 | 21 | 3.00ms | ok | 512 B | 4.00 KiB | 7 |
+           | 12 | 2 | 525152 | 40.00ms | pass | 1.50 KiB | 3.00 MiB | 1200 |
+           "#
+    })]
+    fn test_write_memory(#[case] format: Format, #[case] expected: &str) {
+        let mut out: Vec<u8> = Vec::new();
+        write(format, &memory_results(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1024, "1.00 KiB")]
+    #[case(5 << 30, "5.00 GiB")]
+    #[case(3 << 40, "3072.00 GiB")]
+    fn test_bytes(#[case] n: usize, #[case] expected: &str) {
+        assert_eq!(bytes(n), expected);
+    }
+
+    #[rstest]
+    #[case("json", Ok(Format::Json))]
+    #[case("markdown", Ok(Format::Markdown))]
+    #[case("xml", Err(String::from("xml is not an output format, use one of text, json, csv or markdown")))]
+    fn test_parse_format(#[case] input: &str, #[case] expected: Result<Format, String>) {
+        assert_eq!(input.parse::<Format>(), expected);
+    }
+}
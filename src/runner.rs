@@ -0,0 +1,282 @@
+//! Loads the input for each day, solves it and times it.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::cancel;
+use crate::cancel::Token;
+use crate::check::Answers;
+use crate::common::{InputSource, load_from, ParseError};
+use crate::memory;
+use crate::report::{PartResult, Status};
+use crate::solution::{Part, Solver};
+
+/// Why a day couldn't be run
+#[derive(Debug)]
+pub enum DayError {
+    /// The input couldn't be read
+    Input(io::Error),
+    /// The input couldn't be parsed
+    Parse(ParseError),
+    /// The solver panicked, with the panic's message
+    Panicked(String)
+}
+
+// On one line, for the results. The day comes from the results, so the parse error's is left out.
+impl Display for DayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DayError::Input(e) => write!(f, "{}", e),
+            DayError::Parse(e) => write!(f, "line {}, column {}: {}", e.line, e.column, e.message),
+            DayError::Panicked(message) => write!(f, "panicked: {}", message)
+        }
+    }
+}
+
+/// A result for each of `parts` saying why the day couldn't be run, so the day still shows up in the results.
+pub fn error_results(day: u8, parts: &[Part], error: &DayError) -> Vec<PartResult> {
+    parts.iter().map(|part| PartResult {
+        day,
+        part: *part,
+        answer: String::new(),
+        duration: Duration::ZERO,
+        status: Status::Error(error.to_string()),
+        memory: None
+    }).collect()
+}
+
+/// Everything that affects how each day is run
+pub struct Settings<'a> {
+    /// Where to read each day's input from
+    pub source: &'a InputSource,
+    /// Which parts to solve
+    pub parts: &'a [Part],
+    /// The answers to check against, if --check was used
+    pub answers: Option<&'a Answers>,
+    /// Whether to report the memory each part uses, which needs the counting allocator to be installed and enabled
+    pub memory: bool,
+    /// How long each day can take, including parsing, before whatever part it's on is stopped
+    pub timeout: Option<Duration>
+}
+
+/// A day's results, and how long it took to get its input ready to solve
+#[derive(Debug)]
+pub struct DayRun {
+    /// How long reading the input took
+    pub load: Duration,
+    /// How long parsing the input took
+    pub parse: Duration,
+    /// The result of each part
+    pub parts: Vec<PartResult>
+}
+
+/// Loads, parses and solves one day, timing each part and checking it if there are answers to check against. Parts
+/// that are still running when the timeout is up, or haven't started by then, are reported as timed out. A day that
+/// panics is reported as [`DayError::Panicked`], so it doesn't take any other days down with it.
+pub fn run_day(solver: &dyn Solver, settings: &Settings) -> Result<Vec<PartResult>, DayError> {
+    run_day_timed(solver, settings).map(|run| run.parts)
+}
+
+/// The same as [`run_day`], but also says how long loading and parsing took, for --bench.
+pub fn run_day_timed(solver: &dyn Solver, settings: &Settings) -> Result<DayRun, DayError> {
+    panic::catch_unwind(AssertUnwindSafe(|| solve_day(solver, settings)))
+        .unwrap_or_else(|payload| Err(DayError::Panicked(panic_message(payload.as_ref()))))
+}
+
+/// The message a panic was raised with, if it has one.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| String::from("unknown panic"))
+}
+
+fn solve_day(solver: &dyn Solver, settings: &Settings) -> Result<DayRun, DayError> {
+    let day = solver.day();
+    let token = Token::new();
+    let _timer = settings.timeout.map(|timeout| cancel::cancel_after(&token, timeout));
+    let start = Instant::now();
+    let data = load_from(settings.source, day).map_err(DayError::Input)?;
+    let load = start.elapsed();
+    let start = Instant::now();
+    let parsed = solver.parse_input(data.as_str()).map_err(DayError::Parse)?;
+    let parse = start.elapsed();
+    for part in settings.parts.iter().copied() {
+        solver.check_part(data.as_str(), parsed.as_ref(), part).map_err(DayError::Parse)?;
+    }
+    let mut results: Vec<PartResult> = Vec::new();
+    for part in settings.parts.iter().copied() {
+        let start = Instant::now();
+        let (answer, usage) = memory::measure(|| cancel::run(&token, || solver.solve(parsed.as_ref(), part)));
+        let duration = start.elapsed();
+        let (answer, status) = match (answer, settings.answers) {
+            (Ok(answer), Some(answers)) => {
+                let status = answers.check(day, part, answer.as_str());
+                (answer, status)
+            }
+            (Ok(answer), None) => (answer, Status::Solved),
+            (Err(_), _) => (String::new(), Status::Timeout)
+        };
+        results.push(PartResult { day, part, answer, duration, status, memory: settings.memory.then_some(usage) });
+    }
+    Ok(DayRun { load, parse, parts: results })
+}
+
+/// Runs the days on up to `jobs` threads. Each day is handed to `report` on the calling thread, in the same order as
+/// days_to_run, as soon as it and all the days before it have finished.
+pub fn run_all(
+    days_to_run: &[u8],
+    days: &BTreeMap<u8, &'static dyn Solver>,
+    settings: &Settings,
+    jobs: usize,
+    mut report: impl FnMut(u8, Result<Vec<PartResult>, DayError>)
+) {
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, days_to_run.len().max(1)) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days_to_run.get(index) else {
+                    break;
+                };
+                let result = run_day(*days.get(day).unwrap(), settings);
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // Days finish in any order, so keep hold of them until everything before them has been reported
+        let mut finished: BTreeMap<usize, Result<Vec<PartResult>, DayError>> = BTreeMap::new();
+        let mut reported = 0;
+        for (index, result) in receiver {
+            finished.insert(index, result);
+            while let Some(result) = finished.remove(&reported) {
+                report(days_to_run[reported], result);
+                reported += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+    use rstest::rstest;
+    use std::collections::BTreeMap;
+    use crate::common::{InputSource, ParseError};
+    use crate::report::Status;
+    use crate::runner::{DayError, error_results, run_all, run_day, Settings};
+    use crate::solution::{Part, registry, Solution, Solver};
+    use crate::testing::TempDir;
+
+    // A day that parses anything, and then panics in part two
+    struct Panics;
+
+    impl Solution for Panics {
+        const DAY: u8 = 3;
+
+        type Parsed = ();
+        type PartOne = u8;
+        type PartTwo = u8;
+
+        fn parse(&self, _input: &str) -> Result<(), ParseError> {
+            Ok(())
+        }
+
+        fn part_one(&self, _parsed: &()) -> u8 {
+            1
+        }
+
+        fn part_two(&self, _parsed: &()) -> u8 {
+            panic!("part two fell over")
+        }
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(8)]
+    fn test_run_all_reports_in_order(#[case] jobs: usize) {
+        let dir = TempDir::new("runner");
+        fs::write(dir.join("day01.txt"), "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet").unwrap();
+        fs::write(dir.join("day06.txt"), "Time:      7  15   30\nDistance:  9  40  200").unwrap();
+        fs::write(dir.join("day15.txt"), "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7").unwrap();
+
+        let mut reported: Vec<(u8, Option<Vec<String>>)> = Vec::new();
+        let source = InputSource::DataDir(dir.to_path_buf());
+        let settings = Settings { source: &source, parts: &[Part::One, Part::Two], answers: None, memory: false, timeout: None };
+        run_all(&[15, 2, 6, 1], &registry(), &settings, jobs, |day, result| {
+            reported.push((day, result.ok().map(|parts| parts.into_iter().map(|p| p.answer).collect())));
+        });
+
+        let answers = |a: &str, b: &str| Some(vec![String::from(a), String::from(b)]);
+        assert_eq!(reported, vec![
+            (15, answers("1320", "145")),
+            (2, None),
+            (6, answers("288", "71503")),
+            (1, answers("142", "142")),
+        ]);
+    }
+
+    #[test]
+    fn test_error_results() {
+        let error = DayError::Parse(ParseError::new(2, 5, "Game two: 4 red", "expected a number, found \"two\"").with_day(2));
+        let results = error_results(2, &[Part::One, Part::Two], &error);
+        let status = Status::Error(String::from("line 2, column 5: expected a number, found \"two\""));
+        assert_eq!(results.iter().map(|r| (r.day, r.part, r.answer.as_str(), &r.status)).collect::<Vec<_>>(), vec![
+            (2, Part::One, "", &status),
+            (2, Part::Two, "", &status)
+        ]);
+    }
+
+    #[test]
+    fn test_run_all_reports_a_panicking_day() {
+        let mut days: BTreeMap<u8, &'static dyn Solver> = registry();
+        days.insert(3, &Panics);
+        let source = InputSource::Text(String::from("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7"));
+        let settings = Settings { source: &source, parts: &[Part::One, Part::Two], answers: None, memory: false, timeout: None };
+
+        let mut reported: Vec<(u8, Result<Vec<String>, String>)> = Vec::new();
+        run_all(&[3, 15], &days, &settings, 2, |day, result| {
+            reported.push((day, match result {
+                Ok(parts) => Ok(parts.into_iter().map(|p| p.answer).collect()),
+                Err(DayError::Panicked(message)) => Err(message),
+                Err(e) => panic!("expected day {} to panic, got {:?}", day, e)
+            }));
+        });
+        assert_eq!(reported, vec![
+            (3, Err(String::from("part two fell over"))),
+            (15, Ok(vec![String::from("1320"), String::from("145")]))
+        ]);
+    }
+
+    #[test]
+    fn test_run_day_timeout() {
+        // Part two walks hundreds of steps across an open garden, which takes much longer than part one's 64
+        let mut rows = vec![".".repeat(201); 201];
+        rows[100].replace_range(100..101, "S");
+        let dir = TempDir::new("runner-timeout");
+        fs::write(dir.join("day21.txt"), rows.join("\n")).unwrap();
+
+        let source = InputSource::DataDir(dir.to_path_buf());
+        let timeout = Some(Duration::from_secs(1));
+        let settings = Settings { source: &source, parts: &[Part::One, Part::Two], answers: None, memory: false, timeout };
+        let results = run_day(*registry().get(&21).unwrap(), &settings).unwrap();
+
+        assert_eq!((results[0].answer.as_str(), &results[0].status), ("4225", &Status::Solved));
+        assert_eq!((results[1].answer.as_str(), &results[1].status), ("", &Status::Timeout));
+        assert!(results[1].duration < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::Path;
+use aoc_2023::common::data_file_name;
+
+// A day that parses and solves, so everything that goes through every day in the registry keeps working until the
+// real solution is written
+fn template(day: u8) -> String {
+    TEMPLATE.replace("{day}", day.to_string().as_str()).replace("{nn}", format!("{:02}", day).as_str())
+}
+
+const TEMPLATE: &str = r#"//! Day {day}: TODO
+//!
+//! TODO: how the puzzle is solved.
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::Rng;
+use crate::common::ParseError;
+use crate::solution::Solution;
+
+/// TODO: what each part works out.
+pub struct Day{nn};
+
+impl Solution for Day{nn} {
+    const DAY: u8 = {day};
+
+    type Parsed = Vec<String>;
+    type PartOne = usize;
+    type PartTwo = usize;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>, ParseError> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part_one(&self, lines: &Vec<String>) -> usize {
+        day{nn}a(lines)
+    }
+
+    fn part_two(&self, lines: &Vec<String>) -> usize {
+        day{nn}b(lines)
+    }
+}
+
+fn day{nn}a(lines: &[String]) -> usize {
+    lines.len()
+}
+
+fn day{nn}b(lines: &[String]) -> usize {
+    lines.len()
+}
+
+/// Generates `size` lines. TODO: make inputs that keep to everything the real inputs promise.
+pub fn generate(rng: &mut StdRng, size: usize) -> String {
+    (0..size.max(1)).map(|_| rng.gen_range(0..100).to_string()).join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::day{nn}::{day{nn}a, day{nn}b};
+    use crate::examples::DAY{nn} as TEST_DATA;
+
+    #[test]
+    fn test_day{nn}a() {
+        let lines: Vec<String> = TEST_DATA.lines().map(String::from).collect();
+        assert_eq!(day{nn}a(&lines), 0);
+    }
+
+    #[test]
+    fn test_day{nn}b() {
+        let lines: Vec<String> = TEST_DATA.lines().map(String::from).collect();
+        assert_eq!(day{nn}b(&lines), 0);
+    }
+}
+"#;
+
+// Adds `line` among the lines that belong to a day, as told by `day_of`, keeping them in day order
+fn insert_line(source: &str, line: String, day: u8, day_of: impl Fn(&str) -> Option<u8>) -> Result<String, String> {
+    let mut lines: Vec<String> = source.lines().map(String::from).collect();
+    let days: Vec<(usize, u8)> = lines.iter().enumerate().filter_map(|(i, l)| day_of(l).map(|d| (i, d))).collect();
+    if days.iter().any(|(_, d)| *d == day) {
+        return Err(format!("day {} is already there", day));
+    }
+    let index = match days.iter().rev().find(|(_, d)| *d < day) {
+        Some((i, _)) => i + 1,
+        None => days.first().map(|(i, _)| *i).ok_or_else(|| String::from("there are no days to add it next to"))?
+    };
+    lines.insert(index, line);
+    Ok(lines.join("\n") + if source.ends_with('\n') { "\n" } else { "" })
+}
+
+// Adds the day to the `use crate::{day01, day02, ...};` list
+fn add_to_use_list(source: &str, day: u8) -> Result<String, String> {
+    let module = format!("day{:02}", day);
+    let start = source.find("use crate::{day").ok_or_else(|| String::from("there's no list of days to add it to"))?;
+    let end = start + source[start..].find("};").ok_or_else(|| String::from("the list of days isn't finished"))?;
+    let mut modules: Vec<&str> = source[start + "use crate::{".len()..end].split(", ").collect();
+    if modules.contains(&module.as_str()) {
+        return Err(format!("{} is already there", module));
+    }
+    modules.push(module.as_str());
+    modules.sort();
+    Ok(format!("{}use crate::{{{}{}", &source[..start], modules.join(", "), &source[end..]))
+}
+
+// The day a `pub mod dayNN;` line is for
+fn module_day(line: &str) -> Option<u8> {
+    line.strip_prefix("pub mod day")?.strip_suffix(';')?.parse().ok()
+}
+
+// The day a `&dayNN::DayNN,` line in the registry is for
+fn registry_day(line: &str) -> Option<u8> {
+    line.trim().strip_prefix("&day")?.split_once("::")?.0.parse().ok()
+}
+
+// The day a `NN => dayNN::generate,` line is for
+fn generator_day(line: &str) -> Option<u8> {
+    let (day, generator) = line.trim().split_once(" => ")?;
+    generator.ends_with("::generate,").then_some(())?;
+    day.parse().ok()
+}
+
+// The day an `Example { name: "dayNN", day: NN, ... },` entry is for
+fn example_day(line: &str) -> Option<u8> {
+    line.trim().strip_prefix("Example {")?.split_once("day: ")?.1.split_once(',')?.0.parse().ok()
+}
+
+// Adds an empty example for the day, whose answers are the ones the template gives, after the last day's constant
+fn add_example(source: &str, day: u8) -> Result<String, String> {
+    let line = format!(
+        "    Example {{ name: \"day{:02}\", day: {}, input: DAY{:02}, part_one: Some(\"0\"), part_two: Some(\"0\") }},",
+        day, day, day
+    );
+    let source = insert_line(source, line, day, example_day)?;
+    let tests = source.find("\n#[cfg(test)]").ok_or_else(|| String::from("there's nowhere to add the example"))?;
+    Ok(format!(
+        "{}\n/// The sample input from day {}'s puzzle.\npub const DAY{:02}: &str = \"\";\n{}",
+        &source[..tests], day, day, &source[tests..]
+    ))
+}
+
+// The file's contents once `change` has been made to them
+fn changed(path: &Path, change: impl FnOnce(&str) -> Result<String, String>) -> Result<String, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    change(source.as_str()).map_err(|e| format!("Could not add to {}: {}", path.display(), e))
+}
+
+// Writes the new day's module, adds it to the library, the registry, the generators and the examples, and makes an
+// empty data file if there isn't one. Only the day after the last one can be added, as the registry can't have gaps.
+// Returns the files it wrote.
+pub fn new_day(day: u8, root: &Path, data_dir: &Path) -> Result<Vec<String>, String> {
+    let src = root.join("src");
+    let module = src.join(format!("day{:02}.rs", day));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+
+    // Every change is worked out before anything is written, so a failure doesn't leave the day half added
+    let lib = changed(&src.join("lib.rs"), |s| {
+        let next = s.lines().filter_map(module_day).max().unwrap_or(0) + 1;
+        if day != next {
+            return Err(format!("day {} can't be added yet, the next day is {}", day, next));
+        }
+        insert_line(s, format!("pub mod day{:02};", day), day, module_day)
+    })?;
+    let solution = changed(&src.join("solution.rs"), |s| {
+        let line = format!("        &day{:02}::Day{:02},", day, day);
+        insert_line(add_to_use_list(s, day)?.as_str(), line, day, registry_day)
+    })?;
+    let generate = changed(&src.join("generate.rs"), |s| {
+        let line = format!("        {} => day{:02}::generate,", day, day);
+        insert_line(add_to_use_list(s, day)?.as_str(), line, day, generator_day)
+    })?;
+    let examples = changed(&src.join("examples.rs"), |s| add_example(s, day))?;
+
+    let mut written = Vec::new();
+    let files = [
+        (module, template(day)),
+        (src.join("lib.rs"), lib),
+        (src.join("solution.rs"), solution),
+        (src.join("generate.rs"), generate),
+        (src.join("examples.rs"), examples)
+    ];
+    for (path, contents) in files {
+        fs::write(&path, contents).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        written.push(path);
+    }
+    let data_file = data_dir.join(data_file_name(day));
+    if !data_file.exists() {
+        fs::create_dir_all(data_dir)
+            .and_then(|_| fs::write(&data_file, ""))
+            .map_err(|e| format!("Could not write {}: {}", data_file.display(), e))?;
+        written.push(data_file);
+    }
+    Ok(written.iter().map(|path| path.display().to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use rstest::rstest;
+    use aoc_2023::testing::TempDir;
+    use crate::scaffold::{add_example, add_to_use_list, example_day, generator_day, insert_line, module_day, new_day, registry_day, template};
+
+    #[rstest]
+    #[case("pub mod day07;", Some(7))]
+    #[case("pub mod day22;", Some(22))]
+    #[case("pub mod examples;", None)]
+    fn test_module_day(#[case] line: &str, #[case] expected: Option<u8>) {
+        assert_eq!(module_day(line), expected);
+    }
+
+    #[rstest]
+    #[case("        &day09::Day09,", Some(9))]
+    #[case("    let solvers: Vec<&'static dyn Solver> = vec![", None)]
+    fn test_registry_day(#[case] line: &str, #[case] expected: Option<u8>) {
+        assert_eq!(registry_day(line), expected);
+    }
+
+    #[rstest]
+    #[case("        12 => day12::generate,", Some(12))]
+    #[case("        _ => return None", None)]
+    fn test_generator_day(#[case] line: &str, #[case] expected: Option<u8>) {
+        assert_eq!(generator_day(line), expected);
+    }
+
+    #[rstest]
+    #[case("    Example { name: \"day08-2\", day: 8, input: DAY08_2, part_one: Some(\"6\"), part_two: Some(\"6\") },", Some(8))]
+    #[case("pub const EXAMPLES: &[Example] = &[", None)]
+    fn test_example_day(#[case] line: &str, #[case] expected: Option<u8>) {
+        assert_eq!(example_day(line), expected);
+    }
+
+    #[test]
+    fn test_add_example() {
+        let source = "pub const EXAMPLES: &[Example] = &[\n    Example { name: \"day01\", day: 1, input: DAY01, part_one: None, part_two: None },\n];\n\npub const DAY01: &str = \"1\";\n\n#[cfg(test)]\nmod tests {}\n";
+        let expected = "pub const EXAMPLES: &[Example] = &[\n    Example { name: \"day01\", day: 1, input: DAY01, part_one: None, part_two: None },\n    Example { name: \"day02\", day: 2, input: DAY02, part_one: Some(\"0\"), part_two: Some(\"0\") },\n];\n\npub const DAY01: &str = \"1\";\n\n/// The sample input from day 2's puzzle.\npub const DAY02: &str = \"\";\n\n#[cfg(test)]\nmod tests {}\n";
+        assert_eq!(add_example(source, 2), Ok(String::from(expected)));
+    }
+
+    #[rstest]
+    #[case(23, "pub mod day01;\npub mod day05;\npub mod day23;\npub mod examples;\n")]
+    #[case(3, "pub mod day01;\npub mod day03;\npub mod day05;\npub mod examples;\n")]
+    fn test_insert_line(#[case] day: u8, #[case] expected: &str) {
+        let source = "pub mod day01;\npub mod day05;\npub mod examples;\n";
+        assert_eq!(insert_line(source, format!("pub mod day{:02};", day), day, module_day), Ok(String::from(expected)));
+    }
+
+    #[test]
+    fn test_insert_line_already_there() {
+        let result = insert_line("pub mod day05;", String::from("pub mod day05;"), 5, module_day);
+        assert_eq!(result, Err(String::from("day 5 is already there")));
+    }
+
+    #[test]
+    fn test_add_to_use_list() {
+        let source = "use std::any::Any;\nuse crate::{day01, day02, day22};\n";
+        assert_eq!(add_to_use_list(source, 3), Ok(String::from("use std::any::Any;\nuse crate::{day01, day02, day03, day22};\n")));
+        assert_eq!(add_to_use_list(source, 22), Err(String::from("day22 is already there")));
+    }
+
+    #[test]
+    fn test_template() {
+        let module = template(23);
+        assert!(module.starts_with("//! Day 23: TODO"));
+        assert!(module.contains("pub struct Day23;\n\nimpl Solution for Day23 {\n    const DAY: u8 = 23;"));
+        assert!(!module.contains("{nn}") && !module.contains("{day}"));
+    }
+
+    // Works on copies of the real files, so it fails if they change in a way new_day doesn't understand
+    #[test]
+    fn test_new_day() {
+        let root = TempDir::new("new-day");
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        for file in ["lib.rs", "solution.rs", "generate.rs", "examples.rs"] {
+            fs::copy(Path::new(env!("CARGO_MANIFEST_DIR")).join("src").join(file), src.join(file)).unwrap();
+        }
+        let too_far = new_day(24, &root, &root.join("data"));
+        let written = new_day(23, &root, &root.join("data"));
+        let read = |name: &str| fs::read_to_string(src.join(name)).unwrap();
+        let (lib, solution, generate, examples) = (read("lib.rs"), read("solution.rs"), read("generate.rs"), read("examples.rs"));
+        let data = fs::read_to_string(root.join("data").join("day23.txt"));
+        let again = new_day(23, &root, &root.join("data"));
+
+        assert!(too_far.unwrap_err().ends_with("day 24 can't be added yet, the next day is 23"));
+        assert_eq!(written.map(|files| files.len()), Ok(6));
+        assert!(lib.contains("pub mod day22;\npub mod day23;\n"), "{}", lib);
+        assert!(solution.contains("day22, day23};"), "{}", solution);
+        assert!(solution.contains("        &day22::Day22,\n        &day23::Day23,\n"), "{}", solution);
+        assert!(generate.contains("day22, day23};"), "{}", generate);
+        assert!(generate.contains("        22 => day22::generate,\n        23 => day23::generate,\n"), "{}", generate);
+        assert!(examples.contains("\n    Example { name: \"day23\", day: 23, input: DAY23, part_one: Some(\"0\"), part_two: Some(\"0\") },\n];"), "{}", examples);
+        assert!(examples.contains("\n/// The sample input from day 23's puzzle.\npub const DAY23: &str = \"\";\n\n#[cfg(test)]"), "{}", examples);
+        assert_eq!(data.unwrap(), "");
+        assert!(again.unwrap_err().ends_with("day23.rs already exists"));
+    }
+}
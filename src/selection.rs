@@ -0,0 +1,99 @@
+use std::str::FromStr;
+use aoc_2023::solution::Part;
+
+// One --day argument, which can be a single day, a range or a comma separated list of both, like 1-5,9,20-22.
+#[derive(PartialEq, Debug)]
+pub struct DayList(pub Vec<u8>);
+
+impl FromStr for DayList {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days: Vec<u8> = Vec::new();
+        for entry in s.split(',').map(|x| x.trim()) {
+            match entry.split_once('-') {
+                Some((start, end)) => {
+                    let start = parse_day(start)?;
+                    let end = parse_day(end)?;
+                    if start > end {
+                        return Err(format!("{} is not a valid range, the first day must come first", entry));
+                    }
+                    days.extend(start..=end);
+                }
+                None => days.push(parse_day(entry)?)
+            }
+        }
+        Ok(DayList(days))
+    }
+}
+
+fn parse_day(day: &str) -> Result<u8, String> {
+    match day.trim() {
+        "" => Err(String::from("a day is missing from the list")),
+        d => d.parse::<u8>().map_err(|_| format!("{} is not a day", d))
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PartSelection {
+    A,
+    B,
+    Both
+}
+
+impl FromStr for PartSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "1" => Ok(PartSelection::A),
+            "b" | "2" => Ok(PartSelection::B),
+            "both" => Ok(PartSelection::Both),
+            x => Err(format!("{} is not a part, use a, b or both", x))
+        }
+    }
+}
+
+impl PartSelection {
+    pub fn parts(&self) -> Vec<Part> {
+        match self {
+            PartSelection::A => vec![Part::One],
+            PartSelection::B => vec![Part::Two],
+            PartSelection::Both => vec![Part::One, Part::Two]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::selection::{DayList, PartSelection};
+    use aoc_2023::solution::Part;
+
+    #[rstest]
+    #[case("7", Ok(DayList(vec![7])))]
+    #[case("1-5,9,20-22", Ok(DayList(vec![1, 2, 3, 4, 5, 9, 20, 21, 22])))]
+    #[case("3, 1", Ok(DayList(vec![3, 1])))]
+    #[case("4-4", Ok(DayList(vec![4])))]
+    #[case("5-1", Err(String::from("5-1 is not a valid range, the first day must come first")))]
+    #[case("1-x", Err(String::from("x is not a day")))]
+    #[case("1,,2", Err(String::from("a day is missing from the list")))]
+    #[case("300", Err(String::from("300 is not a day")))]
+    fn test_day_list(#[case] input: &str, #[case] expected: Result<DayList, String>) {
+        assert_eq!(input.parse::<DayList>(), expected);
+    }
+
+    #[rstest]
+    #[case("a", vec![Part::One])]
+    #[case("b", vec![Part::Two])]
+    #[case("2", vec![Part::Two])]
+    #[case("both", vec![Part::One, Part::Two])]
+    fn test_part_selection(#[case] input: &str, #[case] expected: Vec<Part>) {
+        assert_eq!(input.parse::<PartSelection>().unwrap().parts(), expected);
+    }
+
+    #[test]
+    fn test_part_selection_error() {
+        assert_eq!("c".parse::<PartSelection>(), Err(String::from("c is not a part, use a, b or both")));
+    }
+}
@@ -0,0 +1,158 @@
+//! The traits each day implements, and the registry of every day.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use crate::common::ParseError;
+use crate::{day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day20, day21, day22};
+
+/// Each day implements this. Parsing is kept separate from the two parts so that the runner
+/// can call (and time) each step on its own, and get the answers back rather than printing them.
+pub trait Solution {
+    /// The day of the puzzle, from 1 to 25.
+    const DAY: u8;
+
+    /// What the input is parsed into, which both parts work from.
+    type Parsed: 'static;
+    /// The answer to part one.
+    type PartOne: Display;
+    /// The answer to part two.
+    type PartTwo: Display;
+
+    /// Reads the puzzle input, or says where it couldn't make sense of it.
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError>;
+    /// Checks that `input`, which parsed as `parsed`, has whatever only `part` needs. Parse checks everything both parts
+    /// need, but some of the puzzles' examples leave out what the other part needs, so they can only be run on one.
+    /// There's nothing else to check by default.
+    fn check(&self, _input: &str, _parsed: &Self::Parsed, _part: Part) -> Result<(), ParseError> {
+        Ok(())
+    }
+    /// Solves part one.
+    fn part_one(&self, parsed: &Self::Parsed) -> Self::PartOne;
+    /// Solves part two.
+    fn part_two(&self, parsed: &Self::Parsed) -> Self::PartTwo;
+}
+
+/// One of the two parts of a day's puzzle.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum Part {
+    /// The first part, which is all there is to begin with.
+    One,
+    /// The second part, which is unlocked by solving the first.
+    Two
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2")
+        }
+    }
+}
+
+/// Solution has associated types, so it can't be put in a map as is. Solver erases them so that
+/// every day can sit in the registry together -- the parsed data is boxed up and the answers are
+/// returned as strings.
+pub trait Solver: Sync {
+    /// The day of the puzzle.
+    fn day(&self) -> u8;
+    /// Parses the input, boxed up, with the day filled in on any error.
+    fn parse_input(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    /// Checks that the input parse_input was given has what the part needs, with the day filled in on any error.
+    fn check_part(&self, input: &str, parsed: &dyn Any, part: Part) -> Result<(), ParseError>;
+    /// Solves a part from what parse_input gave, and gives the answer as a string. Panics if `parsed` came from
+    /// another day.
+    fn solve(&self, parsed: &dyn Any, part: Part) -> String;
+}
+
+impl<T: Solution + Sync> Solver for T {
+    fn day(&self) -> u8 {
+        T::DAY
+    }
+
+    fn parse_input(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        match self.parse(input) {
+            Ok(parsed) => Ok(Box::new(parsed)),
+            Err(e) => Err(e.with_day(T::DAY))
+        }
+    }
+
+    fn check_part(&self, input: &str, parsed: &dyn Any, part: Part) -> Result<(), ParseError> {
+        let data = parsed.downcast_ref::<T::Parsed>().expect("Parsed data is not for this day");
+        self.check(input, data, part).map_err(|e| e.with_day(T::DAY))
+    }
+
+    fn solve(&self, parsed: &dyn Any, part: Part) -> String {
+        // The only way to get the parsed data is from parse_input above, so this is always the right type.
+        let data = parsed.downcast_ref::<T::Parsed>().expect("Parsed data is not for this day");
+        match part {
+            Part::One => self.part_one(data).to_string(),
+            Part::Two => self.part_two(data).to_string()
+        }
+    }
+}
+
+/// Every day's solver, keyed by day.
+// Add new days here
+pub fn registry() -> BTreeMap<u8, &'static dyn Solver> {
+    let solvers: Vec<&'static dyn Solver> = vec![
+        &day01::Day01,
+        &day02::Day02,
+        &day03::Day03,
+        &day04::Day04,
+        &day05::Day05,
+        &day06::Day06,
+        &day07::Day07,
+        &day08::Day08,
+        &day09::Day09,
+        &day10::Day10,
+        &day11::Day11,
+        &day12::Day12,
+        &day13::Day13,
+        &day14::Day14,
+        &day15::Day15,
+        &day16::Day16,
+        &day17::Day17,
+        &day18::Day18,
+        &day19::Day19,
+        &day20::Day20,
+        &day21::Day21,
+        &day22::Day22,
+    ];
+    solvers.into_iter().map(|s| (s.day(), s)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::{Part, registry};
+
+    #[test]
+    fn test_registry_has_every_day() {
+        // New days are added on the end with new-day, so there are never any gaps
+        let days: Vec<u8> = registry().keys().copied().collect();
+        assert!(days.len() >= 22);
+        assert_eq!(days, (1..=days.len() as u8).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_solve_through_registry() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green\n\
+        Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue\n\
+        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red\n\
+        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red\n\
+        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let registry = registry();
+        let solver = registry.get(&2).unwrap();
+        let parsed = solver.parse_input(input).unwrap();
+        assert_eq!(solver.solve(parsed.as_ref(), Part::One), "8");
+        assert_eq!(solver.solve(parsed.as_ref(), Part::Two), "2286");
+    }
+
+    #[test]
+    fn test_parse_error_has_day() {
+        let registry = registry();
+        let error = registry.get(&2).unwrap().parse_input("Game 1: 3 blue\nGame two: 4 red").err().unwrap();
+        assert_eq!((error.day, error.line), (2, 2));
+    }
+}
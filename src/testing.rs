@@ -0,0 +1,39 @@
+//! Helpers for the tests. It's in the library, rather than behind `#[cfg(test)]`, so the binary's tests can use it too,
+//! but it's not part of the API.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Tells apart the directories made by tests running at the same time in the same process
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A new, empty directory under the system's temporary directory, which is removed along with everything in it when
+/// dropped, so a failing test doesn't leave it behind.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Makes the directory, with `name` in its name to tell which test it's for.
+    pub fn new(name: &str) -> TempDir {
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("aoc-2023-{}-{}-{}", name, std::process::id(), count));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use aoc_2023::common::InputSource;
+
+// How often the input files are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Remembers when each day's input was last modified, so we can tell which days need running again. A missing file
+// counts as a modification time of its own, so creating or removing a file is a change too.
+pub struct Watcher {
+    files: BTreeMap<u8, (PathBuf, Option<SystemTime>)>
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl Watcher {
+    // None if the input doesn't come from files, as there's nothing to watch
+    pub fn new(days: &[u8], source: &InputSource) -> Option<Watcher> {
+        let files = days.iter()
+            .map(|day| source.path(*day).map(|path| {
+                let modified = modified(&path);
+                (*day, (path, modified))
+            }))
+            .collect::<Option<BTreeMap<_, _>>>()?;
+        Some(Watcher { files })
+    }
+
+    // The days whose input has changed since the last time this was called
+    pub fn changed(&mut self) -> Vec<u8> {
+        let mut changed = Vec::new();
+        for (day, (path, last_modified)) in self.files.iter_mut() {
+            let modified = modified(path);
+            if modified != *last_modified {
+                *last_modified = modified;
+                changed.push(*day);
+            }
+        }
+        changed
+    }
+
+    // Runs the days again whenever their input changes. This only stops when the process is killed.
+    pub fn watch(mut self, mut run: impl FnMut(&[u8])) -> ! {
+        eprintln!("Watching the inputs for changes, press Ctrl-C to stop");
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let changed = self.changed();
+            if !changed.is_empty() {
+                // A half edited input can easily make a day panic, which has already been reported by the time it
+                // gets here, so keep watching for the fix
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| run(&changed)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+    use aoc_2023::common::InputSource;
+    use aoc_2023::testing::TempDir;
+    use crate::watch::Watcher;
+
+    // Filesystems don't all record modification times precisely, so each change moves the time on by a whole second
+    fn touch(path: &PathBuf, seconds: u64) {
+        File::options().write(true).open(path).unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + seconds)).unwrap();
+    }
+
+    #[test]
+    fn test_changed() {
+        let dir = TempDir::new("watch");
+        fs::write(dir.join("day01.txt"), "1abc2").unwrap();
+        fs::write(dir.join("day02.txt"), "Game 1: 3 blue").unwrap();
+
+        let mut watcher = Watcher::new(&[1, 2, 3], &InputSource::DataDir(dir.to_path_buf())).unwrap();
+        let nothing = watcher.changed();
+        touch(&dir.join("day02.txt"), 1);
+        let modified = watcher.changed();
+        let again = watcher.changed();
+        fs::write(dir.join("day03.txt"), "467..114..").unwrap();
+        fs::remove_file(dir.join("day01.txt")).unwrap();
+        let created_and_removed = watcher.changed();
+
+        assert_eq!(nothing, Vec::<u8>::new());
+        assert_eq!(modified, vec![2]);
+        assert_eq!(again, Vec::<u8>::new());
+        assert_eq!(created_and_removed, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_nothing_to_watch() {
+        assert!(Watcher::new(&[1], &InputSource::Stdin).is_none());
+    }
+}